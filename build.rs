@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::Path;
+
+/// The data files built into the editor, which `--version` and the About view report a version
+/// for.
+const DATA_FILES: [&str; 18] = [
+    "talents.txt",
+    "blueprints.txt",
+    "workshop_items.txt",
+    "prospects.txt",
+    "prospect_tiers.txt",
+    "workshop_costs.txt",
+    "workshop_exotic_costs.txt",
+    "items.txt",
+    "levels.txt",
+    "display_names.txt",
+    "talent_trees.txt",
+    "talent_prerequisites.txt",
+    "flags.txt",
+    "dlc.txt",
+    "item_categories.txt",
+    "blueprint_levels.txt",
+    "cosmetics.txt",
+    "talent_points.txt",
+];
+
+const ICON: &str = "images/icon.ico";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-env=ICARUS_DATA_VERSION={}", data_version());
+    embed_windows_resources();
+}
+
+/// A short fingerprint of the built in data files, so two builds can be told apart by the game data
+/// they carry even when the editor's version is the same. FNV-1a, as it never changes between Rust
+/// versions the way the standard library's hasher may.
+fn data_version() -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for file in DATA_FILES {
+        let path = Path::new("src").join(file);
+        println!("cargo:rerun-if-changed={}", path.display());
+        // Line endings depend on how git checked the files out, so they're left out
+        let text = fs::read_to_string(&path).unwrap_or_default().replace('\r', "");
+        for byte in file.bytes().chain(text.bytes()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)[..8].to_string()
+}
+
+/// Gives the Windows executable its icon and a version resource, so Explorer shows both.
+#[cfg(windows)]
+fn embed_windows_resources() {
+    println!("cargo:rerun-if-changed={}", ICON);
+    if std::env::var("CARGO_CFG_TARGET_OS").map(|x| x != "windows").unwrap_or(true) {
+        return;
+    }
+    let mut resource = winres::WindowsResource::new();
+    resource.set_icon(ICON);
+    resource.set("FileDescription", "Icarus Offline Character Editor");
+    resource.set("ProductName", "Icarus Offline Character Editor");
+    if let Err(e) = resource.compile() {
+        println!("cargo:warning=Unable to embed the icon and version information: {}", e);
+    }
+}
+
+#[cfg(not(windows))]
+fn embed_windows_resources() {
+    println!("cargo:rerun-if-changed={}", ICON);
+}
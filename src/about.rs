@@ -0,0 +1,29 @@
+use crate::{game_data, UiState, BLUEPRINTS, PROSPECTS, TALENTS, WORKSHOP_ITEMS};
+
+/// Where problems with the editor get reported.
+pub const ISSUES_URL: &str = "https://github.com/ipsi/icarus-offline-character-editor/issues";
+
+/// A line for each of the main data files: how many rows it has and where it was loaded from.
+pub fn data_file_lines() -> Vec<String> {
+    let files = [
+        (game_data::TALENTS_FILE, TALENTS.len()),
+        (game_data::BLUEPRINTS_FILE, BLUEPRINTS.len()),
+        (game_data::PROSPECTS_FILE, PROSPECTS.len()),
+        (game_data::WORKSHOP_ITEMS_FILE, WORKSHOP_ITEMS.len()),
+    ];
+    let overriding = game_data::overriding_files();
+    files.iter().map(|(file, rows)| {
+        let source = if overriding.iter().any(|x| x == file) { "from the data folder" } else { "built in" };
+        format!("{}: {} rows, {}", file, rows, source)
+    }).collect()
+}
+
+impl UiState {
+    /// When the newest backup of this save was made.
+    pub fn last_backup(&self) -> String {
+        match self.backups.front() {
+            Some(backup) => backup.time().map(|x| x.format("%Y-%m-%d %H:%M:%S").to_string()).unwrap_or_else(|| backup.name.clone()),
+            None => "Never".to_string(),
+        }
+    }
+}
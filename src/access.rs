@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::fs::{self, Permissions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use druid::Data;
+use druid::im::vector::Vector;
+use icarus_save::{CHARACTERS_FILE, PROFILE_FILE};
+use icarus_save::prospect::PROSPECTS_DIR;
+
+use crate::{guard, save_locations};
+
+/// Folders in the save folder with a file per character or prospect.
+const SAVE_SUBFOLDERS: [&str; 3] = ["Inventory", "Loadout", PROSPECTS_DIR];
+
+/// A common reason a save folder can't be read or written on Windows, spotted after it fails to
+/// open.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub enum AccessProblem {
+    /// The folder is inside OneDrive, which can leave files online only or lock them while syncing
+    OneDrive,
+    /// The folder is on a network share, as when AppData is redirected by company policy
+    NetworkFolder,
+    /// The folder is where the Microsoft Store installed the game, rather than a save folder
+    PackagedInstall,
+    /// The folder is a Game Pass save, in the game's package folder
+    PackagedStorage,
+    /// The folder can't be listed at all
+    FolderDenied,
+    /// Files marked read only, by name
+    ReadOnly(Vector<String>),
+    /// Files another program has open, by name
+    Locked(Vector<String>),
+}
+
+impl AccessProblem {
+    pub fn title(&self) -> String {
+        match self {
+            AccessProblem::OneDrive => "The save folder is in OneDrive".to_string(),
+            AccessProblem::NetworkFolder => "The save folder is on a network drive".to_string(),
+            AccessProblem::PackagedInstall => "This is the game's install folder, not its save folder".to_string(),
+            AccessProblem::PackagedStorage => "This is a Game Pass save".to_string(),
+            AccessProblem::FolderDenied => "Windows won't let the editor into the save folder".to_string(),
+            AccessProblem::ReadOnly(files) => format!("Read only: {}", files.iter().cloned().collect::<Vec<String>>().join(", ")),
+            AccessProblem::Locked(files) => format!("In use by another program: {}", files.iter().cloned().collect::<Vec<String>>().join(", ")),
+        }
+    }
+
+    /// What the user can do about it.
+    pub fn advice(&self) -> &'static str {
+        match self {
+            AccessProblem::OneDrive => "OneDrive may only have a placeholder for the files until they're opened, or lock them while it syncs. \
+                Right click the save folder in Explorer and choose \"Always keep on this device\", or pause syncing, then retry.",
+            AccessProblem::NetworkFolder => "Your AppData folder seems to be redirected to a network share, which may be slow, offline or read only. \
+                Copy the save folder somewhere local and open that with \"Choose Save Folder...\", or ask whoever manages the computer.",
+            AccessProblem::PackagedInstall => "Windows keeps the WindowsApps folder locked, even from administrators, and the game doesn't save there. \
+                The Game Pass version's saves are under %LOCALAPPDATA%\\Packages, in the folder named after the game, then LocalCache\\Local\\Icarus\\Saved\\PlayerData.",
+            AccessProblem::PackagedStorage => "The Xbox app syncs Game Pass saves and can hold the files while it does. \
+                Close Icarus and the Xbox app, then retry. If Windows still refuses, an update may have reset the package folder's permissions - try \"Make Files Writable\" or running as administrator.",
+            AccessProblem::FolderDenied => "The folder's permissions don't allow your account in. \
+                Try running the editor as administrator, or fix the folder's permissions under Properties > Security.",
+            AccessProblem::ReadOnly(_) => "Read only files can be opened but never saved. \
+                \"Make Files Writable\" clears the read only attribute, or clear it yourself under the files' Properties.",
+            AccessProblem::Locked(_) => "Icarus, Steam or an antivirus scanner may be holding the files. \
+                Close the game, wait for any scan to finish (or add the save folder to the antivirus's exclusions), then retry.",
+        }
+    }
+}
+
+/// The save files in `save_dir` that exist.
+fn save_files(save_dir: &Path) -> Vec<PathBuf> {
+    let mut files = vec![save_dir.join(PROFILE_FILE), save_dir.join(CHARACTERS_FILE)];
+    for folder in SAVE_SUBFOLDERS {
+        if let Ok(entries) = save_dir.join(folder).read_dir() {
+            files.extend(entries.filter_map(|x| x.ok()).map(|x| x.path()).filter(|x| x.is_file()));
+        }
+    }
+    files.retain(|x| x.exists());
+
+    files
+}
+
+fn file_names(files: &[PathBuf]) -> Vector<String> {
+    files.iter().map(|x| x.file_name().unwrap_or_default().to_string_lossy().to_string()).collect()
+}
+
+fn is_read_only(path: &Path) -> bool {
+    fs::metadata(path).map(|x| x.permissions().readonly()).unwrap_or(false)
+}
+
+/// Looks for the usual reasons `save_dir` couldn't be opened.
+pub fn diagnose(save_dir: &Path) -> Vector<AccessProblem> {
+    let mut problems = Vector::new();
+    if save_dir.as_os_str().is_empty() {
+        return problems;
+    }
+
+    if save_dir.components().any(|x| x.as_os_str().to_string_lossy().to_lowercase().starts_with("onedrive")) {
+        problems.push_back(AccessProblem::OneDrive);
+    }
+    if save_locations::is_packaged_install(save_dir) {
+        problems.push_back(AccessProblem::PackagedInstall);
+    } else if save_locations::is_packaged_storage(save_dir) {
+        problems.push_back(AccessProblem::PackagedStorage);
+    }
+    let dir = save_dir.to_string_lossy();
+    // `\\?\` is only a long path prefix for a local drive
+    if (dir.starts_with(r"\\") && !dir.starts_with(r"\\?\")) || dir.starts_with(r"\\?\UNC\") {
+        problems.push_back(AccessProblem::NetworkFolder);
+    }
+    if let Err(e) = save_dir.read_dir() {
+        if e.kind() == ErrorKind::PermissionDenied {
+            problems.push_back(AccessProblem::FolderDenied);
+        }
+        return problems;
+    }
+
+    let (read_only, writable): (Vec<PathBuf>, Vec<PathBuf>) = save_files(save_dir).into_iter().partition(|x| is_read_only(x));
+    if !read_only.is_empty() {
+        problems.push_back(AccessProblem::ReadOnly(file_names(&read_only)));
+    }
+    let locked = guard::locked_files(&writable);
+    if !locked.is_empty() {
+        problems.push_back(AccessProblem::Locked(file_names(&locked)));
+    }
+
+    problems
+}
+
+#[cfg(windows)]
+fn allow_writing(permissions: &mut Permissions) {
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+}
+
+/// Lets the owner write to the file, without opening it to everyone else.
+#[cfg(not(windows))]
+fn allow_writing(permissions: &mut Permissions) {
+    use std::os::unix::fs::PermissionsExt;
+    permissions.set_mode(permissions.mode() | 0o200);
+}
+
+/// Clears the read only attribute from every save file in `save_dir`.
+pub fn make_writable(save_dir: &Path) -> Result<(), Box<dyn Error>> {
+    for path in save_files(save_dir).iter().filter(|x| is_read_only(x)) {
+        let mut permissions = fs::metadata(path)?.permissions();
+        allow_writing(&mut permissions);
+        fs::set_permissions(path, permissions)?;
+    }
+
+    Ok(())
+}
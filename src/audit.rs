@@ -0,0 +1,200 @@
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use druid::Data;
+use druid::im::vector::Vector;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::backup;
+use crate::history::EditSnapshot;
+use crate::{Character, UiState};
+
+const AUDIT_LOG_FILE: &str = "audit.log";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// Fields that identify an entry in a list, so a change is logged against the talent or item rather
+/// than its position.
+const ROW_KEYS: [&str; 3] = ["RowName", "MetaRow", "MissionRow"];
+
+/// One value the editor changed.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct AuditEntry {
+    pub time: String,
+    pub field: String,
+    pub old: String,
+    pub new: String,
+}
+
+impl AuditEntry {
+    pub fn now(field: &str, old: &str, new: &str) -> AuditEntry {
+        AuditEntry {
+            time: Local::now().format(TIMESTAMP_FORMAT).to_string(),
+            field: field.to_string(),
+            old: old.to_string(),
+            new: new.to_string(),
+        }
+    }
+
+    fn to_line(&self) -> String {
+        [&self.time, &self.field, &self.old, &self.new].iter()
+            .map(|x| x.replace(['\t', '\r', '\n'], " "))
+            .collect::<Vec<String>>()
+            .join("\t")
+    }
+
+    fn from_line(line: &str) -> Option<AuditEntry> {
+        let mut parts = line.splitn(4, '\t');
+        Some(AuditEntry {
+            time: parts.next()?.to_string(),
+            field: parts.next()?.to_string(),
+            old: parts.next()?.to_string(),
+            new: parts.next()?.to_string(),
+        })
+    }
+}
+
+/// The change log sits with the backups, so it goes wherever they do.
+pub fn log_path(save_dir: &Path) -> PathBuf {
+    backup::backup_root(save_dir).join(AUDIT_LOG_FILE)
+}
+
+/// Adds `entries` to the end of the save's change log.
+pub fn append(save_dir: &Path, entries: &Vector<AuditEntry>) -> Result<(), Box<dyn Error>> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let path = log_path(save_dir);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for entry in entries {
+        writeln!(file, "{}", entry.to_line())?;
+    }
+
+    Ok(())
+}
+
+/// Everything in the save's change log, oldest first. Lines that can't be read are left out.
+pub fn read_log(save_dir: &Path) -> Result<Vector<AuditEntry>, Box<dyn Error>> {
+    let path = log_path(save_dir);
+    if !path.exists() {
+        return Ok(Vector::new());
+    }
+
+    Ok(fs::read_to_string(path)?.lines().filter_map(AuditEntry::from_line).collect())
+}
+
+fn show(value: &Value) -> String {
+    match value {
+        Value::Null => "(none)".to_string(),
+        Value::String(text) => text.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// The name of a list entry, if every entry in the list has one.
+fn row_key(value: &Value) -> Option<String> {
+    ROW_KEYS.iter().find_map(|key| value.get(key)).and_then(|x| x.as_str()).map(|x| x.to_string())
+}
+
+fn keyed(list: &[Value]) -> Option<Vec<(String, &Value)>> {
+    list.iter().map(|x| Some((row_key(x)?, x))).collect()
+}
+
+/// Adds a `(field, old, new)` to `changes` for each value that differs between `before` and `after`.
+fn diff(field: &str, before: &Value, after: &Value, changes: &mut Vec<(String, String, String)>) {
+    match (before, after) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                diff(&format!("{}.{}", field, key), value, b.get(key).unwrap_or(&Value::Null), changes);
+            }
+            for (key, value) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                diff(&format!("{}.{}", field, key), &Value::Null, value, changes);
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => match (keyed(a), keyed(b)) {
+            (Some(a), Some(b)) => {
+                for (key, value) in &a {
+                    let other = b.iter().find(|(x, _)| x == key).map(|(_, x)| *x).unwrap_or(&Value::Null);
+                    diff(&format!("{}[{}]", field, key), value, other, changes);
+                }
+                for (key, value) in b.iter().filter(|(key, _)| !a.iter().any(|(x, _)| x == key)) {
+                    diff(&format!("{}[{}]", field, key), &Value::Null, value, changes);
+                }
+            }
+            _ => {
+                for idx in 0..a.len().max(b.len()) {
+                    diff(&format!("{}[{}]", field, idx), a.get(idx).unwrap_or(&Value::Null), b.get(idx).unwrap_or(&Value::Null), changes);
+                }
+            }
+        },
+        _ if before != after => changes.push((field.to_string(), show(before), show(after))),
+        _ => {}
+    }
+}
+
+fn diff_serialized<T: Serialize>(field: &str, before: &T, after: &T, changes: &mut Vec<(String, String, String)>) {
+    match (serde_json::to_value(before), serde_json::to_value(after)) {
+        (Ok(before), Ok(after)) => diff(field, &before, &after, changes),
+        _ => changes.push((field.to_string(), "(unreadable)".to_string(), "(unreadable)".to_string())),
+    }
+}
+
+/// Whether `after` is just `before` in a different order.
+fn is_reordering(before: &Vector<Character>, after: &Vector<Character>) -> bool {
+    !before.same(after) && after.iter().all(|b| before.iter().any(|a| a.same(b)))
+}
+
+/// Every value that differs between two snapshots of the save. Only the parts that changed are
+/// serialized, as this runs after every edit.
+fn diff_snapshots(before: &EditSnapshot, after: &EditSnapshot) -> Vec<(String, String, String)> {
+    let mut changes = Vec::new();
+    if !before.profile.same(&after.profile) {
+        diff_serialized("Profile", &before.profile, &after.profile, &mut changes);
+    }
+    let names = |characters: &Vector<Character>| characters.iter().map(|c| c.character_name.clone()).collect::<Vec<String>>().join(", ");
+    if before.characters.len() != after.characters.len() {
+        changes.push(("Characters".to_string(), names(&before.characters), names(&after.characters)));
+    } else if is_reordering(&before.characters, &after.characters) {
+        changes.push(("Character order".to_string(), names(&before.characters), names(&after.characters)));
+    } else {
+        for (a, b) in before.characters.iter().zip(after.characters.iter()).filter(|(a, b)| !a.same(b)) {
+            let field = format!("Character \"{}\"", a.character_name);
+            diff_serialized(&field, a, b, &mut changes);
+            if !a.inventory.same(&b.inventory) {
+                diff_serialized(&format!("{} inventory", field), &a.inventory, &b.inventory, &mut changes);
+            }
+            if !a.loadout.same(&b.loadout) {
+                diff_serialized(&format!("{} loadout", field), &a.loadout, &b.loadout, &mut changes);
+            }
+        }
+    }
+    for (a, b) in before.prospects.iter().zip(after.prospects.iter()).filter(|(a, b)| !a.same(b)) {
+        diff_serialized(&format!("Prospect {}", a.file_name), &a.data, &b.data, &mut changes);
+    }
+
+    changes
+}
+
+impl UiState {
+    /// Notes every value that's changed since `before` in the change log, to be written out with the
+    /// next save.
+    pub fn record_changes(&mut self, before: &EditSnapshot) {
+        for (field, old, new) in diff_snapshots(before, &self.snapshot()) {
+            self.audit_pending.push_back(AuditEntry::now(&field, &old, &new));
+        }
+    }
+
+    /// Opens the change log viewer, with the changes not saved yet at the end.
+    pub fn show_audit_log(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut entries = read_log(&self.save_dir)?;
+        entries.append(self.audit_pending.clone());
+        self.audit_log = Some(entries);
+
+        Ok(())
+    }
+}
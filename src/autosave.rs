@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use druid::widget::Controller;
+use druid::{Data, Env, Event, EventCtx, TimerToken, Widget};
+
+use crate::guard;
+use crate::history::EditSnapshot;
+use crate::UiState;
+
+/// How long after the last change an autosave happens, unless the user picks something else.
+pub const DEFAULT_AUTOSAVE_DELAY: u64 = 30;
+
+impl UiState {
+    /// Whether there's anything to autosave, and nothing an autosave could get in the way of: a
+    /// question for the user, a save that's changed on disk, or work still going on.
+    fn can_autosave(&self) -> bool {
+        self.config.autosave && !self.config.safe_mode && self.error.is_none() && self.prompt.is_none()
+            && !self.external_change && !self.is_busy() && self.is_dirty()
+    }
+
+    /// Saves like the Save button does, backing up first, but never asks anything. If a save file is
+    /// in use or Icarus is running it gives up until the next try instead.
+    fn autosave(&mut self) {
+        let files = self.save_files();
+        self.run_in_background("autosave", "Checking save files", move || Ok(guard::locked_files(&files).is_empty() && !guard::game_running()), |t, can_save| {
+            if can_save {
+                t.save_in_background(t.changed_files(), |t| t.status = "Autosaved".to_string());
+            } else {
+                t.status = "Not autosaved, as Icarus is running or a save file is in use".to_string();
+            }
+
+            Ok(())
+        });
+    }
+}
+
+/// Autosaves the configured number of seconds after the last change, trying again as often while
+/// it can't. Meant to wrap the root widget.
+pub struct AutosaveController {
+    timer: TimerToken,
+    /// The edits as of the last event, to spot changes by
+    seen: Option<EditSnapshot>,
+    /// Whether autosave was on as of the last event, so turning it on starts the wait too
+    enabled: bool,
+}
+
+impl Default for AutosaveController {
+    fn default() -> Self {
+        AutosaveController { timer: TimerToken::INVALID, seen: None, enabled: false }
+    }
+}
+
+impl AutosaveController {
+    fn delay(data: &UiState) -> Duration {
+        Duration::from_secs(data.config.autosave_delay.max(1))
+    }
+}
+
+impl<W: Widget<UiState>> Controller<UiState, W> for AutosaveController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        if let Event::Timer(token) = event {
+            if *token == self.timer {
+                self.timer = TimerToken::INVALID;
+                if data.config.autosave && data.is_dirty() {
+                    if data.can_autosave() {
+                        data.autosave();
+                    }
+                    self.timer = ctx.request_timer(AutosaveController::delay(data));
+                }
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env);
+
+        // Edits made from commands only show up here on the next event, which is soon enough
+        let snapshot = data.snapshot();
+        let changed = self.seen.as_ref().map(|x| !x.same(&snapshot)).unwrap_or(false);
+        if data.config.autosave && (changed || !self.enabled) {
+            self.timer = ctx.request_timer(AutosaveController::delay(data));
+        }
+        self.seen = Some(snapshot);
+        self.enabled = data.config.autosave;
+    }
+}
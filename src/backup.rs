@@ -0,0 +1,116 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime};
+use im::Vector;
+
+use crate::files;
+
+const BACKUP_DIR: &str = "Backups";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub const DEFAULT_BACKUP_RETENTION: usize = 10;
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data))]
+pub struct Backup {
+    pub name: String,
+    #[cfg_attr(feature = "gui", data(eq))]
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// When the backup was made, going by its folder name. A suffix added for backups made in the
+    /// same second is ignored.
+    pub fn time(&self) -> Option<NaiveDateTime> {
+        let timestamp = self.name.get(..19).unwrap_or(&self.name);
+        NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()
+    }
+}
+
+pub fn backup_root(save_dir: &Path) -> PathBuf {
+    save_dir.join(BACKUP_DIR)
+}
+
+/// Copies every file in `files` that exists into a new timestamped folder under `Backups/`, keeping
+/// each file's path relative to `save_dir` so a backup can be copied straight back over the save.
+pub fn create_backup(save_dir: &Path, files: &[PathBuf]) -> Result<Backup, Box<dyn Error>> {
+    let root = backup_root(save_dir);
+    let timestamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let mut name = timestamp.clone();
+    let mut suffix = 1;
+    while root.join(&name).exists() {
+        name = format!("{}_{}", timestamp, suffix);
+        suffix += 1;
+    }
+
+    let path = root.join(&name);
+    fs::create_dir_all(&path)?;
+    for file in files {
+        if !file.exists() {
+            continue;
+        }
+        let destination = path.join(file.strip_prefix(save_dir)?);
+        if let Some(parent) = destination.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(file, destination)?;
+    }
+
+    Ok(Backup { name, path })
+}
+
+/// Lists the backups for `save_dir`, newest first.
+pub fn list_backups(save_dir: &Path) -> Result<Vector<Backup>, Box<dyn Error>> {
+    let root = backup_root(save_dir);
+    let mut backups = Vec::new();
+    if root.exists() {
+        for entry in root.read_dir()? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                backups.push(Backup {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    path: entry.path(),
+                });
+            }
+        }
+    }
+    // Timestamps are zero-padded, so sorting by name is sorting by age
+    backups.sort_by(|a, b| b.name.cmp(&a.name));
+
+    Ok(Vector::from(backups))
+}
+
+/// Deletes all but the newest `retention` backups. A retention of 0 keeps everything.
+pub fn prune_backups(save_dir: &Path, retention: usize) -> Result<(), Box<dyn Error>> {
+    if retention == 0 {
+        return Ok(());
+    }
+    for backup in list_backups(save_dir)?.iter().skip(retention) {
+        fs::remove_dir_all(&backup.path)?;
+    }
+
+    Ok(())
+}
+
+/// Copies every file in `backup` back over the save. Each file is replaced atomically, as a save
+/// would, so a crash part way through never leaves a file cut short.
+pub fn restore_backup(save_dir: &Path, backup: &Backup) -> Result<(), Box<dyn Error>> {
+    copy_dir(&backup.path, save_dir)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(to)?;
+    for entry in from.read_dir()? {
+        let entry = entry?;
+        let destination = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &destination)?;
+        } else {
+            files::write_atomic(&destination, &fs::read(entry.path())?)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+
+use druid::Data;
+use druid::im::vector::Vector;
+use icarus_save::{Character, CharacterStore, Profile};
+
+use crate::backup::Backup;
+use crate::UiState;
+
+/// A backup read back in, to look through in the backup window without touching the save.
+#[derive(Clone, Data)]
+pub struct BackupView {
+    pub backup: Backup,
+    pub profile: Profile,
+    pub characters: Vector<Character>,
+}
+
+impl UiState {
+    /// Reads `backup` for the backup window. Characters in it that can't be read are left out.
+    pub fn view_backup(&mut self, backup: &Backup) {
+        let backup = backup.clone();
+        self.backup_view = None;
+        self.run_in_background("open backup", "Reading backup", move || {
+            let store = CharacterStore::load_with(&backup.path, true)?;
+            Ok(BackupView { backup, profile: store.profile, characters: store.characters })
+        }, |t, view| {
+            t.backup_view = Some(view);
+
+            Ok(())
+        });
+    }
+
+    /// Puts the character at `backup_idx` in `backup` back into the editor, in place of the character
+    /// in the same slot (or as a new one if the slot is empty now), with their inventory and loadout.
+    /// Nothing is written until the next save.
+    pub fn restore_character_from_backup(&mut self, backup: &Backup, backup_idx: usize) -> Result<(), Box<dyn Error>> {
+        let view = self.backup_view.as_ref()
+            .filter(|view| view.backup == *backup)
+            .ok_or_else(|| format!("Backup {} isn't open any more - view it again to restore from it", backup.name))?;
+        let mut character = view.characters.get(backup_idx).cloned().ok_or("That character isn't in the backup")?;
+        let backup_name = view.backup.name.clone();
+        character.set_file_paths(&self.save_dir);
+
+        let name = character.character_name.clone();
+        match self.characters.iter().position(|c| c.character_slot == character.character_slot) {
+            Some(idx) => self.characters[idx] = character,
+            None => self.characters.push_back(character),
+        }
+        self.status = format!("Restored {} from backup {} - save to keep it", name, backup_name);
+
+        Ok(())
+    }
+}
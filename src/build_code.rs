@@ -0,0 +1,61 @@
+use std::error::Error;
+
+/// Marks a build code and the version of its format, so codes from a later format can be told apart.
+const PREFIX: &str = "ICB1:";
+
+/// A character's talent ranks and blueprints, without anything else about the character.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Build {
+    pub talents: Vec<(String, i64)>,
+    pub blueprints: Vec<String>,
+}
+
+/// Packs a build into a short code that can be pasted into a chat message. Row names never contain
+/// `*`, `,` or `|`, so they can separate the parts.
+pub fn encode(build: &Build) -> String {
+    let talents = build.talents.iter().map(|(row_name, rank)| format!("{}*{}", row_name, rank)).collect::<Vec<String>>();
+    let text = format!("{}|{}", talents.join(","), build.blueprints.join(","));
+    format!("{}{}", PREFIX, base64::encode_config(text, base64::URL_SAFE_NO_PAD))
+}
+
+pub fn decode(code: &str) -> Result<Build, Box<dyn Error>> {
+    let encoded = code.trim().strip_prefix(PREFIX).ok_or("that isn't a build code")?;
+    let text = String::from_utf8(base64::decode_config(encoded, base64::URL_SAFE_NO_PAD)?)?;
+    let (talents, blueprints) = text.split_once('|').ok_or("the build code is damaged")?;
+
+    let mut build = Build::default();
+    for talent in talents.split(',').filter(|x| !x.is_empty()) {
+        let (row_name, rank) = talent.split_once('*').ok_or("the build code is damaged")?;
+        build.talents.push((row_name.to_string(), rank.parse().map_err(|_| format!("[{}] is not a rank", rank))?));
+    }
+    build.blueprints = blueprints.split(',').filter(|x| !x.is_empty()).map(|x| x.to_string()).collect();
+
+    Ok(build)
+}
+
+/// Reads a list of `row_name,rank` lines, as saved from a spreadsheet. Blank lines, a header line and
+/// quotes around fields are fine, and a row without a rank counts as rank 1, as for a blueprint.
+pub fn parse_ranks_csv(text: &str) -> Result<Vec<(String, i64)>, Box<dyn Error>> {
+    let mut ranks = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let mut fields = line.split([',', ';', '\t']).map(|x| x.trim().trim_matches('"').trim());
+        let row_name = fields.next().unwrap_or_default();
+        let rank = fields.next().unwrap_or_default();
+        if row_name.is_empty() {
+            continue;
+        }
+        let rank = match rank.parse::<f64>() {
+            Ok(rank) if rank.is_finite() => rank.round() as i64,
+            _ if rank.is_empty() => 1,
+            // Spreadsheets usually start with a row of column names
+            _ if idx == 0 => continue,
+            _ => Err(format!("line {}: [{}] is not a rank", idx + 1, rank))?,
+        };
+        ranks.push((row_name.to_string(), rank));
+    }
+    if ranks.is_empty() {
+        Err("there aren't any `row_name,rank` lines in it")?
+    }
+
+    Ok(ranks)
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use crate::numbers::Slot;
+use crate::{game_data, guard, CharacterEdits, ProfileEdits, UiState, META_RESOURCE_CREDITS, META_RESOURCE_EXOTICS};
+
+pub const USAGE: &str = "Usage: icarus-offline-character-editor [--save-dir <folder> | <folder or file>] [--safe-mode | <command> [--slot <slot>] [--force] [--wipe-inventory]]
+
+With no command, the editor window opens. --safe-mode opens it without allowing anything to be
+written to the save folder. The save can be given as a folder or as a file in it, such as
+Characters.json, so dropping either onto the program opens that save. Commands:
+    max-level               Set characters to the maximum level
+    unlock-talents          Unlock every talent for characters
+    unlock-blueprints       Unlock every blueprint for characters
+    unlock-prospects        Unlock every prospect
+    unlock-workshop         Unlock every workshop item
+    set-credits <amount>    Set the number of credits
+    set-exotics <amount>    Set the number of exotics
+    restore --slot <slot>   Restore a dead or abandoned character, keeping their items unless
+                            --wipe-inventory is given
+    help                    Show this message
+    version                 Show the editor's version and that of its game data
+
+Character commands apply to every character unless --slot is given. Saving is refused while
+Icarus is running unless --force is given.";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CliCommand {
+    MaxLevel,
+    UnlockTalents,
+    UnlockBlueprints,
+    UnlockProspects,
+    UnlockWorkshop,
+    SetCredits(f64),
+    SetExotics(f64),
+    Restore,
+    Help,
+    Version,
+}
+
+impl CliCommand {
+    /// Commands that are only in the full version, matching the buttons in each UI.
+    fn is_cheat(&self) -> bool {
+        !matches!(self, CliCommand::UnlockProspects | CliCommand::Restore | CliCommand::Help | CliCommand::Version)
+    }
+
+    /// Commands that print something and stop, without loading the save.
+    pub fn is_info(&self) -> bool {
+        matches!(self, CliCommand::Help | CliCommand::Version)
+    }
+
+    /// Commands that change characters, rather than the profile, so need at least one to work on.
+    fn is_character_command(&self) -> bool {
+        matches!(self, CliCommand::MaxLevel | CliCommand::UnlockTalents | CliCommand::UnlockBlueprints | CliCommand::Restore)
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct Options {
+    pub save_dir: Option<PathBuf>,
+    pub command: Option<CliCommand>,
+    pub slot: Option<Slot>,
+    pub force: bool,
+    pub wipe_inventory: bool,
+    pub safe_mode: bool,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args;
+    while let Some(arg) = args.next() {
+        let command = match arg.as_str() {
+            "--save-dir" => {
+                options.save_dir = Some(args.next().ok_or("--save-dir needs a folder")?.into());
+                continue;
+            }
+            "--slot" => {
+                let slot = args.next().ok_or("--slot needs a slot number")?;
+                options.slot = Some(slot.parse().map_err(|_| format!("[{}] is not a slot number", slot))?);
+                continue;
+            }
+            "--force" => {
+                options.force = true;
+                continue;
+            }
+            "--wipe-inventory" => {
+                options.wipe_inventory = true;
+                continue;
+            }
+            "--safe-mode" => {
+                options.safe_mode = true;
+                continue;
+            }
+            "max-level" => CliCommand::MaxLevel,
+            "unlock-talents" => CliCommand::UnlockTalents,
+            "unlock-blueprints" => CliCommand::UnlockBlueprints,
+            "unlock-prospects" => CliCommand::UnlockProspects,
+            "unlock-workshop" => CliCommand::UnlockWorkshop,
+            "set-credits" => CliCommand::SetCredits(parse_amount(args.next())?),
+            "set-exotics" => CliCommand::SetExotics(parse_amount(args.next())?),
+            "restore" => CliCommand::Restore,
+            "help" | "--help" | "-h" => CliCommand::Help,
+            "version" | "--version" | "-V" => CliCommand::Version,
+            _ => {
+                if let Some(path) = arg.strip_prefix("--save-dir=") {
+                    options.save_dir = Some(path.into());
+                    continue;
+                }
+                // What Windows passes when a folder or file is dropped onto the program
+                if options.save_dir.is_none() && Path::new(&arg).exists() {
+                    options.save_dir = Some(arg.into());
+                    continue;
+                }
+                return Err(format!("Unknown argument [{}]", arg));
+            }
+        };
+        if options.command.is_some() {
+            return Err(format!("Only one command can be run at a time, but got [{}] as well", arg));
+        }
+        if command.is_cheat() && !cfg!(feature = "full") {
+            return Err(format!("[{}] is only available in the full version", arg));
+        }
+        options.command = Some(command);
+    }
+    if options.safe_mode && options.command.map(|x| !x.is_info()).unwrap_or(false) {
+        return Err("--safe-mode can't be used with a command, since every command saves".into());
+    }
+    options.save_dir = options.save_dir.map(|x| save_dir_for(&x));
+    if options.command == Some(CliCommand::Restore) && options.slot.is_none() {
+        return Err("restore needs --slot".into());
+    }
+
+    Ok(options)
+}
+
+/// The editor's version, which build it is, and the version of the game data it has.
+pub fn version_text() -> String {
+    let edition = if cfg!(feature = "full") { "full" } else { "no cheats" };
+    let mut text = format!("icarus-offline-character-editor {} ({})\nGame data: {}", env!("CARGO_PKG_VERSION"), edition, game_data::DATA_VERSION);
+    let overriding = game_data::overriding_files();
+    if !overriding.is_empty() {
+        text.push_str(&format!(" (overridden by {} from the data folder)", overriding.join(", ")));
+    }
+    text
+}
+
+/// The save folder a path refers to - the path itself for a folder, or the folder a save file such
+/// as `Characters.json` is in.
+fn save_dir_for(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(parent) if path.is_file() => parent.to_path_buf(),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// An amount of credits or exotics. Anything that isn't a finite, non-negative number is refused, as
+/// it can't be written to the profile as a number.
+fn parse_amount(arg: Option<String>) -> Result<f64, String> {
+    let arg = arg.ok_or("Missing amount")?;
+    match arg.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(amount),
+        _ => Err(format!("[{}] is not an amount - it must be a number of at least 0", arg)),
+    }
+}
+
+/// Applies `command` to the save in `data` and saves it, without opening a window.
+pub fn run(command: CliCommand, options: &Options, mut data: UiState) -> Result<(), Box<dyn Error>> {
+    if !options.force && guard::game_running() {
+        Err(format!("Icarus ({}) is running and would overwrite any changes on exit - close it or pass --force", guard::GAME_PROCESS))?
+    }
+    let locked = guard::locked_files(&data.save_files());
+    if !locked.is_empty() {
+        Err(format!("These files are in use by another program: {:?}", locked))?
+    }
+
+    let indices = data.characters.iter().enumerate()
+        .filter(|(_, c)| options.slot.map(|slot| c.character_slot == slot).unwrap_or(true))
+        .map(|(idx, _)| idx)
+        .collect::<Vec<usize>>();
+    if indices.is_empty() && command.is_character_command() {
+        match options.slot {
+            Some(slot) => Err(format!("No character found in slot {}", slot))?,
+            None => Err("The save has no characters")?,
+        }
+    }
+
+    match command {
+        CliCommand::MaxLevel => indices.iter().for_each(|idx| data.characters[*idx].level_to_max()),
+        CliCommand::UnlockTalents => indices.iter().for_each(|idx| data.characters[*idx].unlock_all_talents()),
+        CliCommand::UnlockBlueprints => indices.iter().for_each(|idx| data.characters[*idx].unlock_all_blueprints()),
+        CliCommand::UnlockProspects => data.profile.unlock_all_prospects(),
+        CliCommand::UnlockWorkshop => data.profile.unlock_all_workshop_items(),
+        CliCommand::SetCredits(amount) => data.profile.set_meta_resource(META_RESOURCE_CREDITS, amount),
+        CliCommand::SetExotics(amount) => data.profile.set_meta_resource(META_RESOURCE_EXOTICS, amount),
+        CliCommand::Restore => {
+            data.wipe_inventory = options.wipe_inventory;
+            for idx in indices {
+                data.restore_character(idx)?;
+            }
+        }
+        // Printed before the save is even loaded
+        CliCommand::Help | CliCommand::Version => return Ok(()),
+    }
+
+    let changed = data.changed_files();
+    if changed.is_empty() {
+        println!("Nothing changed, so nothing was saved");
+        return Ok(());
+    }
+    data.save()?;
+    for path in changed {
+        println!("Saved [{}]", path.to_string_lossy());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::collections::BTreeSet;
+
+use druid::Data;
+
+use crate::{display_name, Character, CharacterEdits, BLUEPRINTS, FLAGS};
+
+/// Something two characters have differently, with how each of them has it.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct Difference {
+    /// Which part of the character it's in, e.g. "Talents"
+    pub section: &'static str,
+    pub name: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl Difference {
+    fn new(section: &'static str, name: &str, left: String, right: String) -> Difference {
+        Difference { section, name: name.to_string(), left, right }
+    }
+}
+
+fn unlocked(yes: bool) -> String {
+    if yes { "Unlocked".to_string() } else { "-".to_string() }
+}
+
+fn flag_name(flag: u32) -> String {
+    FLAGS.iter().find(|(x, _, _)| x.0 == flag).map(|(_, name, _)| name.to_string()).unwrap_or_else(|| format!("Flag {}", flag))
+}
+
+/// Everything that differs between two characters' XP, talents, blueprints and flags, in that
+/// order. Talents and blueprints are sorted by name, flags by number.
+pub fn compare(left: &Character, right: &Character) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    if left.xp != right.xp {
+        differences.push(Difference::new("XP", "XP", format!("{} (level {})", left.xp, left.level()), format!("{} (level {})", right.xp, right.level())));
+    }
+    if left.xp_debt != right.xp_debt {
+        differences.push(Difference::new("XP", "XP debt", left.xp_debt.to_string(), right.xp_debt.to_string()));
+    }
+
+    let rows = left.talents.iter().chain(right.talents.iter()).map(|t| t.row_name.as_str()).collect::<BTreeSet<&str>>();
+    let (blueprints, talents): (Vec<&str>, Vec<&str>) = rows.into_iter().partition(|x| BLUEPRINTS.contains(x));
+    for talent in talents {
+        let (left_rank, right_rank) = (left.talent_rank(talent), right.talent_rank(talent));
+        if left_rank != right_rank {
+            differences.push(Difference::new("Talents", display_name(talent), left_rank.to_string(), right_rank.to_string()));
+        }
+    }
+    for blueprint in blueprints {
+        let (left_has, right_has) = (left.talent_rank(blueprint) > 0, right.talent_rank(blueprint) > 0);
+        if left_has != right_has {
+            differences.push(Difference::new("Blueprints", display_name(blueprint), unlocked(left_has), unlocked(right_has)));
+        }
+    }
+
+    let flags = left.unlocked_flags.iter().chain(right.unlocked_flags.iter()).map(|x| x.0).collect::<BTreeSet<u32>>();
+    for flag in flags {
+        let (left_has, right_has) = (left.unlocked_flags.iter().any(|x| x.0 == flag), right.unlocked_flags.iter().any(|x| x.0 == flag));
+        if left_has != right_has {
+            differences.push(Difference::new("Flags", &flag_name(flag), unlocked(left_has), unlocked(right_has)));
+        }
+    }
+
+    differences
+}
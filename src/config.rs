@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use druid::widget::Controller;
+use druid::{Data, Env, Event, EventCtx, Lens, Widget};
+use serde::{Deserialize, Serialize};
+
+use crate::autosave::DEFAULT_AUTOSAVE_DELAY;
+use crate::backup::DEFAULT_BACKUP_RETENTION;
+use crate::files;
+use crate::game_data::DEFAULT_DATA_URL;
+use crate::theme::Theme;
+use crate::UiState;
+
+const CONFIG_FILE: &str = "config.json";
+
+pub const DEFAULT_WINDOW_SIZE: (f64, f64) = (750.0, 900.0);
+
+/// Editor preferences, kept in the platform config dir rather than next to the save files.
+#[derive(Clone, Serialize, Deserialize, Debug, Data, Lens)]
+#[serde(default)]
+pub struct Config {
+    #[data(eq)]
+    pub save_dir: Option<PathBuf>,
+    pub backup_retention: usize,
+    pub window_size: (f64, f64),
+    pub window_position: Option<(f64, f64)>,
+    /// The top-level tab that was open when the editor was closed
+    pub last_tab: usize,
+    /// Ask before closing or switching accounts with unsaved changes
+    pub confirm_unsaved: bool,
+    /// Ask before saving while Icarus is running
+    pub confirm_game_running: bool,
+    /// Ask before resetting or unlocking everything, or restoring or rescuing a character
+    pub confirm_bulk_changes: bool,
+    /// Save by itself a while after each change, for anyone who keeps forgetting to
+    pub autosave: bool,
+    /// How many seconds after the last change to autosave
+    pub autosave_delay: u64,
+    pub theme: Theme,
+    /// How much bigger than normal to draw text and widgets, for high resolution screens or anyone
+    /// who finds the default too small
+    pub ui_scale: f64,
+    /// Show rows from DLC the save doesn't seem to own
+    pub show_unowned_dlc: bool,
+    /// Look for a newer release on GitHub at startup. Off unless turned on, as it goes online
+    pub check_for_updates: bool,
+    /// Where "Download Data Files" gets the data files from, e.g. a fork's raw file URL
+    pub data_url: String,
+    /// Whether the first run wizard has been finished or skipped. Configs from before there was a
+    /// wizard count as finished.
+    #[serde(default = "already_set_up")]
+    pub setup_done: bool,
+    /// Nothing is written to the save folder. Only ever turned on for the current run, so it's never
+    /// left on by accident.
+    #[serde(skip)]
+    pub safe_mode: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            save_dir: None,
+            backup_retention: DEFAULT_BACKUP_RETENTION,
+            window_size: DEFAULT_WINDOW_SIZE,
+            window_position: None,
+            last_tab: 0,
+            confirm_unsaved: true,
+            confirm_game_running: true,
+            confirm_bulk_changes: true,
+            autosave: false,
+            autosave_delay: DEFAULT_AUTOSAVE_DELAY,
+            theme: Theme::System,
+            ui_scale: 1.0,
+            show_unowned_dlc: false,
+            check_for_updates: false,
+            data_url: DEFAULT_DATA_URL.to_string(),
+            setup_done: false,
+            safe_mode: false,
+        }
+    }
+}
+
+fn already_set_up() -> bool {
+    true
+}
+
+/// The editor's own folder in the platform config dir.
+pub fn config_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "Icarus Offline Character Editor").map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+impl Config {
+    fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(CONFIG_FILE))
+    }
+
+    /// Loads the config, falling back to the defaults if there isn't one yet or it can't be read.
+    pub fn load() -> Config {
+        let path = match Config::path() {
+            Some(path) if path.exists() => path,
+            _ => return Config::default(),
+        };
+        match files::read_json(&path) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("Unable to read config [{}]: {}", path.to_string_lossy(), e);
+                Config::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let path = Config::path().ok_or::<Box<dyn Error>>("Unable to find config directory".into())?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        files::write_atomic(&path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+/// Keeps track of the window's size and position, and writes the config when the window closes.
+/// Meant to wrap the root widget.
+pub struct ConfigController;
+
+impl<W: Widget<UiState>> Controller<UiState, W> for ConfigController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        match event {
+            Event::WindowSize(size) => data.config.window_size = (size.width, size.height),
+            // There's no event for the window moving, so check whenever the mouse comes back
+            Event::MouseMove(_) => {
+                let position = ctx.window().get_position();
+                let position = Some((position.x, position.y));
+                if data.config.window_position != position {
+                    data.config.window_position = position;
+                }
+            }
+            Event::WindowDisconnected => {
+                if let Err(e) = data.config.save() {
+                    println!("Unable to save config: {}", e);
+                }
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
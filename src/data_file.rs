@@ -0,0 +1,78 @@
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref PROBLEMS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+/// One line of a `name,value[,display name]` data file.
+pub struct Row {
+    pub name: &'static str,
+    pub value: f64,
+    pub display_name: Option<&'static str>,
+}
+
+/// Notes a problem with a data file, to be shown once the editor is up. Each problem is only noted
+/// once, however many times the file is read.
+pub fn report(problem: String) {
+    let mut problems = PROBLEMS.lock().unwrap_or_else(|e| e.into_inner());
+    if !problems.contains(&problem) {
+        println!("{}", problem);
+        problems.push(problem);
+    }
+}
+
+/// Everything wrong with the data files read so far.
+pub fn problems() -> Vec<String> {
+    PROBLEMS.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Reads the rows of data file `file`. Blank lines and lines starting with `#` are skipped, and lines
+/// that can't be read are reported and left out rather than stopping the editor.
+pub fn parse(file: &str, text: &'static str) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for (number, line) in lines(text) {
+        let parts = line.split(',').map(str::trim).collect::<Vec<&'static str>>();
+        if parts.len() < 2 || parts.len() > 3 {
+            report(format!("{} line {}: expected `name,value` or `name,value,display name` but got [{}]", file, number, line));
+            continue;
+        }
+        let value = match parts[1].parse::<f64>() {
+            Ok(value) => value,
+            Err(_) => {
+                report(format!("{} line {}: [{}] is not a number", file, number, parts[1]));
+                continue;
+            }
+        };
+        rows.push(Row {
+            name: parts[0],
+            value,
+            display_name: parts.get(2).copied().filter(|x| !x.is_empty()),
+        });
+    }
+
+    rows
+}
+
+/// Reads a file of `name,text` pairs, such as display names. Anything after the first comma is taken
+/// as the text, commas and all.
+pub fn parse_pairs(file: &str, text: &'static str) -> Vec<(&'static str, &'static str)> {
+    let mut pairs = Vec::new();
+    for (number, line) in lines(text) {
+        match line.split_once(',') {
+            Some((name, text)) => pairs.push((name.trim(), text.trim())),
+            None => report(format!("{} line {}: expected `name,text` but got [{}]", file, number, line)),
+        }
+    }
+
+    pairs
+}
+
+/// The non-blank, non-comment lines of `text`, trimmed and numbered from 1.
+fn lines(text: &'static str) -> impl Iterator<Item = (usize, &'static str)> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
@@ -0,0 +1,192 @@
+use std::error::Error;
+
+use druid::Selector;
+
+use crate::backup::Backup;
+use crate::loadout_templates::LoadoutTemplate;
+use crate::numbers::{Flag, Slot, Xp};
+use crate::save_check::Issue;
+use crate::{CharacterEdits, ProfileEdits, UiState};
+
+/// Makes an edit to the profile, a character or a prospect. Buttons submit these rather than
+/// changing the save themselves, so their edits go through `UiState::apply_edit`. Changes big
+/// enough to ask about first, such as restoring a backup, go through `BulkAction` instead, and
+/// importing goes through `UiState::run_action` once the file has been picked. Snapshots, presets
+/// and templates are files of the editor's own rather than part of the save, so their buttons
+/// manage them directly.
+pub const EDIT: Selector<Edit> = Selector::new("icarus-editor.edit");
+
+/// What reviving a character does with the XP debt they died with.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ReviveDebt {
+    Keep,
+    Forgive,
+    TakeFromXp,
+}
+
+/// One change a button makes. Characters and prospects are by index, talents and resources by row
+/// name.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Edit {
+    MaxLevel(usize),
+    ClearXpDebt(usize),
+    PayXpDebt(usize),
+    /// Changes a character's talent rank by this much.
+    ChangeTalentRank(usize, &'static str, i64),
+    RemoveFlag(usize, Flag),
+    RemoveItem(usize, usize),
+    /// Swaps two of a character's loadout slots.
+    SwapLoadoutSlots(usize, usize, usize),
+    ClearLoadoutSlot(usize, usize),
+    AddMetaResource(&'static str),
+    RemoveMetaResource(usize),
+    /// Changes a profile talent's rank by this much.
+    ChangeProfileTalentRank(String, i64),
+    UnlockProspectsUpToTier(usize),
+    RejoinAbandoned(usize),
+    DetachFromProspect(usize),
+    Revive { idx: usize, debt: ReviveDebt },
+    /// Removes a character, and on save their inventory and loadout files too if `remove_files`
+    DeleteCharacter { idx: usize, remove_files: bool },
+    /// Sets the ticked talents to this rank, or to their max rank when `None`.
+    SetSelectedTalentRanks(usize, Option<i64>),
+    ApplyRespec,
+    /// Takes ranks off a character's talents until they've spent no more points than they've earned.
+    TrimTalentPoints(usize),
+    /// Adds the flag typed into the new flag box.
+    AddFlag(usize),
+    /// Adds the spawner's quantity of an item to the inventory.
+    SpawnItem(usize, &'static str),
+    ApplyLoadoutTemplate(usize, LoadoutTemplate),
+    CreateCharacter,
+    DuplicateCharacter(usize),
+    MoveCharacter(usize, Slot),
+    SwapCharacters(usize, Slot),
+    /// Puts the character at this index in the open backup back into the editor.
+    RestoreCharacterFromBackup { backup: Backup, backup_idx: usize },
+    /// Starts a character's unreadable inventory and loadout afresh.
+    FixCharacterFiles(usize),
+    RepairIssue(Issue),
+    RepairAllIssues,
+}
+
+impl Edit {
+    /// What the edit does, for the message shown if it fails.
+    fn action(&self) -> &'static str {
+        match self {
+            Edit::ApplyLoadoutTemplate(..) => "apply loadout template",
+            Edit::CreateCharacter => "create character",
+            Edit::DuplicateCharacter(_) => "duplicate character",
+            Edit::MoveCharacter(..) => "move character",
+            Edit::SwapCharacters(..) => "swap characters",
+            Edit::RestoreCharacterFromBackup { .. } => "restore character from backup",
+            Edit::FixCharacterFiles(_) => "fix character files",
+            Edit::RepairIssue(_) => "fix problem",
+            Edit::RepairAllIssues => "fix problems",
+            _ => "edit the save",
+        }
+    }
+}
+
+impl UiState {
+    /// Makes `edit` as one undo step, showing any error in a dialog. Edits for a character or
+    /// prospect that's gone are ignored.
+    pub fn apply_edit(&mut self, edit: Edit) {
+        let what = edit.action();
+        self.undoable(|t| { t.run_action(what, |t| t.make_edit(edit)); });
+    }
+
+    fn make_edit(&mut self, edit: Edit) -> Result<(), Box<dyn Error>> {
+        match edit {
+            Edit::MaxLevel(idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.level_to_max();
+                }
+            }
+            Edit::ClearXpDebt(idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.xp_debt = Xp(0);
+                }
+            }
+            Edit::PayXpDebt(idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.pay_xp_debt();
+                }
+            }
+            Edit::ChangeTalentRank(idx, row_name, by) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.set_talent_rank(row_name, c.talent_rank(row_name) + by);
+                }
+            }
+            Edit::RemoveFlag(idx, flag) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.unlocked_flags.retain(|x| *x != flag);
+                }
+            }
+            Edit::RemoveItem(idx, item_idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.inventory.remove_item(item_idx);
+                }
+            }
+            Edit::SwapLoadoutSlots(idx, a, b) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.loadout.swap_slots(a, b);
+                }
+            }
+            Edit::ClearLoadoutSlot(idx, slot) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.loadout.clear_slot(slot);
+                }
+            }
+            Edit::AddMetaResource(meta_row) => self.profile.add_meta_resource(meta_row),
+            Edit::RemoveMetaResource(idx) => self.profile.remove_meta_resource(idx),
+            Edit::ChangeProfileTalentRank(row_name, by) => {
+                let rank = self.profile.talent_rank(&row_name) + by;
+                self.profile.set_talent_rank(&row_name, rank);
+            }
+            Edit::UnlockProspectsUpToTier(tier) => self.profile.unlock_prospects_up_to_tier(tier),
+            Edit::RejoinAbandoned(idx) => {
+                if let Some(prospect) = self.prospects.get_mut(idx) {
+                    prospect.data.info.reset_abandoned();
+                }
+            }
+            Edit::DetachFromProspect(idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.detach_from_prospect();
+                }
+            }
+            Edit::Revive { idx, debt } => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    c.is_dead = false;
+                    match debt {
+                        ReviveDebt::Keep => {}
+                        ReviveDebt::Forgive => c.xp_debt = Xp(0),
+                        ReviveDebt::TakeFromXp => c.pay_xp_debt(),
+                    }
+                }
+            }
+            Edit::DeleteCharacter { idx, remove_files } => self.delete_character(idx, remove_files),
+            Edit::SetSelectedTalentRanks(idx, rank) => self.set_selected_talent_ranks(idx, rank),
+            Edit::ApplyRespec => self.apply_respec(),
+            Edit::TrimTalentPoints(idx) => {
+                if let Some(c) = self.characters.get_mut(idx) {
+                    let removed = c.trim_talent_points();
+                    self.status = format!("Removed {} excess talent points", removed);
+                }
+            }
+            Edit::AddFlag(idx) => self.add_flag(idx),
+            Edit::SpawnItem(idx, row_name) => self.spawn_item(idx, row_name),
+            Edit::ApplyLoadoutTemplate(idx, template) => self.apply_loadout_template(idx, &template)?,
+            Edit::CreateCharacter => self.create_character()?,
+            Edit::DuplicateCharacter(idx) => self.duplicate_character(idx)?,
+            Edit::MoveCharacter(idx, slot) => self.move_character(idx, slot)?,
+            Edit::SwapCharacters(idx, slot) => self.swap_characters(idx, slot)?,
+            Edit::RestoreCharacterFromBackup { backup, backup_idx } => self.restore_character_from_backup(&backup, backup_idx)?,
+            Edit::FixCharacterFiles(idx) => self.fix_character_files(idx)?,
+            Edit::RepairIssue(issue) => self.repair_issue(&issue)?,
+            Edit::RepairAllIssues => self.repair_all_issues()?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::error::Error;
+use std::path::Path;
+
+use druid::Data;
+use serde::{Deserialize, Serialize};
+
+use crate::files;
+use crate::inventory::Inventory;
+use crate::loadout::Loadout;
+use crate::{Character, Profile};
+
+const EXPORT_FORMAT: u32 = 1;
+
+/// A character bundled with its inventory and loadout in a single file, for moving it between
+/// machines or sharing it.
+#[derive(Serialize, Deserialize)]
+pub struct CharacterExport {
+    #[serde(rename = "Format")]
+    pub format: u32,
+    #[serde(rename = "Character")]
+    pub character: Character,
+    #[serde(rename = "Inventory", default)]
+    pub inventory: Option<Inventory>,
+    #[serde(rename = "Loadout", default)]
+    pub loadout: Option<Loadout>,
+}
+
+pub fn export_character(character: &Character, path: &Path) -> Result<(), Box<dyn Error>> {
+    let export = CharacterExport {
+        format: EXPORT_FORMAT,
+        character: character.clone(),
+        inventory: if character.inventory_loaded { Some(character.inventory.clone()) } else { None },
+        loadout: if character.loadout_loaded { Some(character.loadout.clone()) } else { None },
+    };
+
+    files::write_atomic(path, serde_json::to_string_pretty(&export)?.as_bytes())
+}
+
+pub fn import_character(path: &Path) -> Result<CharacterExport, Box<dyn Error>> {
+    let export: CharacterExport = files::read_json(path)?;
+    if export.format > EXPORT_FORMAT {
+        Err(format!("[{}] was exported by a newer version of this editor", path.to_string_lossy()))?
+    }
+
+    Ok(export)
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ProfileExport {
+    #[serde(rename = "Format")]
+    pub format: u32,
+    #[serde(rename = "Profile")]
+    pub profile: Profile,
+}
+
+/// How an imported profile is combined with the current one.
+#[derive(Clone, Copy, Data, PartialEq, Debug)]
+pub enum MergeStrategy {
+    /// Take everything from the import
+    Replace,
+    /// Add resource counts together, and keep talents and flags from both
+    AddCounts,
+    /// Keep the larger of each resource count, and talents and flags from both
+    Union,
+}
+
+pub fn export_profile(profile: &Profile, path: &Path) -> Result<(), Box<dyn Error>> {
+    let export = ProfileExport { format: EXPORT_FORMAT, profile: profile.clone() };
+
+    files::write_atomic(path, serde_json::to_string_pretty(&export)?.as_bytes())
+}
+
+pub fn import_profile(path: &Path) -> Result<Profile, Box<dyn Error>> {
+    let export: ProfileExport = files::read_json(path)?;
+    if export.format > EXPORT_FORMAT {
+        Err(format!("[{}] was exported by a newer version of this editor", path.to_string_lossy()))?
+    }
+
+    Ok(export.profile)
+}
+
+/// Merges `imported` into `profile`. The `UserID` always stays as it is, so a profile can be moved
+/// between accounts.
+pub fn merge_profile(profile: &mut Profile, imported: Profile, strategy: MergeStrategy) {
+    if strategy == MergeStrategy::Replace {
+        let user_id = profile.user_id.clone();
+        *profile = imported;
+        profile.user_id = user_id;
+        return;
+    }
+
+    for resource in imported.meta_resources {
+        let current = profile.meta_resource(&resource.meta_row);
+        let count = match strategy {
+            MergeStrategy::AddCounts => current + resource.count,
+            _ => current.max(resource.count),
+        };
+        profile.set_meta_resource(&resource.meta_row, count);
+    }
+    for flag in imported.unlocked_flags {
+        if !profile.unlocked_flags.contains(&flag) {
+            profile.unlocked_flags.push_back(flag);
+        }
+    }
+    for talent in imported.talents {
+        match profile.talents.iter_mut().find(|t| t.row_name == talent.row_name) {
+            Some(existing) => existing.rank = existing.rank.max(talent.rank),
+            None => profile.talents.push_back(talent),
+        }
+    }
+    for mission in imported.completed_missions {
+        if !profile.completed_missions.contains(&mission) {
+            profile.completed_missions.push_back(mission);
+        }
+    }
+    for time in imported.mission_best_times {
+        match profile.mission_best_times.iter_mut().find(|x| x.mission_row == time.mission_row) {
+            Some(existing) => existing.best_time = existing.best_time.min(time.best_time),
+            None => profile.mission_best_times.push_back(time),
+        }
+    }
+}
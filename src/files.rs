@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const TEMP_SUFFIX: &str = ".tmp";
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, Box<dyn Error>> {
+    let mut file_io = OpenOptions::new().read(true).open(path)?;
+    let mut file_contents = String::new();
+    file_io.read_to_string(&mut file_contents)?;
+
+    Ok(serde_json::from_str(&file_contents)?)
+}
+
+/// Reads a save file that's about to be edited. It's opened for writing as well, so a file another
+/// program has locked is caught before any editing is done rather than on save.
+pub fn read_writable(path: &Path) -> Result<String, Box<dyn Error>> {
+    let mut file_io = OpenOptions::new().write(true).read(true).open(path)?;
+    let mut file_contents = String::new();
+    file_io.read_to_string(&mut file_contents)?;
+
+    Ok(file_contents)
+}
+
+/// Makes the folder `path` goes in, if it isn't there yet - `Inventory/` and `Loadout/` only
+/// appear once the game has written a character.
+pub fn create_parent(path: &Path) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    Ok(())
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), Box<dyn Error>> {
+    let file_contents = serde_json::to_string(value)?;
+    write_atomic(path, file_contents.as_bytes())
+}
+
+/// Writes `contents` to a temporary file next to `path`, then renames it over `path`. If anything
+/// goes wrong part way through, the original file is left untouched rather than truncated.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut temp_name = path.file_name().map(OsString::from).ok_or(format!("[{}] is not a file", path.to_string_lossy()))?;
+    temp_name.push(TEMP_SUFFIX);
+    let temp_path = path.with_file_name(temp_name);
+
+    let result = write_and_sync(&temp_path, contents).and_then(|_| Ok(fs::rename(&temp_path, path)?));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+fn write_and_sync(path: &Path, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+    let mut file_io = File::create(path)?;
+    file_io.write_all(contents)?;
+    file_io.sync_all()?;
+
+    Ok(())
+}
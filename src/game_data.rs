@@ -0,0 +1,246 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::config;
+use crate::files;
+
+const DATA_DIR: &str = "data";
+
+/// Where "Download Data Files" gets them from unless the config says otherwise - the data files in
+/// the editor's own repository, which are kept up to date with game patches between releases.
+pub const DEFAULT_DATA_URL: &str = "https://raw.githubusercontent.com/ipsi/icarus-offline-character-editor/main/src/";
+
+/// The data table the game keeps talents, blueprints, workshop items and prospects in - they're all
+/// talents as far as the save files are concerned.
+const TALENTS_TABLE: &str = "D_Talents.json";
+
+pub const TALENTS_FILE: &str = "talents.txt";
+pub const BLUEPRINTS_FILE: &str = "blueprints.txt";
+pub const WORKSHOP_ITEMS_FILE: &str = "workshop_items.txt";
+pub const PROSPECTS_FILE: &str = "prospects.txt";
+pub const PROSPECT_TIERS_FILE: &str = "prospect_tiers.txt";
+pub const WORKSHOP_COSTS_FILE: &str = "workshop_costs.txt";
+pub const WORKSHOP_EXOTIC_COSTS_FILE: &str = "workshop_exotic_costs.txt";
+pub const ITEMS_FILE: &str = "items.txt";
+pub const LEVELS_FILE: &str = "levels.txt";
+pub const DISPLAY_NAMES_FILE: &str = "display_names.txt";
+pub const TALENT_TREES_FILE: &str = "talent_trees.txt";
+pub const TALENT_PREREQUISITES_FILE: &str = "talent_prerequisites.txt";
+pub const FLAGS_FILE: &str = "flags.txt";
+pub const DLC_FILE: &str = "dlc.txt";
+pub const ITEM_CATEGORIES_FILE: &str = "item_categories.txt";
+pub const BLUEPRINT_LEVELS_FILE: &str = "blueprint_levels.txt";
+pub const COSMETICS_FILE: &str = "cosmetics.txt";
+pub const TALENT_POINTS_FILE: &str = "talent_points.txt";
+
+/// A fingerprint of the data files built into this copy of the editor, worked out by `build.rs`.
+pub const DATA_VERSION: &str = env!("ICARUS_DATA_VERSION");
+
+/// Where imported game data is kept. Any of the editor's data files can be put here by hand too, to
+/// add rows from a game patch without waiting for a new release.
+pub fn data_dir() -> Option<PathBuf> {
+    config::config_dir().map(|x| x.join(DATA_DIR))
+}
+
+/// The data files in the data folder, which are used instead of the built in ones.
+pub fn overriding_files() -> Vec<String> {
+    let mut files = data_dir()
+        .and_then(|dir| dir.read_dir().ok())
+        .map(|entries| entries.filter_map(|x| x.ok())
+            .map(|x| x.file_name().to_string_lossy().to_string())
+            .filter(|x| x.ends_with(".txt"))
+            .collect::<Vec<_>>())
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The contents of data file `file_name` - the copy in the data folder if there is one, otherwise
+/// `default`. Files are read once and kept for the life of the program.
+pub fn table(file_name: &str, default: &'static str) -> &'static str {
+    let path = match data_dir() {
+        Some(dir) => dir.join(file_name),
+        None => return default,
+    };
+    if !path.exists() {
+        return default;
+    }
+    match fs::read_to_string(&path) {
+        Ok(text) => {
+            println!("Using [{}] instead of the built in list", path.to_string_lossy());
+            Box::leak(text.into_boxed_str())
+        }
+        Err(e) => {
+            println!("Unable to read [{}], using the built in list: {}", path.to_string_lossy(), e);
+            default
+        }
+    }
+}
+
+/// Reads the talents data table from `source` - a folder of data tables exported from the game's
+/// `data.pak` - and writes the editor's data files from it. Returns a line for each file written.
+/// The new data is used the next time the editor starts.
+pub fn import(source: &Path) -> Result<Vec<String>, Box<dyn Error>> {
+    let table_path = find_file(source, TALENTS_TABLE)?
+        .ok_or_else(|| format!("Unable to find {} in [{}]", TALENTS_TABLE, source.to_string_lossy()))?;
+    let table: Value = files::read_json(&table_path)?;
+
+    let mut lists = BTreeMap::<&str, Vec<String>>::new();
+    for (name, row) in rows(&table) {
+        let (file, value) = match classify(&name, row) {
+            Some(file) if file == TALENTS_FILE => (file, max_rank(row)),
+            Some(file) => (file, 1.0),
+            None => continue,
+        };
+        lists.entry(file).or_default().push(format!("{},{:.1}", name, value));
+        if file == TALENTS_FILE {
+            lists.entry(TALENT_TREES_FILE).or_default().push(format!("{},{}", name, talent_tree(row)));
+            for required in required_talents(row) {
+                lists.entry(TALENT_PREREQUISITES_FILE).or_default().push(format!("{},{}", name, required));
+            }
+        }
+    }
+    if lists.is_empty() {
+        Err(format!("[{}] doesn't contain any rows the editor recognises", table_path.to_string_lossy()))?
+    }
+
+    let dir = data_dir().ok_or("Unable to find config directory")?;
+    fs::create_dir_all(&dir)?;
+    let mut written = Vec::new();
+    for (file, mut lines) in lists {
+        lines.sort();
+        files::write_atomic(&dir.join(file), lines.join("\n").as_bytes())?;
+        written.push(format!("{}: {} rows", file, lines.len()));
+    }
+
+    Ok(written)
+}
+
+/// Copies each built in data file in `defaults` to the data folder, as a starting point for editing.
+/// Files that are already there are left alone. Returns the data folder.
+pub fn write_defaults(defaults: &[(&str, &str)]) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = data_dir().ok_or("Unable to find config directory")?;
+    fs::create_dir_all(&dir)?;
+    for (file_name, contents) in defaults {
+        let path = dir.join(file_name);
+        if !path.exists() {
+            files::write_atomic(&path, contents.as_bytes())?;
+        }
+    }
+
+    Ok(dir)
+}
+
+/// Downloads each of the data files in `built_in` from `base_url` into the data folder, replacing any
+/// copy already there. Everything is downloaded before anything is written, so a dropped connection
+/// can't leave a mix of old and new files. A file that's the same as the built in one is removed from
+/// the data folder instead, so a later release's built in copy isn't hidden behind it. Returns a line
+/// for each file. The new data is used the next time the editor starts.
+pub fn download(base_url: &str, built_in: &[(&str, &str)]) -> Result<Vec<String>, Box<dyn Error>> {
+    let base_url = if base_url.ends_with('/') { base_url.to_string() } else { format!("{}/", base_url) };
+    let mut downloaded = Vec::new();
+    for (file_name, default) in built_in {
+        let url = format!("{}{}", base_url, file_name);
+        let text = ureq::get(&url)
+            .timeout(Duration::from_secs(30))
+            .call()
+            .map_err(|e| format!("Unable to download {}: {}", url, e))?
+            .into_string()?;
+        downloaded.push((*file_name, *default, text));
+    }
+
+    let dir = data_dir().ok_or("Unable to find config directory")?;
+    fs::create_dir_all(&dir)?;
+    let mut results = Vec::new();
+    for (file_name, default, text) in downloaded {
+        let path = dir.join(file_name);
+        if text.trim() == default.trim() {
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            results.push(format!("{}: same as built in", file_name));
+        } else {
+            files::write_atomic(&path, text.as_bytes())?;
+            let rows = text.lines().filter(|x| !x.trim().is_empty() && !x.trim().starts_with('#')).count();
+            results.push(format!("{}: {} rows", file_name, rows));
+        }
+    }
+
+    Ok(results)
+}
+
+fn find_file(dir: &Path, file_name: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            if let Some(found) = find_file(&entry.path(), file_name)? {
+                return Ok(Some(found));
+            }
+        } else if entry.file_name().to_string_lossy().eq_ignore_ascii_case(file_name) {
+            return Ok(Some(entry.path()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The rows of a data table, which exporters write either as a list of objects with a `Name`, or as
+/// an object keyed by row name.
+fn rows(table: &Value) -> Vec<(String, &Value)> {
+    match table.get("Rows") {
+        Some(Value::Array(rows)) => rows.iter()
+            .filter_map(|row| Some((row.get("Name")?.as_str()?.to_string(), row)))
+            .collect(),
+        Some(Value::Object(rows)) => rows.iter().map(|(name, row)| (name.clone(), row)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Exporters write references to other rows either as the row name, or as an object with a
+/// `RowName`.
+fn row_reference(value: &Value) -> Option<String> {
+    match value {
+        Value::String(name) => Some(name.clone()),
+        value => value.get("RowName").and_then(|x| x.as_str()).map(|x| x.to_string()),
+    }
+}
+
+fn talent_tree(row: &Value) -> String {
+    row.get("TalentTree").and_then(row_reference).unwrap_or_default()
+}
+
+fn required_talents(row: &Value) -> Vec<String> {
+    match row.get("RequiredTalents") {
+        Some(Value::Array(required)) => required.iter().filter_map(row_reference).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Works out which data file a row belongs in from the talent tree it's part of.
+fn classify(name: &str, row: &Value) -> Option<&'static str> {
+    let tree = talent_tree(row);
+    if name.starts_with("Prospect_") || tree.contains("Prospect") {
+        Some(PROSPECTS_FILE)
+    } else if name.starts_with("Workshop_") || tree.contains("Workshop") {
+        Some(WORKSHOP_ITEMS_FILE)
+    } else if tree.contains("Blueprint") {
+        Some(BLUEPRINTS_FILE)
+    } else if !tree.is_empty() {
+        Some(TALENTS_FILE)
+    } else {
+        None
+    }
+}
+
+/// A talent has a reward for each rank.
+fn max_rank(row: &Value) -> f64 {
+    match row.get("Rewards") {
+        Some(Value::Array(rewards)) if !rewards.is_empty() => rewards.len() as f64,
+        _ => 1.0,
+    }
+}
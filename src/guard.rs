@@ -0,0 +1,41 @@
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::process::Command;
+
+pub const GAME_PROCESS: &str = "Icarus-Win64-Shipping.exe";
+
+/// Whether Icarus is running. The game writes its own copy of the save files on exit, so anything
+/// saved while it's open is lost.
+#[cfg(windows)]
+pub fn game_running() -> bool {
+    use std::os::windows::process::CommandExt;
+    // Without this, every check flashes up a console window
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    Command::new("tasklist")
+        .args(["/FI", &format!("IMAGENAME eq {}", GAME_PROCESS), "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains(GAME_PROCESS))
+        .unwrap_or(false)
+}
+
+/// Whether Icarus is running, e.g. under Proton. The game writes its own copy of the save files
+/// on exit, so anything saved while it's open is lost.
+#[cfg(not(windows))]
+pub fn game_running() -> bool {
+    Command::new("pgrep")
+        .args(["-f", GAME_PROCESS])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Returns the files in `files` that exist but can't be opened for writing, e.g. because the game
+/// or another program has them locked.
+pub fn locked_files(files: &[PathBuf]) -> Vec<PathBuf> {
+    files.iter()
+        .filter(|file| file.exists() && OpenOptions::new().write(true).open(file).is_err())
+        .cloned()
+        .collect()
+}
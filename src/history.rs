@@ -0,0 +1,167 @@
+use druid::{Data, Env, Event, EventCtx, HotKey, SysMods, Widget};
+use druid::im::vector::Vector;
+use druid::widget::Controller;
+
+use crate::{Character, Profile, Prompt, UiState};
+use crate::prospect::Prospect;
+
+const MAX_UNDO_STEPS: usize = 100;
+
+#[derive(Clone, Data)]
+pub struct EditSnapshot {
+    pub(crate) profile: Profile,
+    pub(crate) characters: Vector<Character>,
+    pub(crate) prospects: Vector<Prospect>,
+}
+
+#[derive(Clone, Data, Default)]
+pub struct History {
+    undo: Vector<EditSnapshot>,
+    redo: Vector<EditSnapshot>,
+    saved: Option<EditSnapshot>,
+}
+
+impl History {
+    fn record(&mut self, snapshot: EditSnapshot) {
+        self.undo.push_back(snapshot);
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Remembers `snapshot` as what's on disk, for saves that finish after more edits were made.
+    pub fn mark_saved_as(&mut self, snapshot: EditSnapshot) {
+        self.saved = Some(snapshot);
+    }
+
+    /// What's on disk, as of the last load or save.
+    pub fn saved(&self) -> Option<&EditSnapshot> {
+        self.saved.as_ref()
+    }
+}
+
+impl UiState {
+    pub fn snapshot(&self) -> EditSnapshot {
+        EditSnapshot {
+            profile: self.profile.clone(),
+            characters: self.characters.clone(),
+            prospects: self.prospects.clone(),
+        }
+    }
+
+    fn apply_snapshot(&mut self, snapshot: EditSnapshot) {
+        self.profile = snapshot.profile;
+        self.characters = snapshot.characters;
+        self.prospects = snapshot.prospects;
+        // Undo and redo are handled outside the widget that keeps the raw JSON in step
+        self.sync_to_raw_json();
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.history.undo.pop_back() {
+            let current = self.snapshot();
+            self.history.redo.push_back(current);
+            self.apply_snapshot(snapshot);
+        }
+    }
+
+    /// Remembers the current profile and characters as what's on disk.
+    pub fn mark_saved(&mut self) {
+        self.history.saved = Some(self.snapshot());
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        match &self.history.saved {
+            Some(saved) => !saved.same(&self.snapshot()),
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(snapshot) = self.history.redo.pop_back() {
+            let current = self.snapshot();
+            self.history.undo.push_back(current);
+            self.apply_snapshot(snapshot);
+        }
+    }
+
+    /// Undoes the last step with what it changed in the change log, as Ctrl+Z does.
+    pub fn undo_logged(&mut self) {
+        let before = self.snapshot();
+        self.undo();
+        self.record_changes(&before);
+    }
+
+    pub fn redo_logged(&mut self) {
+        let before = self.snapshot();
+        self.redo();
+        self.record_changes(&before);
+    }
+
+    /// Whether closing the window should ask about unsaved changes first. There's no saving in safe
+    /// mode, so nothing to ask about.
+    pub fn asks_before_closing(&self) -> bool {
+        self.is_dirty() && self.config.confirm_unsaved && !self.config.safe_mode
+    }
+
+    /// Runs `edit` as one undo step with its changes in the change log, like the `UndoController`
+    /// does for edits made by widgets. For edits made from commands, which it doesn't see.
+    pub fn undoable(&mut self, edit: impl FnOnce(&mut UiState)) {
+        let before = self.snapshot();
+        edit(self);
+        if !self.snapshot().same(&before) {
+            self.record_changes(&before);
+            self.history.record(before);
+            self.sync_to_raw_json();
+        }
+    }
+}
+
+/// Records an undo step and change log entries whenever an event changes the profile, characters or
+/// prospects, handles the Ctrl+Z / Ctrl+Y shortcuts, and holds the window open while there are
+/// unsaved changes. Meant to wrap the root widget.
+pub struct UndoController;
+
+impl<W: Widget<UiState>> Controller<UiState, W> for UndoController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        if let Event::WindowCloseRequested = event {
+            if data.asks_before_closing() {
+                data.prompt = Some(Prompt::Close);
+                ctx.set_handled();
+                return;
+            }
+        }
+        if let Event::KeyDown(key) = event {
+            if HotKey::new(SysMods::Cmd, "z").matches(key) {
+                data.undo_logged();
+                ctx.set_handled();
+                return;
+            }
+            if HotKey::new(SysMods::Cmd, "y").matches(key) || HotKey::new(SysMods::CmdShift, "Z").matches(key) {
+                data.redo_logged();
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        let before = data.snapshot();
+        let history = data.history.clone();
+        child.event(ctx, event, data, env);
+        if !data.snapshot().same(&before) {
+            data.record_changes(&before);
+            // Undo/redo buttons move snapshots around themselves, so only record plain edits
+            if data.history.same(&history) {
+                data.history.record(before);
+            }
+        }
+    }
+}
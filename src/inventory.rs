@@ -0,0 +1,158 @@
+use std::error::Error;
+use std::path::Path;
+
+use im::Vector;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::files;
+
+pub const DEFAULT_INVENTORY_ID: &str = "MetaInventoryID_Main";
+pub const ITEMS_DATA_TABLE: &str = "D_ItemsStatic";
+pub const PROPERTY_STACK: &str = "ItemableStack";
+pub const PROPERTY_DURABILITY: &str = "Durability";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Inventory {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "Delta")]
+    pub delta: Vector<InventoryItem>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct InventoryItem {
+    #[serde(rename = "ItemStaticData")]
+    pub item_static_data: ItemStaticData,
+    #[serde(rename = "ItemDynamicData", default)]
+    pub item_dynamic_data: Vector<ItemProperty>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct ItemStaticData {
+    #[serde(rename = "RowName")]
+    pub row_name: String,
+    #[serde(rename = "DataTableName")]
+    pub data_table_name: String,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct ItemProperty {
+    #[serde(rename = "PropertyType")]
+    pub property_type: String,
+    #[serde(rename = "Value")]
+    pub value: f64,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ItemProperty {
+    pub fn new(property_type: &str, value: f64) -> ItemProperty {
+        ItemProperty {
+            property_type: property_type.to_string(),
+            value,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl InventoryItem {
+    /// A stack of `count` of an item, with nothing else set.
+    pub fn new(row_name: &str, count: f64) -> InventoryItem {
+        let mut item_dynamic_data = Vector::new();
+        item_dynamic_data.push_back(ItemProperty::new(PROPERTY_STACK, count));
+        InventoryItem {
+            item_static_data: ItemStaticData {
+                row_name: row_name.to_string(),
+                data_table_name: ITEMS_DATA_TABLE.to_string(),
+                extra: Map::new(),
+            },
+            item_dynamic_data,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Inventory {
+            id: DEFAULT_INVENTORY_ID.to_string(),
+            delta: Vector::new(),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Inventory {
+    pub fn load(path: &Path) -> Result<Inventory, Box<dyn Error>> {
+        files::read_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        files::write_json(path, self)
+    }
+
+    /// Reads whatever is left of an inventory file, fixing its ID and dropping any `Delta` entries
+    /// that can't be read, so a damaged inventory can be made loadable without losing the items that
+    /// are still intact. A file that isn't JSON at all comes back as an empty inventory.
+    pub fn repair(path: &Path) -> Inventory {
+        let mut raw = match files::read_json::<Map<String, Value>>(path) {
+            Ok(raw) => raw,
+            Err(_) => return Inventory::default(),
+        };
+        let delta = match raw.remove("Delta") {
+            Some(Value::Array(items)) => items.into_iter().filter_map(|x| serde_json::from_value(x).ok()).collect(),
+            _ => Vector::new(),
+        };
+        raw.remove("ID");
+
+        Inventory {
+            id: DEFAULT_INVENTORY_ID.to_string(),
+            delta,
+            extra: raw,
+        }
+    }
+
+    pub fn add_item(&mut self, row_name: &str, count: f64) {
+        self.delta.push_back(InventoryItem::new(row_name, count));
+    }
+
+    /// Adds `quantity` of an item, split into stacks of at most `max_stack` the way the game would
+    /// have them, each with `durability` if it's given. Returns how many stacks were added.
+    pub fn spawn_item(&mut self, row_name: &str, quantity: u32, max_stack: u32, durability: Option<f64>) -> usize {
+        let max_stack = max_stack.max(1);
+        let mut left = quantity;
+        let mut stacks = 0;
+        while left > 0 {
+            let count = left.min(max_stack);
+            self.add_item(row_name, count as f64);
+            if let (Some(durability), Some(item)) = (durability, self.delta.back_mut()) {
+                item.item_dynamic_data.push_back(ItemProperty::new(PROPERTY_DURABILITY, durability));
+            }
+            left -= count;
+            stacks += 1;
+        }
+
+        stacks
+    }
+
+    pub fn remove_item(&mut self, idx: usize) {
+        if idx < self.delta.len() {
+            self.delta.remove(idx);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use druid::Data;
+use druid::im::vector::Vector;
+use icarus_save::{Characters, CHARACTERS_FILE, PROFILE_FILE};
+use serde_json::{Deserializer, Value};
+
+use crate::{backup, files, Profile, UiState};
+
+/// How many places to try cutting a truncated file back to before giving up on closing it.
+const MAX_TRUNCATION_ATTEMPTS: usize = 1000;
+/// How much of the end of a repaired file to show, which is where any damage would have been.
+const PREVIEW_LENGTH: usize = 400;
+
+/// A proposed fix for a save file that can't be read. Nothing is written until the user agrees.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct Salvage {
+    #[data(eq)]
+    pub path: PathBuf,
+    /// What was done to get a readable file
+    pub method: String,
+    pub original_length: usize,
+    pub text: String,
+}
+
+impl Salvage {
+    /// The end of the repaired file.
+    pub fn preview(&self) -> String {
+        let start = self.text.char_indices().rev().nth(PREVIEW_LENGTH).map(|(idx, _)| idx).unwrap_or(0);
+        format!("...{}", &self.text[start..])
+    }
+}
+
+/// The first JSON value in `text`, without whatever follows it - typically the remains of an older,
+/// longer copy of the file that a write only partly replaced.
+fn strip_trailing_garbage(text: &str) -> Option<String> {
+    let mut stream = Deserializer::from_str(text).into_iter::<Value>();
+    match stream.next() {
+        Some(Ok(_)) if stream.byte_offset() < text.trim_end().len() => Some(text[..stream.byte_offset()].to_string()),
+        _ => None,
+    }
+}
+
+/// Closes the strings, arrays and objects a truncated file left open. The last element is often cut
+/// off part way through, so the file is cut back to the end of each complete element in turn, newest
+/// first, until one makes a file that passes `valid`.
+fn close_truncated(text: &str, valid: &dyn Fn(&str) -> bool) -> Option<String> {
+    // Places the file could be cut, with the brackets still open there
+    let mut cuts = Vec::new();
+    let mut open = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in text.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                open.push(if c == '{' { '}' } else { ']' });
+                cuts.push((idx + 1, open.clone()));
+            }
+            '}' | ']' => {
+                open.pop();
+                cuts.push((idx + 1, open.clone()));
+            }
+            ',' => cuts.push((idx, open.clone())),
+            _ => {}
+        }
+    }
+
+    cuts.into_iter().rev().take(MAX_TRUNCATION_ATTEMPTS).find_map(|(idx, open)| {
+        let closed = format!("{}{}", &text[..idx], open.iter().rev().collect::<String>());
+        if valid(&closed) { Some(closed) } else { None }
+    })
+}
+
+/// The newest backup of `path` that passes `valid`, with the backup's name.
+fn newest_backup(save_dir: &Path, path: &Path, valid: &dyn Fn(&str) -> bool) -> Result<Option<(String, String)>, Box<dyn Error>> {
+    let relative = path.strip_prefix(save_dir)?;
+    for backup in backup::list_backups(save_dir)? {
+        if let Ok(text) = fs::read_to_string(backup.path.join(relative)) {
+            if valid(&text) {
+                return Ok(Some((backup.name, text)));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// Works out the least destructive way to make `path` readable again: dropping anything after the
+/// JSON, closing off a truncated file, or failing those, going back to the newest good backup.
+fn salvage(save_dir: &Path, path: &Path, valid: &dyn Fn(&str) -> bool) -> Result<Salvage, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let (method, repaired) = if let Some(repaired) = strip_trailing_garbage(&text).filter(|x| valid(x)) {
+        ("Removed data after the end of the file".to_string(), repaired)
+    } else if let Some(repaired) = close_truncated(&text, valid) {
+        ("Closed off a truncated file - anything after the last complete entry is lost".to_string(), repaired)
+    } else if let Some((name, repaired)) = newest_backup(save_dir, path, valid)? {
+        (format!("Replaced with the copy in backup {} - changes since then are lost", name), repaired)
+    } else {
+        Err(format!("[{}] couldn't be repaired, and there's no readable backup of it", path.to_string_lossy()))?
+    };
+
+    Ok(Salvage { path: path.to_path_buf(), method, original_length: text.len(), text: repaired })
+}
+
+fn profile_readable(text: &str) -> bool {
+    serde_json::from_str::<Profile>(text).is_ok()
+}
+
+fn characters_readable(text: &str) -> bool {
+    serde_json::from_str::<Characters>(text).is_ok()
+}
+
+impl UiState {
+    /// Works out repairs for whichever of the profile and characters files can't be read, to be
+    /// shown to the user before anything is written.
+    pub fn plan_json_repair(&mut self) -> Result<(), Box<dyn Error>> {
+        // Not `self.profile_file`, as repairing is offered when the save couldn't be loaded
+        let profile_file = self.save_dir.join(PROFILE_FILE);
+        let characters_file = self.save_dir.join(CHARACTERS_FILE);
+        let mut salvages = Vector::new();
+        for (path, valid) in [(profile_file, profile_readable as fn(&str) -> bool), (characters_file, characters_readable)] {
+            if path.exists() && !valid(&fs::read_to_string(&path).unwrap_or_default()) {
+                salvages.push_back(salvage(&self.save_dir, &path, &valid)?);
+            }
+        }
+        if salvages.is_empty() {
+            Err("the profile and characters files can both be read, so there's nothing to repair")?
+        }
+        self.salvages = salvages;
+
+        Ok(())
+    }
+
+    /// Writes the planned repairs, after backing up the damaged files, then loads the save again.
+    pub fn apply_json_repair(&mut self) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        let paths = self.salvages.iter().map(|x| x.path.clone()).collect::<Vec<PathBuf>>();
+        backup::create_backup(&self.save_dir, &paths)?;
+        for salvage in self.salvages.iter() {
+            files::write_atomic(&salvage.path, salvage.text.as_bytes())?;
+        }
+        self.salvages.clear();
+        self.reload_from_disk();
+
+        Ok(())
+    }
+}
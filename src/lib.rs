@@ -0,0 +1,536 @@
+//! Reading and writing Icarus offline saves: the profile, the characters, and each character's
+//! inventory and loadout, along with backups of them. This is the save handling the Icarus Offline
+//! Character Editor is built on, without any of its window.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use icarus_save::numbers::Xp;
+//! use icarus_save::CharacterStore;
+//!
+//! let mut store = CharacterStore::load(Path::new("C:/Users/me/AppData/Local/Icarus/Saved/PlayerData/1234"))?;
+//! for character in store.characters.iter_mut() {
+//!     character.xp_debt = Xp(0);
+//! }
+//! store.profile.set_meta_resource("Credits", 500.0);
+//! store.save()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Fields the game adds that aren't known here are kept in each type's `extra` map and written
+//! back untouched. With the `gui` feature, the types also implement druid's `Data` and `Lens`.
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use im::Vector;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::inventory::Inventory;
+use crate::loadout::Loadout;
+use crate::numbers::{CosmeticIndex, Flag, Rank, Slot, Xp};
+use crate::prospect::Prospect;
+
+pub mod backup;
+pub mod files;
+pub mod inventory;
+pub mod loadout;
+pub mod numbers;
+pub mod prospect;
+pub mod validation;
+
+pub const PROFILE_FILE: &str = "Profile.json";
+pub const CHARACTERS_FILE: &str = "Characters.json";
+
+/// What the game writes for a character with nothing in their inventory.
+pub const DEFAULT_INVENTORY: &str = "{
+    \"ID\": \"MetaInventoryID_Main\",
+    \"Delta\": []
+}";
+
+/// The `Location` of a character that's on the orbital station rather than a prospect
+pub const STATION_LOCATION: &str = "Station";
+
+/// The contents of `Characters.json`: each character is stored as a JSON string of its own.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Characters {
+    #[serde(rename = "Characters.json")]
+    pub characters_json: Vector<String>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Character {
+    #[serde(rename = "CharacterName")]
+    pub character_name: String,
+    #[serde(rename = "ChrSlot")]
+    pub character_slot: Slot,
+    #[serde(rename = "XP")]
+    pub xp: Xp,
+    #[serde(rename = "XP_Debt")]
+    pub xp_debt: Xp,
+    #[serde(rename = "IsDead")]
+    pub is_dead: bool,
+    #[serde(rename = "IsAbandoned")]
+    pub is_abandoned: bool,
+    #[serde(rename = "LastProspectId")]
+    pub last_prospect_id: String,
+    #[serde(rename = "Location")]
+    pub location: String,
+    #[serde(rename = "UnlockedFlags")]
+    pub unlocked_flags: Vector<Flag>,
+    #[serde(rename = "MetaResources")]
+    pub meta_resources: Vector<MetaResources>,
+    #[serde(rename = "Cosmetic")]
+    pub cosmetics: Cosmetics,
+    #[serde(rename = "Talents")]
+    pub talents: Vector<Talent>,
+    /// Fields the editor doesn't know about, written back untouched
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(skip)]
+    pub inventory_path: PathBuf,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(skip)]
+    pub loadout_path: PathBuf,
+    #[serde(skip)]
+    pub inventory: Inventory,
+    /// Whether `inventory` was read from `inventory_path`. Inventories that couldn't be read aren't
+    /// written on save.
+    #[serde(skip)]
+    pub inventory_loaded: bool,
+    #[serde(skip)]
+    pub loadout: Loadout,
+    #[serde(skip)]
+    pub loadout_loaded: bool,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct MetaResources {
+    #[serde(rename = "MetaRow")]
+    pub meta_row: String,
+    #[serde(rename = "Count")]
+    pub count: f64,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Cosmetics {
+    #[serde(rename = "Customization_Head")]
+    pub customization_head: CosmeticIndex,
+    #[serde(rename = "Customization_Hair")]
+    pub customization_hair: CosmeticIndex,
+    #[serde(rename = "Customization_HairColor")]
+    pub customization_hair_color: CosmeticIndex,
+    #[serde(rename = "Customization_Body")]
+    pub customization_body: CosmeticIndex,
+    #[serde(rename = "Customization_BodyColor")]
+    pub customization_body_color: CosmeticIndex,
+    #[serde(rename = "Customization_SkinTone")]
+    pub customization_skin_tone: CosmeticIndex,
+    #[serde(rename = "Customization_HeadTattoo")]
+    pub customization_head_tattoo: CosmeticIndex,
+    #[serde(rename = "Customization_HeadScar")]
+    pub customization_head_scar: CosmeticIndex,
+    #[serde(rename = "Customization_HeadFacialHair")]
+    pub customization_head_facial_hair: CosmeticIndex,
+    #[serde(rename = "Customization_CapLogo")]
+    pub customization_cap_logo: CosmeticIndex,
+    #[serde(rename = "IsMale")]
+    pub is_male: bool,
+    #[serde(rename = "Customization_Voice")]
+    pub customization_voice: CosmeticIndex,
+    #[serde(rename = "Customization_EyeColor")]
+    pub customization_eye_color: CosmeticIndex,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A talent, blueprint, workshop item or prospect - they're all talents as far as the save files
+/// are concerned.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Talent {
+    #[serde(rename = "RowName")]
+    pub row_name: String,
+    #[serde(rename = "Rank")]
+    pub rank: Rank,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Talent {
+    pub fn new(row_name: &str, rank: i64) -> Talent {
+        Talent { row_name: row_name.to_owned(), rank: Rank(rank), extra: Map::new() }
+    }
+}
+
+/// The account-wide save in `Profile.json`: workshop unlocks, prospect unlocks and currencies.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Profile {
+    #[serde(rename = "UserID")]
+    pub user_id: String,
+    #[serde(rename = "MetaResources")]
+    pub meta_resources: Vector<MetaResources>,
+    #[serde(rename = "UnlockedFlags")]
+    pub unlocked_flags: Vector<Flag>,
+    #[serde(rename = "Talents")]
+    pub talents: Vector<Talent>,
+    /// Prospects the player has finished. Only written if there are any, as older saves don't have it.
+    #[serde(rename = "CompletedMissions", default, skip_serializing_if = "Vector::is_empty")]
+    pub completed_missions: Vector<String>,
+    #[serde(rename = "MissionBestTimes", default, skip_serializing_if = "Vector::is_empty")]
+    pub mission_best_times: Vector<MissionTime>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// The quickest a prospect has been finished, in seconds.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct MissionTime {
+    #[serde(rename = "MissionRow")]
+    pub mission_row: String,
+    #[serde(rename = "BestTime")]
+    pub best_time: f64,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Profile {
+    pub fn talent_rank(&self, row_name: &str) -> i64 {
+        self.talents.iter().find(|t| t.row_name == row_name).map(|t| t.rank.0).unwrap_or(0)
+    }
+
+    /// Sets the rank of a profile talent directly, without buying or refunding anything.
+    pub fn set_talent_rank(&mut self, row_name: &str, rank: i64) {
+        let rank = rank.max(0);
+        if rank == 0 {
+            self.talents.retain(|t| t.row_name != row_name);
+        } else if let Some(talent) = self.talents.iter_mut().find(|t| t.row_name == row_name) {
+            talent.rank = Rank(rank);
+        } else {
+            self.talents.push_back(Talent::new(row_name, rank));
+        }
+    }
+
+    pub fn meta_resource(&self, meta_row: &str) -> f64 {
+        self.meta_resources.iter().find(|x| x.meta_row == meta_row).map(|x| x.count).unwrap_or(0.0)
+    }
+
+    pub fn set_meta_resource(&mut self, meta_row: &str, count: f64) {
+        self.add_meta_resource(meta_row);
+        if let Some(resource) = self.meta_resources.iter_mut().find(|x| x.meta_row == meta_row) {
+            resource.count = count;
+        }
+    }
+
+    pub fn add_meta_resource(&mut self, meta_row: &str) {
+        if !self.meta_resources.iter().any(|x| x.meta_row == meta_row) {
+            self.meta_resources.push_back(MetaResources { meta_row: meta_row.to_string(), count: 0.0, extra: Map::new() });
+        }
+    }
+
+    pub fn remove_meta_resource(&mut self, idx: usize) {
+        if idx < self.meta_resources.len() {
+            self.meta_resources.remove(idx);
+        }
+    }
+}
+
+impl Character {
+    /// A level 1 character at the station, with the default appearance and an empty inventory and
+    /// loadout. Their files are written on the next save.
+    pub fn new(name: &str, slot: Slot, save_dir: &Path) -> Character {
+        let mut character = Character {
+            character_name: name.to_string(),
+            character_slot: slot,
+            xp: Xp(0),
+            xp_debt: Xp(0),
+            is_dead: false,
+            is_abandoned: false,
+            last_prospect_id: String::new(),
+            location: STATION_LOCATION.to_string(),
+            unlocked_flags: Vector::new(),
+            meta_resources: Vector::new(),
+            cosmetics: Cosmetics::default(),
+            talents: Vector::new(),
+            extra: Map::new(),
+            inventory_path: PathBuf::new(),
+            loadout_path: PathBuf::new(),
+            inventory: Inventory::default(),
+            inventory_loaded: true,
+            loadout: Loadout::default(),
+            loadout_loaded: true,
+        };
+        character.set_file_paths(save_dir);
+        character
+    }
+
+    pub fn level_to_max(&mut self) {
+        self.xp = Xp(99_999_999);
+    }
+
+    /// Takes the XP debt off the character's XP, so the numbers add up without the debt.
+    pub fn pay_xp_debt(&mut self) {
+        self.xp = Xp((self.xp.0 - self.xp_debt.0).max(0));
+        self.xp_debt = Xp(0);
+    }
+
+    pub fn talent_rank(&self, row_name: &str) -> i64 {
+        self.talents.iter().find(|t| t.row_name == row_name).map(|t| t.rank.0).unwrap_or(0)
+    }
+
+    /// The count of a meta resource the character holds themselves, apart from the profile's.
+    pub fn meta_resource(&self, meta_row: &str) -> f64 {
+        self.meta_resources.iter().find(|x| x.meta_row == meta_row).map(|x| x.count).unwrap_or(0.0)
+    }
+
+    /// The talent points removing `talents` would give back.
+    pub fn respec_refund(&self, talents: &Vector<String>) -> i64 {
+        self.talents.iter().filter(|t| talents.contains(&t.row_name)).map(|t| t.rank.0).sum()
+    }
+
+    pub fn remove_talents(&mut self, talents: &Vector<String>) {
+        self.talents.retain(|t| !talents.contains(&t.row_name));
+    }
+
+    /// Points the inventory and loadout paths at the files for the character's slot.
+    pub fn set_file_paths(&mut self, save_dir: &Path) {
+        self.inventory_path = save_dir.join("Inventory").join(format!("InventoryID_{}.json", self.character_slot));
+        self.loadout_path = save_dir.join("Loadout").join(format!("Slot_{}.json", self.character_slot));
+    }
+
+    /// Clears the abandoned and dead flags and fixes up the inventory and loadout files. The items
+    /// the character was carrying are kept unless `wipe_inventory` is set.
+    pub fn restore(&mut self, wipe_inventory: bool) -> Result<(), Box<dyn Error>> {
+        self.is_abandoned = false;
+        self.is_dead = false;
+
+        self.update_inventory(wipe_inventory)?;
+        self.update_loadout()?;
+
+        Ok(())
+    }
+
+    /// Forgets the prospect the character was last on, putting them back at the station, without
+    /// touching their inventory or anything else. The game won't send them back to it after this.
+    pub fn detach_from_prospect(&mut self) {
+        self.last_prospect_id = String::new();
+        self.location = STATION_LOCATION.to_string();
+    }
+
+    /// Brings a character stuck on a prospect (dead, abandoned or just unreachable) back to the
+    /// station.
+    pub fn rescue(&mut self, wipe_inventory: bool) -> Result<(), Box<dyn Error>> {
+        self.detach_from_prospect();
+
+        self.restore(wipe_inventory)
+    }
+
+    fn update_loadout(&mut self) -> Result<(), Box<dyn Error>> {
+        // Only the Valid flag is written here, to an empty loadout if the game never wrote one for
+        // this slot - any slot edits stay in memory until the next save
+        let mut loadout = if self.loadout_path.exists() { Loadout::load(&self.loadout_path)? } else { Loadout::default() };
+        loadout.valid = true;
+        loadout.save(&self.loadout_path)?;
+        if self.loadout_loaded {
+            self.loadout.valid = true;
+        } else {
+            // The file is readable now, so the editor can have it as the inventory does
+            self.loadout = loadout;
+            self.loadout_loaded = true;
+        }
+
+        Ok(())
+    }
+
+    fn update_inventory(&mut self, wipe: bool) -> Result<(), Box<dyn Error>> {
+        if wipe {
+            files::write_atomic(&self.inventory_path, DEFAULT_INVENTORY.as_bytes())?;
+            self.inventory = serde_json::from_str(DEFAULT_INVENTORY)?;
+        } else {
+            let inventory = Inventory::repair(&self.inventory_path);
+            inventory.save(&self.inventory_path)?;
+            self.inventory = inventory;
+        }
+        // Keep the in-memory copy in step, otherwise the next save would write the old items back
+        self.inventory_loaded = true;
+
+        Ok(())
+    }
+}
+
+/// Everything in a save folder: the profile, the characters with their inventories and loadouts,
+/// and the prospects.
+#[derive(Clone, Debug)]
+pub struct CharacterStore {
+    pub save_dir: PathBuf,
+    pub profile_file: PathBuf,
+    pub profile: Profile,
+    pub characters_file: PathBuf,
+    /// The characters, in the order `Characters.json` lists them, which `save` keeps
+    pub characters: Vector<Character>,
+    /// Fields of `Characters.json` other than the characters
+    pub characters_extra: Map<String, Value>,
+    /// Raw JSON of characters that couldn't be read, with where they were in `Characters.json`,
+    /// written back untouched in the same place on save
+    pub skipped_characters: Vector<(usize, String)>,
+    pub prospects: Vector<Prospect>,
+    /// Anything that went wrong reading the save that didn't stop it from loading
+    pub warnings: Vec<String>,
+}
+
+impl CharacterStore {
+    /// Loads the save in `save_dir`. A character that can't be read is an error.
+    pub fn load(save_dir: &Path) -> Result<CharacterStore, Box<dyn Error>> {
+        CharacterStore::load_with(save_dir, false)
+    }
+
+    /// Loads the save in `save_dir`. Characters that can't be read are an error, unless
+    /// `skip_broken` is set, in which case they're left out of `characters` but kept in the file.
+    pub fn load_with(save_dir: &Path, skip_broken: bool) -> Result<CharacterStore, Box<dyn Error>> {
+        let profile_file = save_dir.join(PROFILE_FILE);
+        let characters_file = save_dir.join(CHARACTERS_FILE);
+
+        if !profile_file.exists() {
+            Err(format!(
+                "[{}] does not exist - please open Icarus and start playing offline before running this tool",
+                profile_file.to_string_lossy(),
+            ))?
+        }
+
+        let profile: Profile = serde_json::from_str(&files::read_writable(&profile_file)?)
+            .map_err(|e| format!("Unable to read [{}]: {}", profile_file.to_string_lossy(), e))?;
+        let mut warnings = Vec::new();
+        warnings.extend(validation::unknown_fields_warning(&profile_file, "profile", &profile.extra));
+
+        // The game only writes Characters.json once the first character has been made
+        let chars: Characters = if characters_file.exists() {
+            serde_json::from_str(&files::read_writable(&characters_file)?)
+                .map_err(|e| format!("Unable to read [{}]: {}", characters_file.to_string_lossy(), e))?
+        } else {
+            Characters { characters_json: Vector::new(), extra: Map::new() }
+        };
+        let mut characters = Vec::<Character>::with_capacity(chars.characters_json.len());
+        let mut skipped_characters = Vector::new();
+        for (idx, c) in chars.characters_json.into_iter().enumerate() {
+            let description = validation::describe_character(idx, &c);
+            let mut character: Character = match serde_json::from_str(&c) {
+                Ok(character) => character,
+                Err(e) => {
+                    let message = format!("Unable to read {} in [{}]: {}", description, characters_file.to_string_lossy(), e);
+                    if !skip_broken {
+                        return Err(message.into());
+                    }
+                    warnings.push(format!("{} - it has been left out of the editor, but will be kept when saving", message));
+                    skipped_characters.push_back((idx, c));
+                    continue;
+                }
+            };
+            warnings.extend(validation::unknown_fields_warning(&characters_file, &description, &character.extra));
+            character.set_file_paths(save_dir);
+            match Inventory::load(&character.inventory_path) {
+                Ok(inventory) => {
+                    character.inventory = inventory;
+                    character.inventory_loaded = true;
+                }
+                Err(_) if !character.inventory_path.exists() => warnings.push(format!("{} has no inventory file [{}]", description, character.inventory_path.to_string_lossy())),
+                Err(e) => warnings.push(format!("Unable to read inventory [{}]: {}", character.inventory_path.to_string_lossy(), e)),
+            }
+            match Loadout::load(&character.loadout_path) {
+                Ok(loadout) => {
+                    character.loadout = loadout;
+                    character.loadout_loaded = true;
+                }
+                Err(_) if !character.loadout_path.exists() => warnings.push(format!("{} has no loadout file [{}]", description, character.loadout_path.to_string_lossy())),
+                Err(e) => warnings.push(format!("Unable to read loadout [{}]: {}", character.loadout_path.to_string_lossy(), e)),
+            }
+            characters.push(character);
+        }
+        let prospects = prospect::load_prospects(save_dir, &mut warnings)?;
+
+        Ok(CharacterStore {
+            save_dir: save_dir.to_path_buf(),
+            profile_file,
+            profile,
+            characters_file,
+            characters: Vector::from(characters),
+            characters_extra: chars.extra,
+            skipped_characters,
+            prospects,
+            warnings,
+        })
+    }
+
+    /// Writes the profile, the characters, every inventory and loadout that was loaded, and the
+    /// prospects. Each file is replaced atomically, but nothing is backed up first - see `backup`.
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        self.save_only(&self.files())
+    }
+
+    /// Like `save`, but only writes the files in `files`, leaving the rest as they are on disk.
+    pub fn save_only(&self, files: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        if files.contains(&self.profile_file) {
+            files::write_json(&self.profile_file, &self.profile)?;
+        }
+
+        if files.contains(&self.characters_file) {
+            let mut characters = Characters {
+                characters_json: Vector::new(),
+                extra: self.characters_extra.clone(),
+            };
+            for c in &self.characters {
+                characters.characters_json.push_back(serde_json::to_string(c)?);
+            }
+            // Skipped characters go back where they were, so the file's order doesn't change. If
+            // characters have been deleted since, they go in as near to it as there's room for
+            for (idx, c) in &self.skipped_characters {
+                let idx = (*idx).min(characters.characters_json.len());
+                characters.characters_json.insert(idx, c.clone());
+            }
+            files::write_json(&self.characters_file, &characters)?;
+        }
+
+        for c in self.characters.iter().filter(|c| c.inventory_loaded && files.contains(&c.inventory_path)) {
+            files::create_parent(&c.inventory_path)?;
+            c.inventory.save(&c.inventory_path)?;
+        }
+        for c in self.characters.iter().filter(|c| c.loadout_loaded && files.contains(&c.loadout_path)) {
+            files::create_parent(&c.loadout_path)?;
+            c.loadout.save(&c.loadout_path)?;
+        }
+        for p in self.prospects.iter().filter(|p| files.contains(&p.path)) {
+            p.save()?;
+        }
+
+        Ok(())
+    }
+
+    /// Every file `save` writes.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let mut files = vec![self.profile_file.clone(), self.characters_file.clone()];
+        for c in &self.characters {
+            files.push(c.inventory_path.clone());
+            files.push(c.loadout_path.clone());
+        }
+        files.extend(self.prospects.iter().map(|p| p.path.clone()));
+
+        files
+    }
+}
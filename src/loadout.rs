@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::path::Path;
+
+use im::Vector;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::files;
+use crate::inventory::InventoryItem;
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Loadout {
+    #[serde(rename = "Valid", default)]
+    pub valid: bool,
+    #[serde(rename = "Items", default)]
+    pub items: Vector<InventoryItem>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Loadout {
+    pub fn load(path: &Path) -> Result<Loadout, Box<dyn Error>> {
+        files::read_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        files::write_json(path, self)
+    }
+
+    /// A valid loadout with a stack of each `(row name, count)` in `items`, one per slot in order.
+    pub fn from_items(items: &[(&str, f64)]) -> Loadout {
+        Loadout {
+            valid: true,
+            items: items.iter().map(|(row_name, count)| InventoryItem::new(row_name, *count)).collect(),
+            extra: Map::new(),
+        }
+    }
+
+    pub fn swap_slots(&mut self, a: usize, b: usize) {
+        if a < self.items.len() && b < self.items.len() {
+            self.items.swap(a, b);
+        }
+    }
+
+    pub fn clear_slot(&mut self, idx: usize) {
+        if idx < self.items.len() {
+            self.items.remove(idx);
+        }
+    }
+}
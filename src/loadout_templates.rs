@@ -0,0 +1,99 @@
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use druid::Data;
+use druid::im::vector::Vector;
+
+use crate::config;
+use crate::files;
+use crate::loadout::Loadout;
+use crate::snapshots::RESERVED_CHARACTERS;
+
+const LOADOUT_TEMPLATE_DIR: &str = "loadout_templates";
+
+/// Loadouts that come with the editor, as `(name, [(item row name, count)])`, one item per slot.
+const BUILT_IN_LOADOUTS: [(&str, &[(&str, f64)]); 2] = [
+    ("Hunter Start", &[("Wood_Bow", 1.0), ("Stone_Arrow", 40.0), ("Stone_Knife", 1.0), ("Bandage", 5.0), ("Cooked_Meat", 10.0), ("Canteen", 1.0)]),
+    ("Miner Start", &[("Stone_Pickaxe", 1.0), ("Stone_Axe", 1.0), ("Torch", 1.0), ("Oxygen_Tank", 1.0), ("Canteen", 1.0), ("Cooked_Meat", 10.0)]),
+];
+
+/// A named loadout that can be given to any character. Built in templates have no file.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct LoadoutTemplate {
+    pub name: String,
+    #[data(eq)]
+    pub path: Option<PathBuf>,
+}
+
+impl LoadoutTemplate {
+    pub fn is_built_in(&self) -> bool {
+        self.path.is_none()
+    }
+
+    pub fn loadout(&self) -> Result<Loadout, Box<dyn Error>> {
+        match &self.path {
+            Some(path) => Loadout::load(path),
+            None => BUILT_IN_LOADOUTS.iter()
+                .find(|(name, _)| *name == self.name)
+                .map(|(_, items)| Loadout::from_items(items))
+                .ok_or_else(|| format!("there's no built in template called [{}]", self.name).into()),
+        }
+    }
+}
+
+fn template_dir() -> Result<PathBuf, Box<dyn Error>> {
+    config::config_dir().map(|x| x.join(LOADOUT_TEMPLATE_DIR)).ok_or_else(|| "Unable to find config directory".into())
+}
+
+/// Saves `loadout` as a template called `name`, replacing any of the user's own templates with
+/// that name. Built in templates can't be replaced.
+pub fn save_template(name: &str, loadout: &Loadout) -> Result<LoadoutTemplate, Box<dyn Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err("the template needs a name")?
+    }
+    if let Some(c) = name.chars().find(|c| RESERVED_CHARACTERS.contains(c)) {
+        Err(format!("template names can't contain [{}]", c))?
+    }
+    if BUILT_IN_LOADOUTS.iter().any(|(x, _)| x.eq_ignore_ascii_case(name)) {
+        Err(format!("[{}] is the name of a built in template", name))?
+    }
+
+    let dir = template_dir()?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", name));
+    files::write_json(&path, loadout)?;
+
+    Ok(LoadoutTemplate { name: name.to_string(), path: Some(path) })
+}
+
+/// The built in templates, followed by the user's own by name.
+pub fn list_templates() -> Result<Vector<LoadoutTemplate>, Box<dyn Error>> {
+    let mut templates = BUILT_IN_LOADOUTS.iter()
+        .map(|(name, _)| LoadoutTemplate { name: name.to_string(), path: None })
+        .collect::<Vector<LoadoutTemplate>>();
+    let dir = match config::config_dir().map(|x| x.join(LOADOUT_TEMPLATE_DIR)) {
+        Some(dir) if dir.exists() => dir,
+        _ => return Ok(templates),
+    };
+
+    let mut saved = Vec::new();
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.extension().map(|x| x == "json").unwrap_or(false) {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            saved.push(LoadoutTemplate { name, path: Some(path) });
+        }
+    }
+    saved.sort_by(|a, b| a.name.cmp(&b.name));
+    templates.extend(saved);
+
+    Ok(templates)
+}
+
+pub fn delete_template(template: &LoadoutTemplate) -> Result<(), Box<dyn Error>> {
+    let path = template.path.as_ref().ok_or("built in templates can't be deleted")?;
+    fs::remove_file(path)?;
+    Ok(())
+}
@@ -1,270 +1,883 @@
 // On Windows platform, don't show a console when opening the app.
 #![windows_subsystem = "windows"]
+// Checking that the save model can be sent to a background thread goes deep into im's vectors
+#![recursion_limit = "256"]
 
-use std::collections::{HashMap, HashSet};
+mod about;
+mod access;
+mod audit;
+mod autosave;
+mod backup_viewer;
+mod build_code;
+mod cli;
+mod compare;
+mod config;
+mod data_file;
+mod edits;
+mod export;
+mod game_data;
+mod guard;
+mod history;
+mod json_repair;
+mod loadout_templates;
+mod presets;
+mod raw_json;
+mod save_check;
+mod save_files;
+mod save_locations;
+mod search;
+mod shell;
+mod shortcuts;
+mod snapshots;
+mod steam_cloud;
+mod summary;
+mod tasks;
+mod theme;
+mod updates;
+mod watcher;
+mod wizard;
+mod xp_input;
+
+#[cfg(test)]
+#[path = "../tests/common/mod.rs"]
+mod test_save;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error;
-use std::fs::OpenOptions;
-use std::io::{Read, Write};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::SystemTime;
 
+use chrono::Local;
 use directories::BaseDirs;
-use druid::{AppLauncher, Data, Env, Lens, LensExt, Widget, WidgetExt, WindowDesc};
+use druid::{commands, lens, AppDelegate, AppLauncher, Application, Color, Command, Data, DelegateCtx, Env, Event, EventCtx, FileDialogOptions, FileInfo, FileSpec, Handled, Lens, LensExt, Menu, MenuItem, Selector, Target, Widget, WidgetExt, WindowDesc, WindowId};
 use druid::im::vector::Vector;
 use druid::text::ParseFormatter;
-use druid::widget::{Align, Button, Checkbox, CrossAxisAlignment, Flex, Label, LabelText, TabInfo, Tabs, TabsPolicy, TextBox, ValueTextBox, ViewSwitcher};
+use druid::widget::{Align, Button, Checkbox, Controller, CrossAxisAlignment, Either, Flex, Label, LabelText, LineBreaking, RadioGroup, Scroll, Spinner, StaticTabs, TabInfo, Tabs, TabsPolicy, TextBox, ValueTextBox, ViewSwitcher};
+use icarus_save::{backup, files, inventory, loadout, numbers, prospect, validation, Character, CharacterStore, Cosmetics, MetaResources, MissionTime, Profile, Talent, CHARACTERS_FILE, STATION_LOCATION};
 use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::access::AccessProblem;
+use crate::audit::AuditEntry;
+use crate::autosave::AutosaveController;
+use crate::backup::Backup;
+use crate::backup_viewer::BackupView;
+use crate::build_code::Build;
+use crate::config::{Config, ConfigController};
+use crate::edits::{Edit, ReviveDebt, EDIT};
+use crate::export::MergeStrategy;
+use crate::history::{History, UndoController};
+use crate::inventory::{Inventory, InventoryItem, ItemProperty, PROPERTY_DURABILITY, PROPERTY_STACK};
+use crate::json_repair::Salvage;
+use crate::loadout::Loadout;
+use crate::loadout_templates::LoadoutTemplate;
+use crate::numbers::{CosmeticIndex, Flag, Rank, Slot, Xp};
+use crate::presets::TalentPreset;
+use crate::prospect::{Prospect, ProspectInfo, ProspectSave};
+use crate::raw_json::{RawJsonSync, RawTarget};
+use crate::save_check::Issue;
+use crate::save_files::SaveChoice;
+use crate::shortcuts::{CharacterListShortcuts, CharacterTabShortcuts, ShortcutController, SELECT_CHARACTER_TAB};
+use crate::snapshots::Snapshot;
+use crate::steam_cloud::RemoteCache;
+use crate::theme::Theme;
+use crate::updates::Release;
+use crate::watcher::FileWatcher;
+use crate::wizard::WizardStep;
+use crate::xp_input::{XpErrorDelegate, XpField, XpFormatter, XpInputErrors, XP_INPUT_ERROR};
 
-const DEFAULT_INVENTORY: &'static str = "{
-    \"ID\": \"MetaInventoryID_Main\",
-    \"Delta\": []
-}";
 const TALENTS_RAW: &'static str = include_str!("talents.txt");
 const BLUEPRINTS_RAW: &'static str = include_str!("blueprints.txt");
 const PROSPECTS_RAW: &'static str = include_str!("prospects.txt");
+const PROSPECT_TIERS_RAW: &'static str = include_str!("prospect_tiers.txt");
 const WORKSHOP_ITEMS_RAW: &'static str = include_str!("workshop_items.txt");
+const WORKSHOP_COSTS_RAW: &'static str = include_str!("workshop_costs.txt");
+const WORKSHOP_EXOTIC_COSTS_RAW: &'static str = include_str!("workshop_exotic_costs.txt");
+const ITEMS_RAW: &'static str = include_str!("items.txt");
+const LEVELS_RAW: &'static str = include_str!("levels.txt");
+const DISPLAY_NAMES_RAW: &'static str = include_str!("display_names.txt");
+const TALENT_TREES_RAW: &'static str = include_str!("talent_trees.txt");
+const TALENT_PREREQUISITES_RAW: &'static str = include_str!("talent_prerequisites.txt");
+const FLAGS_RAW: &'static str = include_str!("flags.txt");
+const DLC_RAW: &'static str = include_str!("dlc.txt");
+const ITEM_CATEGORIES_RAW: &'static str = include_str!("item_categories.txt");
+const BLUEPRINT_LEVELS_RAW: &'static str = include_str!("blueprint_levels.txt");
+const COSMETICS_RAW: &'static str = include_str!("cosmetics.txt");
+const TALENT_POINTS_RAW: &'static str = include_str!("talent_points.txt");
+
+/// Every built in data file, by the name it can be overridden with in the data folder.
+const DATA_FILES: [(&'static str, &'static str); 18] = [
+    (game_data::TALENTS_FILE, TALENTS_RAW),
+    (game_data::BLUEPRINTS_FILE, BLUEPRINTS_RAW),
+    (game_data::PROSPECTS_FILE, PROSPECTS_RAW),
+    (game_data::PROSPECT_TIERS_FILE, PROSPECT_TIERS_RAW),
+    (game_data::WORKSHOP_ITEMS_FILE, WORKSHOP_ITEMS_RAW),
+    (game_data::WORKSHOP_COSTS_FILE, WORKSHOP_COSTS_RAW),
+    (game_data::WORKSHOP_EXOTIC_COSTS_FILE, WORKSHOP_EXOTIC_COSTS_RAW),
+    (game_data::ITEMS_FILE, ITEMS_RAW),
+    (game_data::LEVELS_FILE, LEVELS_RAW),
+    (game_data::DISPLAY_NAMES_FILE, DISPLAY_NAMES_RAW),
+    (game_data::TALENT_TREES_FILE, TALENT_TREES_RAW),
+    (game_data::TALENT_PREREQUISITES_FILE, TALENT_PREREQUISITES_RAW),
+    (game_data::FLAGS_FILE, FLAGS_RAW),
+    (game_data::DLC_FILE, DLC_RAW),
+    (game_data::ITEM_CATEGORIES_FILE, ITEM_CATEGORIES_RAW),
+    (game_data::BLUEPRINT_LEVELS_FILE, BLUEPRINT_LEVELS_RAW),
+    (game_data::COSMETICS_FILE, COSMETICS_RAW),
+    (game_data::TALENT_POINTS_FILE, TALENT_POINTS_RAW),
+];
+
+/// How far under a dedicated server's folder to look for player saves, enough to find them from
+/// its `Saved` folder.
+const SERVER_SEARCH_DEPTH: usize = 4;
 
 const META_RESOURCE_CREDITS: &'static str = "Credits";
 const META_RESOURCE_EXOTICS: &'static str = "Exotic1";
-const META_RESOURCE_RETRAINING_POINTS: &'static str = "Refund";
+const META_RESOURCE_RED_EXOTICS: &'static str = "Exotic_Red";
+/// Workshop refund vouchers, spent to get the credits and exotics back for a workshop item.
+const META_RESOURCE_REFUND_VOUCHERS: &'static str = "Refund";
+/// MetaRows offered by "Add resource", with a friendlier name to show in the table.
+const KNOWN_META_RESOURCES: [(&'static str, &'static str); 4] = [
+    (META_RESOURCE_CREDITS, "Credits"),
+    (META_RESOURCE_EXOTICS, "Exotics"),
+    (META_RESOURCE_RED_EXOTICS, "Red Exotics"),
+    (META_RESOURCE_REFUND_VOUCHERS, "Refund Vouchers"),
+];
+/// Each kind of exotic gets its own field, whether or not the profile has a row for it yet.
+const EXOTIC_META_RESOURCES: [(&'static str, &'static str); 2] = [
+    (META_RESOURCE_EXOTICS, "Exotics"),
+    (META_RESOURCE_RED_EXOTICS, "Red Exotics"),
+];
 
-const EXOTIC_MINING_FLAG: f64 = 17.0;
-const EXOTIC_EXTRACTION_FLAG: f64 = 18.0;
+/// Character fields that hold a level cap or prestige progress, with what each one controls. Any
+/// other number on the character with "level", "cap" or "prestige" in its name is shown too.
+const LEVEL_CAP_FIELDS: [(&'static str, &'static str); 5] = [
+    ("MaxLevel", "The highest level this character can reach. The game may cut it back to its own cap on load"),
+    ("LevelCap", "The highest level this character can reach. The game may cut it back to its own cap on load"),
+    ("PrestigeLevel", "How many times this character has been prestiged"),
+    ("PrestigeXP", "XP earned towards the next prestige"),
+    ("PrestigePoints", "Prestige points the character hasn't spent yet"),
+];
 
 lazy_static! {
-    static ref TALENT_LEVELS: HashMap<&'static str, f64> = build_map(TALENTS_RAW);
-    static ref TALENTS: HashSet<&'static str> = build_set(TALENTS_RAW);
-    static ref BLUEPRINTS: HashSet<&'static str> = build_set(BLUEPRINTS_RAW);
-    static ref PROSPECTS: HashSet<&'static str> = build_set(PROSPECTS_RAW);
-    static ref WORKSHOP_ITEMS: HashSet<&'static str> = build_set(WORKSHOP_ITEMS_RAW);
-}
-
-fn build_map(str: &'static str) -> HashMap<&'static str, f64> {
-    let mut map = HashMap::<&'static str, f64>::new();
-    for line in str.split("\n").into_iter() {
-        let parts = line.split(",").collect::<Vec<&'static str>>();
-        if parts.len() != 2 {
-            panic!("Unable to parse file - expected [{}] to split into 2, but got [{:?}] instead", line, parts);
-        }
-        let talent_name = parts[0];
-        let talent_max_level = if let Ok(parsed) = f64::from_str(parts[1]) {
-            parsed
-        } else {
-            panic!("Unable to parse [{}] as f64", parts[1])
-        };
-        map.insert(talent_name, talent_max_level);
+    // Files in the data folder (imported or added by hand) take the place of the built in lists
+    static ref TALENTS_DATA: &'static str = game_data::table(game_data::TALENTS_FILE, TALENTS_RAW);
+    static ref BLUEPRINTS_DATA: &'static str = game_data::table(game_data::BLUEPRINTS_FILE, BLUEPRINTS_RAW);
+    static ref PROSPECTS_DATA: &'static str = game_data::table(game_data::PROSPECTS_FILE, PROSPECTS_RAW);
+    static ref WORKSHOP_ITEMS_DATA: &'static str = game_data::table(game_data::WORKSHOP_ITEMS_FILE, WORKSHOP_ITEMS_RAW);
+    static ref PROSPECT_TIERS_DATA: &'static str = game_data::table(game_data::PROSPECT_TIERS_FILE, PROSPECT_TIERS_RAW);
+    static ref WORKSHOP_COSTS_DATA: &'static str = game_data::table(game_data::WORKSHOP_COSTS_FILE, WORKSHOP_COSTS_RAW);
+    static ref WORKSHOP_EXOTIC_COSTS_DATA: &'static str = game_data::table(game_data::WORKSHOP_EXOTIC_COSTS_FILE, WORKSHOP_EXOTIC_COSTS_RAW);
+    static ref ITEMS_DATA: &'static str = game_data::table(game_data::ITEMS_FILE, ITEMS_RAW);
+    static ref LEVELS_DATA: &'static str = game_data::table(game_data::LEVELS_FILE, LEVELS_RAW);
+    static ref DISPLAY_NAMES_DATA: &'static str = game_data::table(game_data::DISPLAY_NAMES_FILE, DISPLAY_NAMES_RAW);
+    static ref TALENT_TREES_DATA: &'static str = game_data::table(game_data::TALENT_TREES_FILE, TALENT_TREES_RAW);
+    static ref TALENT_PREREQUISITES_DATA: &'static str = game_data::table(game_data::TALENT_PREREQUISITES_FILE, TALENT_PREREQUISITES_RAW);
+    static ref FLAGS_DATA: &'static str = game_data::table(game_data::FLAGS_FILE, FLAGS_RAW);
+    static ref DLC_DATA: &'static str = game_data::table(game_data::DLC_FILE, DLC_RAW);
+    static ref ITEM_CATEGORIES_DATA: &'static str = game_data::table(game_data::ITEM_CATEGORIES_FILE, ITEM_CATEGORIES_RAW);
+    static ref BLUEPRINT_LEVELS_DATA: &'static str = game_data::table(game_data::BLUEPRINT_LEVELS_FILE, BLUEPRINT_LEVELS_RAW);
+    static ref COSMETICS_DATA: &'static str = game_data::table(game_data::COSMETICS_FILE, COSMETICS_RAW);
+    static ref TALENT_POINTS_DATA: &'static str = game_data::table(game_data::TALENT_POINTS_FILE, TALENT_POINTS_RAW);
+
+    static ref TALENT_LEVELS: HashMap<&'static str, i64> = build_map(game_data::TALENTS_FILE, &TALENTS_DATA).into_iter().map(|(k, v)| (k, v as i64)).collect();
+    static ref TALENTS: HashSet<&'static str> = build_set(game_data::TALENTS_FILE, &TALENTS_DATA);
+    static ref BLUEPRINTS: HashSet<&'static str> = build_set(game_data::BLUEPRINTS_FILE, &BLUEPRINTS_DATA);
+    static ref BLUEPRINT_LEVELS: HashMap<&'static str, f64> = build_map(game_data::BLUEPRINT_LEVELS_FILE, &BLUEPRINT_LEVELS_DATA);
+    static ref PROSPECTS: HashSet<&'static str> = build_set(game_data::PROSPECTS_FILE, &PROSPECTS_DATA);
+    static ref PROSPECT_TIERS: Vec<(usize, Vec<&'static str>)> = build_tiers(game_data::PROSPECT_TIERS_FILE, &PROSPECT_TIERS_DATA);
+    static ref WORKSHOP_ITEMS: HashSet<&'static str> = build_set(game_data::WORKSHOP_ITEMS_FILE, &WORKSHOP_ITEMS_DATA);
+    static ref ITEMS: Vec<&'static str> = build_sorted_list(game_data::ITEMS_FILE, &ITEMS_DATA);
+    static ref ITEM_STACK_SIZES: HashMap<&'static str, f64> = build_map(game_data::ITEMS_FILE, &ITEMS_DATA);
+    static ref ITEM_CATEGORIES: HashMap<&'static str, &'static str> = data_file::parse_pairs(game_data::ITEM_CATEGORIES_FILE, &ITEM_CATEGORIES_DATA).into_iter().collect();
+    static ref TALENT_TREES: HashMap<&'static str, &'static str> = data_file::parse_pairs(game_data::TALENT_TREES_FILE, &TALENT_TREES_DATA).into_iter().collect();
+    static ref TALENT_PREREQUISITES: HashMap<&'static str, Vec<&'static str>> = build_prerequisites(game_data::TALENT_PREREQUISITES_FILE, &TALENT_PREREQUISITES_DATA);
+    static ref TALENT_CATEGORIES: Vec<(&'static str, Vec<&'static str>)> = build_talent_categories();
+    static ref BLUEPRINT_CATEGORIES: Vec<(&'static str, Vec<&'static str>)> = build_categories(game_data::BLUEPRINTS_FILE, &BLUEPRINTS_DATA, "");
+    static ref WORKSHOP_CATEGORIES: Vec<(&'static str, Vec<&'static str>)> = build_categories(game_data::WORKSHOP_ITEMS_FILE, &WORKSHOP_ITEMS_DATA, "Workshop_");
+    static ref WORKSHOP_COSTS: HashMap<&'static str, f64> = build_map(game_data::WORKSHOP_COSTS_FILE, &WORKSHOP_COSTS_DATA);
+    static ref WORKSHOP_EXOTIC_COSTS: HashMap<&'static str, f64> = build_map(game_data::WORKSHOP_EXOTIC_COSTS_FILE, &WORKSHOP_EXOTIC_COSTS_DATA);
+    static ref LEVEL_XP: Vec<i64> = build_level_table(game_data::LEVELS_FILE, &LEVELS_DATA);
+    static ref TALENT_POINTS: HashMap<usize, i64> = build_talent_points(game_data::TALENT_POINTS_FILE, &TALENT_POINTS_DATA);
+    static ref DISPLAY_NAMES: HashMap<&'static str, &'static str> = build_names();
+    static ref FLAGS: Vec<(Flag, &'static str, &'static str)> = build_flags(game_data::FLAGS_FILE, &FLAGS_DATA);
+    static ref DLC: HashMap<&'static str, &'static str> = data_file::parse_pairs(game_data::DLC_FILE, &DLC_DATA).into_iter().collect();
+    static ref DLC_NAMES: Vec<&'static str> = build_dlc_names(game_data::DLC_FILE, &DLC_DATA);
+    static ref COSMETICS: HashMap<&'static str, Vec<(CosmeticIndex, &'static str)>> = build_cosmetics(game_data::COSMETICS_FILE, &COSMETICS_DATA);
+}
+
+/// Reads every data file, so any problems with them are known before the editor opens.
+fn check_data_files() -> Vec<String> {
+    lazy_static::initialize(&TALENT_LEVELS);
+    lazy_static::initialize(&TALENTS);
+    lazy_static::initialize(&BLUEPRINTS);
+    lazy_static::initialize(&BLUEPRINT_LEVELS);
+    lazy_static::initialize(&PROSPECTS);
+    lazy_static::initialize(&PROSPECT_TIERS);
+    lazy_static::initialize(&WORKSHOP_ITEMS);
+    lazy_static::initialize(&ITEMS);
+    lazy_static::initialize(&ITEM_STACK_SIZES);
+    lazy_static::initialize(&ITEM_CATEGORIES);
+    lazy_static::initialize(&WORKSHOP_COSTS);
+    lazy_static::initialize(&WORKSHOP_EXOTIC_COSTS);
+    lazy_static::initialize(&LEVEL_XP);
+    lazy_static::initialize(&TALENT_POINTS);
+    lazy_static::initialize(&DISPLAY_NAMES);
+    lazy_static::initialize(&TALENT_CATEGORIES);
+    lazy_static::initialize(&TALENT_PREREQUISITES);
+    lazy_static::initialize(&FLAGS);
+    lazy_static::initialize(&DLC);
+    lazy_static::initialize(&COSMETICS);
+
+    data_file::problems()
+}
+
+fn build_map(file: &str, str: &'static str) -> HashMap<&'static str, f64> {
+    data_file::parse(file, str).into_iter().map(|row| (row.name, row.value)).collect()
+}
+
+fn build_set(file: &str, str: &'static str) -> HashSet<&'static str> {
+    data_file::parse(file, str).into_iter().map(|row| row.name).collect()
+}
+
+/// Reads `display_names.txt`, along with any display names given in the third column of the other
+/// data files, which win over the names file.
+fn build_names() -> HashMap<&'static str, &'static str> {
+    let mut map = data_file::parse_pairs(game_data::DISPLAY_NAMES_FILE, &DISPLAY_NAMES_DATA).into_iter().collect::<HashMap<_, _>>();
+    let files: [(&str, &'static str); 5] = [
+        (game_data::TALENTS_FILE, &TALENTS_DATA),
+        (game_data::BLUEPRINTS_FILE, &BLUEPRINTS_DATA),
+        (game_data::WORKSHOP_ITEMS_FILE, &WORKSHOP_ITEMS_DATA),
+        (game_data::PROSPECTS_FILE, &PROSPECTS_DATA),
+        (game_data::ITEMS_FILE, &ITEMS_DATA),
+    ];
+    for (file, str) in files {
+        for row in data_file::parse(file, str) {
+            if let Some(display_name) = row.display_name {
+                map.insert(row.name, display_name);
+            }
+        }
     }
     map
 }
 
-fn build_set(str: &'static str) -> HashSet<&'static str> {
-    let mut set = HashSet::<&'static str>::new();
-    for line in str.split("\n").into_iter() {
-        let parts = line.split(",").collect::<Vec<&'static str>>();
-        if parts.len() != 2 {
-            panic!("Unable to parse file - expected [{}] to split into 2, but got [{:?}] instead", line, parts);
-        }
-        let talent_name = parts[0];
-        set.insert(talent_name);
-    }
-    set
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct Characters {
-    #[serde(rename = "Characters.json")]
-    pub characters_json: Vector<String>,
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct Character {
-    #[serde(rename = "CharacterName")]
-    pub character_name: String,
-    #[serde(rename = "ChrSlot")]
-    pub character_slot: f64,
-    #[serde(rename = "XP")]
-    pub xp: f64,
-    #[serde(rename = "XP_Debt")]
-    pub xp_debt: f64,
-    #[serde(rename = "IsDead")]
-    pub is_dead: bool,
-    #[serde(rename = "IsAbandoned")]
-    pub is_abandoned: bool,
-    #[serde(rename = "LastProspectId")]
-    pub last_prospect_id: String,
-    #[serde(rename = "Location")]
-    pub location: String,
-    #[serde(rename = "UnlockedFlags")]
-    pub unlocked_flags: Vector<f64>,
-    #[serde(rename = "MetaResources")]
-    pub meta_resources: Vector<MetaResources>,
-    #[serde(rename = "Cosmetic")]
-    pub cosmetics: Cosmetics,
-    #[serde(rename = "Talents")]
-    pub talents: Vector<Talent>,
-    #[data(eq)]
-    #[serde(skip)]
-    inventory_path: PathBuf,
-    #[data(eq)]
-    #[serde(skip)]
-    loadout_path: PathBuf,
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct MetaResources {
-    #[serde(rename = "MetaRow")]
-    pub meta_row: String,
-    #[serde(rename = "Count")]
-    pub count: f64,
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct Cosmetics {
-    #[serde(rename = "Customization_Head")]
-    customization_head: f64,
-    #[serde(rename = "Customization_Hair")]
-    customization_hair: f64,
-    #[serde(rename = "Customization_HairColor")]
-    customization_hair_color: f64,
-    #[serde(rename = "Customization_Body")]
-    customization_body: f64,
-    #[serde(rename = "Customization_BodyColor")]
-    customization_body_color: f64,
-    #[serde(rename = "Customization_SkinTone")]
-    customization_skin_tone: f64,
-    #[serde(rename = "Customization_HeadTattoo")]
-    customization_head_tattoo: f64,
-    #[serde(rename = "Customization_HeadScar")]
-    customization_head_scar: f64,
-    #[serde(rename = "Customization_HeadFacialHair")]
-    customization_head_facial_hair: f64,
-    #[serde(rename = "Customization_CapLogo")]
-    customization_cap_logo: f64,
-    #[serde(rename = "IsMale")]
-    is_male: bool,
-    #[serde(rename = "Customization_Voice")]
-    customization_voice: f64,
-    #[serde(rename = "Customization_EyeColor")]
-    customization_eye_color: f64,
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct Talent {
-    #[serde(rename = "RowName")]
-    pub row_name: String,
-    #[serde(rename = "Rank")]
-    pub rank: f64,
-}
-
-#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Data, Lens)]
-struct Profile {
-    #[serde(rename = "UserID")]
-    pub user_id: String,
-    #[serde(rename = "MetaResources")]
-    pub meta_resources: Vector<MetaResources>,
-    #[serde(rename = "UnlockedFlags")]
-    pub unlocked_flags: Vector<f64>,
-    #[serde(rename = "Talents")]
-    pub talents: Vector<Talent>,
-}
-
-impl Profile {
+/// The name to show for a talent, blueprint, workshop item, prospect or item, falling back to the raw
+/// row name for rows the names file doesn't cover.
+fn display_name(row_name: &str) -> &str {
+    DISPLAY_NAMES.get(row_name).copied().unwrap_or(row_name)
+}
+
+/// The item spawner's heading for an item, "Other" for items `item_categories.txt` doesn't list.
+fn item_category(row_name: &str) -> &'static str {
+    ITEM_CATEGORIES.get(row_name).copied().unwrap_or("Other")
+}
+
+/// Whether a row matches the search box, by its display name or its raw row name.
+fn row_matches(search: &str, row_name: &str) -> bool {
+    search::matches(search, row_name) || search::matches(search, display_name(row_name))
+}
+
+fn build_sorted_list(file: &str, str: &'static str) -> Vec<&'static str> {
+    let mut list = build_set(file, str).into_iter().collect::<Vec<&'static str>>();
+    list.sort_unstable();
+    list
+}
+
+/// Turns a `level,total XP` file into a list of the total XP needed for each level, starting at
+/// level 1.
+fn build_level_table(file: &str, str: &'static str) -> Vec<i64> {
+    let mut levels = Vec::new();
+    for (level, xp) in build_map(file, str) {
+        match usize::from_str(level) {
+            Ok(level) => levels.push((level, xp as i64)),
+            Err(_) => data_file::report(format!("{}: [{}] is not a level number", file, level)),
+        }
+    }
+    levels.sort_unstable_by_key(|(level, _)| *level);
+    // The table is indexed by level, so a missing level would move every level after it down one
+    let mut expected = 1;
+    for (level, _) in &levels {
+        if *level == expected + 1 {
+            data_file::report(format!("{}: level {} is missing", file, expected));
+        } else if *level > expected {
+            data_file::report(format!("{}: levels {} to {} are missing", file, expected, level - 1));
+        }
+        expected = level + 1;
+    }
+    levels.into_iter().map(|(_, xp)| xp).collect()
+}
+
+/// Reads a `level,points` file into the talent points each level gives.
+fn build_talent_points(file: &str, str: &'static str) -> HashMap<usize, i64> {
+    let mut points = HashMap::new();
+    for (level, value) in build_map(file, str) {
+        match usize::from_str(level) {
+            Ok(level) => { points.insert(level, value as i64); }
+            Err(_) => data_file::report(format!("{}: [{}] is not a level number", file, level)),
+        }
+    }
+    points
+}
+
+/// Groups the rows in a `row name,tier` file by tier, lowest first.
+fn build_tiers(file: &str, str: &'static str) -> Vec<(usize, Vec<&'static str>)> {
+    let mut tiers = BTreeMap::<usize, Vec<&'static str>>::new();
+    for (row_name, tier) in build_map(file, str) {
+        tiers.entry(tier as usize).or_default().push(row_name);
+    }
+    for row_names in tiers.values_mut() {
+        row_names.sort_unstable();
+    }
+    tiers.into_iter().collect()
+}
+
+/// Reads a `flag,description` file, in flag order. The description can be followed by `,` and what
+/// the flag controls.
+fn build_flags(file: &str, str: &'static str) -> Vec<(Flag, &'static str, &'static str)> {
+    let mut flags = Vec::new();
+    for (flag, text) in data_file::parse_pairs(file, str) {
+        let (description, help) = text.split_once(',').map(|(x, y)| (x.trim(), y.trim())).unwrap_or((text, ""));
+        match Flag::from_str(flag) {
+            Ok(flag) => flags.push((flag, description, help)),
+            Err(_) => data_file::report(format!("{}: [{}] is not a flag number", file, flag)),
+        }
+    }
+    flags.sort_by_key(|(flag, _, _)| *flag);
+    flags
+}
+
+/// Each DLC in the DLC file, in the order they first appear.
+fn build_dlc_names(file: &str, str: &'static str) -> Vec<&'static str> {
+    let mut names = Vec::new();
+    for (_, dlc) in data_file::parse_pairs(file, str) {
+        if !names.contains(&dlc) {
+            names.push(dlc);
+        }
+    }
+    names
+}
+
+/// Reads an `option,index,name` cosmetics file into each option's choices, in index order.
+fn build_cosmetics(file: &str, str: &'static str) -> HashMap<&'static str, Vec<(CosmeticIndex, &'static str)>> {
+    let mut options = HashMap::<&'static str, Vec<(CosmeticIndex, &'static str)>>::new();
+    for row in data_file::parse(file, str) {
+        match row.display_name {
+            Some(name) if row.value >= 0.0 && row.value.fract() == 0.0 => options.entry(row.name).or_default().push((CosmeticIndex(row.value as u32), name)),
+            _ => data_file::report(format!("{}: [{}] needs a whole number index and a name", file, row.name)),
+        }
+    }
+    for choices in options.values_mut() {
+        choices.sort_by_key(|(index, _)| *index);
+    }
+    options
+}
+
+/// The name of choice `index` of a cosmetic option, or its number if the cosmetics file doesn't
+/// list it.
+#[cfg(feature = "full")]
+fn cosmetic_name(option: &str, index: CosmeticIndex) -> String {
+    COSMETICS.get(option)
+        .and_then(|choices| choices.iter().find(|(x, _)| *x == index))
+        .map(|(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("Unknown ({})", index))
+}
+
+/// Whether a row belongs to one of the `hidden` DLC.
+fn dlc_hidden(hidden: &Vector<String>, row_name: &str) -> bool {
+    DLC.get(row_name).map(|dlc| hidden.iter().any(|x| x == dlc)).unwrap_or(false)
+}
+
+/// Reads a `talent,required talent` file into the talents each talent needs.
+fn build_prerequisites(file: &str, str: &'static str) -> HashMap<&'static str, Vec<&'static str>> {
+    let mut map = HashMap::<&'static str, Vec<&'static str>>::new();
+    for (talent, required) in data_file::parse_pairs(file, str) {
+        map.entry(talent).or_default().push(required);
+    }
+    map
+}
+
+/// Groups talents by the tree `talent_trees.txt` puts them in, falling back to the start of the row
+/// name like other categories.
+fn build_talent_categories() -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut trees = BTreeMap::<&'static str, Vec<&'static str>>::new();
+    for (category, row_names) in build_categories(game_data::TALENTS_FILE, &TALENTS_DATA, "") {
+        for row_name in row_names {
+            let tree = TALENT_TREES.get(row_name).copied().unwrap_or(category);
+            trees.entry(tree).or_default().push(row_name);
+        }
+    }
+    for row_names in trees.values_mut() {
+        row_names.sort_unstable();
+    }
+    trees.into_iter().collect()
+}
+
+/// Groups the rows in a data file by the part of the row name before the first underscore, once
+/// `prefix` is removed, e.g. `Bow_Accuracy` is in the `Bow` category.
+fn build_categories(file: &str, str: &'static str, prefix: &str) -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut categories = BTreeMap::<&'static str, Vec<&'static str>>::new();
+    for row_name in build_sorted_list(file, str) {
+        let name = row_name.strip_prefix(prefix).unwrap_or(row_name);
+        let category = name.split('_').next().unwrap_or(name);
+        categories.entry(category).or_default().push(row_name);
+    }
+    categories.into_iter().collect()
+}
+
+/// Profile edits that need the editor's data files.
+trait ProfileEdits {
+    fn unlock_all_prospects(&mut self);
+    /// Profile talents that aren't a known prospect or workshop item.
+    fn other_talents(&self) -> Vector<String>;
+    /// What the unlocked workshop items would have cost to buy, as (credits, exotics).
+    fn workshop_spent(&self) -> (f64, f64);
+    fn unlock_prospects_up_to_tier(&mut self, max_tier: usize);
+    fn unlock_all_workshop_items(&mut self);
+}
+
+impl ProfileEdits for Profile {
     fn unlock_all_prospects(&mut self) {
         self.talents.retain(|t| !PROSPECTS.contains(t.row_name.as_str()));
         for t in PROSPECTS.iter() {
-            self.talents.push_back(Talent{row_name: (*t).to_owned(), rank: 1.0 })
+            self.talents.push_back(Talent::new(t, 1))
+        }
+    }
+
+    fn other_talents(&self) -> Vector<String> {
+        self.talents.iter()
+            .map(|t| t.row_name.clone())
+            .filter(|x| !PROSPECTS.contains(x.as_str()) && !WORKSHOP_ITEMS.contains(x.as_str()))
+            .collect()
+    }
+
+    fn workshop_spent(&self) -> (f64, f64) {
+        self.talents.iter()
+            .filter(|t| t.rank.0 >= 1 && WORKSHOP_ITEMS.contains(t.row_name.as_str()))
+            .map(|t| workshop_cost(&t.row_name))
+            .fold((0.0, 0.0), |(credits, exotics), (c, e)| (credits + c, exotics + e))
+    }
+
+    fn unlock_prospects_up_to_tier(&mut self, max_tier: usize) {
+        for (_, row_names) in PROSPECT_TIERS.iter().filter(|(tier, _)| *tier <= max_tier) {
+            for row_name in row_names {
+                UnlockLens { row_name }.put(&mut self.talents, true);
+            }
         }
     }
 
     fn unlock_all_workshop_items(&mut self) {
         self.talents.retain(|t| !WORKSHOP_ITEMS.contains(t.row_name.as_str()));
         for t in WORKSHOP_ITEMS.iter() {
-            self.talents.push_back(Talent{row_name: (*t).to_owned(), rank: 1.0 })
+            self.talents.push_back(Talent::new(t, 1))
         }
     }
 }
 
-struct MetaResourceLens {
-    resource_name: String,
+struct FlagLens {
+    flag: Flag,
+}
+
+impl Lens<Vector<Flag>, bool> for FlagLens {
+    fn with<V, F: FnOnce(&bool) -> V>(&self, data: &Vector<Flag>, f: F) -> V {
+        f(&data.contains(&self.flag))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut Vector<Flag>, f: F) -> V {
+        let mut flag = data.contains(&self.flag);
+        let v = f(&mut flag);
+        if flag {
+            if !data.contains(&self.flag) { data.push_back(self.flag) };
+        } else {
+            data.retain(|x| *x != self.flag);
+        }
+
+        v
+    }
 }
 
-impl Lens<Vector<MetaResources>, MetaResources> for MetaResourceLens {
-    fn with<V, F: FnOnce(&MetaResources) -> V>(&self, data: &Vector<MetaResources>, f: F) -> V {
-        if let Some(resource) = data.iter().find(|x| x.meta_row == self.resource_name) {
-            f(resource)
+/// Exposes a single `ItemDynamicData` property as a number. Properties that aren't present read as
+/// `default`, and are only added to the item once they're set to something else.
+pub struct ItemPropertyLens {
+    pub property: &'static str,
+    pub default: f64,
+}
+
+impl Lens<Vector<ItemProperty>, f64> for ItemPropertyLens {
+    fn with<V, F: FnOnce(&f64) -> V>(&self, data: &Vector<ItemProperty>, f: F) -> V {
+        if let Some(property) = data.iter().find(|x| x.property_type == self.property) {
+            f(&property.value)
         } else {
-            f(&MetaResources{ meta_row: self.resource_name.to_string(), count: 0.0 })
+            f(&self.default)
         }
     }
 
-    fn with_mut<V, F: FnOnce(&mut MetaResources) -> V>(&self, data: &mut Vector<MetaResources>, f: F) -> V {
-        if let Some(resource) = data.iter_mut().find(|x| x.meta_row == self.resource_name) {
-            f(resource)
+    fn with_mut<V, F: FnOnce(&mut f64) -> V>(&self, data: &mut Vector<ItemProperty>, f: F) -> V {
+        if let Some(property) = data.iter_mut().find(|x| x.property_type == self.property) {
+            f(&mut property.value)
         } else {
-            data.push_back(MetaResources {
-                meta_row: self.resource_name.clone(),
-                count: 0.0,
-            });
-            f(data.back_mut().expect("Cannot be empty as we just pushed an object into the vec..."))
+            let mut value = self.default;
+            let v = f(&mut value);
+            if !value.same(&self.default) {
+                data.push_back(ItemProperty::new(self.property, value));
+            }
+
+            v
         }
     }
 }
 
-struct FlagLens {
-    flag: f64,
+/// The count of a MetaResources row, reading as 0 if the profile doesn't have it. The row is only
+/// added once the count is changed.
+struct MetaResourceLens {
+    meta_row: &'static str,
 }
 
-impl Lens<Vector<f64>, bool> for FlagLens {
-    fn with<V, F: FnOnce(&bool) -> V>(&self, data: &Vector<f64>, f: F) -> V {
-        f(&data.contains(&self.flag))
+impl Lens<Profile, f64> for MetaResourceLens {
+    fn with<V, F: FnOnce(&f64) -> V>(&self, data: &Profile, f: F) -> V {
+        f(&data.meta_resource(self.meta_row))
     }
 
-    fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut Vector<f64>, f: F) -> V {
-        let mut flag = data.contains(&self.flag);
-        let v = f(&mut flag);
-        if flag {
-            if !data.contains(&self.flag) { data.push_back(self.flag) };
+    fn with_mut<V, F: FnOnce(&mut f64) -> V>(&self, data: &mut Profile, f: F) -> V {
+        let before = data.meta_resource(self.meta_row);
+        let mut count = before;
+        let v = f(&mut count);
+        if count != before {
+            data.set_meta_resource(self.meta_row, count);
+        }
+
+        v
+    }
+}
+
+/// Whether a row is in a list of row names, such as the talents marked for removal in a respec plan
+/// or the completed prospects.
+struct ListedLens {
+    row_name: &'static str,
+}
+
+impl Lens<Vector<String>, bool> for ListedLens {
+    fn with<V, F: FnOnce(&bool) -> V>(&self, data: &Vector<String>, f: F) -> V {
+        f(&data.iter().any(|x| x == self.row_name))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut Vector<String>, f: F) -> V {
+        let mut marked = data.iter().any(|x| x == self.row_name);
+        let v = f(&mut marked);
+        if marked {
+            if !data.iter().any(|x| x == self.row_name) { data.push_back(self.row_name.to_string()) };
         } else {
-            data.retain(|x| *x != self.flag);
+            data.retain(|x| x != self.row_name);
+        }
+
+        v
+    }
+}
+
+/// A prospect's best time in seconds, reading as 0 if it hasn't got one. Setting it to 0 removes it.
+struct MissionTimeLens {
+    row_name: &'static str,
+}
+
+impl Lens<Vector<MissionTime>, f64> for MissionTimeLens {
+    fn with<V, F: FnOnce(&f64) -> V>(&self, data: &Vector<MissionTime>, f: F) -> V {
+        f(&data.iter().find(|x| x.mission_row == self.row_name).map(|x| x.best_time).unwrap_or(0.0))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut f64) -> V>(&self, data: &mut Vector<MissionTime>, f: F) -> V {
+        let before = data.iter().find(|x| x.mission_row == self.row_name).map(|x| x.best_time).unwrap_or(0.0);
+        let mut best_time = before;
+        let v = f(&mut best_time);
+        if best_time != before {
+            if best_time <= 0.0 {
+                data.retain(|x| x.mission_row != self.row_name);
+            } else if let Some(existing) = data.iter_mut().find(|x| x.mission_row == self.row_name) {
+                existing.best_time = best_time;
+            } else {
+                data.push_back(MissionTime { mission_row: self.row_name.to_string(), best_time, extra: Map::new() });
+            }
+        }
+
+        v
+    }
+}
+
+/// Views a character's XP as a level. Setting a level sets the XP needed to reach it.
+struct LevelLens;
+
+impl Lens<Character, usize> for LevelLens {
+    fn with<V, F: FnOnce(&usize) -> V>(&self, data: &Character, f: F) -> V {
+        f(&data.level())
+    }
+
+    fn with_mut<V, F: FnOnce(&mut usize) -> V>(&self, data: &mut Character, f: F) -> V {
+        let mut level = data.level();
+        let v = f(&mut level);
+        if level != data.level() {
+            data.set_level(level);
+        }
+
+        v
+    }
+}
+
+/// The character's level cap and prestige fields, known or not, with what each one controls.
+fn level_cap_fields(c: &Character) -> Vec<(String, &'static str)> {
+    c.extra.iter()
+        .filter(|(_, value)| value.is_number())
+        .filter_map(|(key, _)| match LEVEL_CAP_FIELDS.iter().find(|(known, _)| known == key) {
+            Some((_, help)) => Some((key.clone(), *help)),
+            None if ["level", "cap", "prestige"].iter().any(|x| key.to_lowercase().contains(x)) => {
+                Some((key.clone(), "Not a field the editor knows - change it with care"))
+            }
+            None => None,
+        })
+        .collect()
+}
+
+/// A number kept in a character's unknown fields. Whole numbers are written back without a
+/// decimal point, as the game writes them.
+struct ExtraNumberLens {
+    key: String,
+}
+
+impl Lens<Character, f64> for ExtraNumberLens {
+    fn with<V, F: FnOnce(&f64) -> V>(&self, data: &Character, f: F) -> V {
+        f(&data.extra.get(&self.key).and_then(|x| x.as_f64()).unwrap_or(0.0))
+    }
+
+    fn with_mut<V, F: FnOnce(&mut f64) -> V>(&self, data: &mut Character, f: F) -> V {
+        let old = data.extra.get(&self.key).and_then(|x| x.as_f64()).unwrap_or(0.0);
+        let mut number = old;
+        let v = f(&mut number);
+        if number != old && number.is_finite() {
+            let value = if number.fract() == 0.0 { Value::from(number as i64) } else { Value::from(number) };
+            data.extra.insert(self.key.clone(), value);
+        }
+
+        v
+    }
+}
+
+/// What a workshop item costs to unlock, as (credits, exotics).
+fn workshop_cost(row_name: &str) -> (f64, f64) {
+    (WORKSHOP_COSTS.get(row_name).copied().unwrap_or(0.0), WORKSHOP_EXOTIC_COSTS.get(row_name).copied().unwrap_or(0.0))
+}
+
+/// Unlocks a workshop item. When purchasing, unlocking costs the item's credits and exotics (and
+/// can't be done without enough of both). Locking it again gives nothing back, since it may never
+/// have been paid for; refunds are what the workshop refund vouchers are for.
+struct WorkshopItemLens {
+    row_name: &'static str,
+}
+
+impl Lens<UiState, bool> for WorkshopItemLens {
+    fn with<V, F: FnOnce(&bool) -> V>(&self, data: &UiState, f: F) -> V {
+        UnlockLens { row_name: self.row_name }.with(&data.profile.talents, f)
+    }
+
+    fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut UiState, f: F) -> V {
+        let unlocked = UnlockLens { row_name: self.row_name }.get(&data.profile.talents);
+        let mut flag = unlocked;
+        let v = f(&mut flag);
+        if flag != unlocked {
+            let credits = data.profile.meta_resource(META_RESOURCE_CREDITS);
+            let exotics = data.profile.meta_resource(META_RESOURCE_EXOTICS);
+            let (credit_cost, exotic_cost) = workshop_cost(self.row_name);
+            if !data.purchase_with_credits {
+                UnlockLens { row_name: self.row_name }.put(&mut data.profile.talents, flag);
+            } else if flag && credits >= credit_cost && exotics >= exotic_cost {
+                data.profile.set_meta_resource(META_RESOURCE_CREDITS, credits - credit_cost);
+                if exotic_cost > 0.0 {
+                    data.profile.set_meta_resource(META_RESOURCE_EXOTICS, exotics - exotic_cost);
+                }
+                UnlockLens { row_name: self.row_name }.put(&mut data.profile.talents, true);
+            } else if !flag {
+                UnlockLens { row_name: self.row_name }.put(&mut data.profile.talents, false);
+            }
         }
 
         v
     }
 }
 
-struct ProspectLens {
-    prospect: &'static str,
+struct UnlockLens {
+    row_name: &'static str,
+}
+
+impl UnlockLens {
+    /// Any rank above 0 counts as unlocked, whatever rank the row was written with.
+    fn is_unlock(&self, talent: &Talent) -> bool {
+        talent.row_name == self.row_name && talent.rank > Rank(0)
+    }
 }
 
-impl Lens<Vector<Talent>, bool> for ProspectLens {
+impl Lens<Vector<Talent>, bool> for UnlockLens {
     fn with<V, F: FnOnce(&bool) -> V>(&self, data: &Vector<Talent>, f: F) -> V {
-        f(&data.contains(&Talent { row_name: self.prospect.into(), rank: 1.0 }))
+        f(&data.iter().any(|x| self.is_unlock(x)))
     }
 
     fn with_mut<V, F: FnOnce(&mut bool) -> V>(&self, data: &mut Vector<Talent>, f: F) -> V {
-        let talent = Talent { row_name: self.prospect.into(), rank: 1.0 };
-        let mut flag = data.contains(&talent);
+        let unlocked = data.iter().any(|x| self.is_unlock(x));
+        let mut flag = unlocked;
         let v = f(&mut flag);
-        if flag {
-            if !data.contains(&talent) { data.push_back(talent) };
-        } else {
-            data.retain(|x| *x != talent);
+        if flag && !unlocked {
+            // A row left at rank 0 is reused, so the save never ends up with the same row twice
+            match data.iter_mut().find(|x| x.row_name == self.row_name) {
+                Some(row) => row.rank = Rank(1),
+                None => data.push_back(Talent::new(self.row_name, 1)),
+            }
+        } else if !flag {
+            data.retain(|x| x.row_name != self.row_name);
         }
 
         v
     }
 }
 
-impl Character {
-    fn level_to_max(&mut self) {
-        self.xp = 99_999_999.0;
+/// Character edits that need the editor's data files.
+trait CharacterEdits {
+    fn level(&self) -> usize;
+    /// The level the character would be at if the XP debt were taken off their XP.
+    fn level_after_debt(&self) -> usize;
+    /// Sets the XP to exactly what's needed to reach `level`, clamped to the levels that exist.
+    fn set_level(&mut self, level: usize);
+    fn reset_talents(&mut self);
+    /// Sets a talent's rank, unlocking anything it needs first.
+    fn set_talent_rank(&mut self, row_name: &str, rank: i64);
+    /// Unlocked talents whose prerequisites aren't, as `(talent, missing prerequisite)`.
+    fn missing_prerequisites(&self) -> Vec<(String, &'static str)>;
+    fn blueprints_unlocked(&self) -> usize;
+    fn talent_points_spent(&self) -> i64;
+    /// The talent points a character of this level would have earned playing normally, going by
+    /// `talent_points.txt`.
+    fn talent_points_earned(&self) -> i64;
+    /// Takes ranks off the most recently unlocked talents until no more points are spent than the
+    /// character's level has earned. Returns how many points came off.
+    fn trim_talent_points(&mut self) -> i64;
+    fn build(&self) -> Build;
+    /// Replaces the character's talents and blueprints with a build's. Returns how many of the
+    /// build's rows the editor doesn't know, which are left out.
+    fn apply_build(&mut self, build: &Build) -> usize;
+    fn reset_blueprints(&mut self);
+    fn unlock_all_talents(&mut self);
+    fn unlock_all_blueprints(&mut self);
+    /// Unlocks the blueprints `blueprint_levels.txt` puts at or below the character's level.
+    /// Returns how many were unlocked.
+    fn unlock_blueprints_up_to_level(&mut self) -> usize;
+}
+
+impl CharacterEdits for Character {
+    fn level(&self) -> usize {
+        level_for_xp(self.xp)
+    }
+
+    fn level_after_debt(&self) -> usize {
+        level_for_xp(Xp(self.xp.0 - self.xp_debt.0))
+    }
+
+    fn set_level(&mut self, level: usize) {
+        // Only if the levels file had no rows that could be read, which is reported at startup
+        if LEVEL_XP.is_empty() {
+            return;
+        }
+        let level = level.clamp(1, LEVEL_XP.len());
+        self.xp = Xp(LEVEL_XP[level - 1]);
     }
 
     fn reset_talents(&mut self) {
         self.talents.retain(|t| !TALENTS.contains(t.row_name.as_str()));
     }
 
+    fn set_talent_rank(&mut self, row_name: &str, rank: i64) {
+        let max_rank = TALENT_LEVELS.get(row_name).copied().unwrap_or(1);
+        let rank = rank.clamp(0, max_rank.max(0));
+        if rank == 0 {
+            self.talents.retain(|t| t.row_name != row_name);
+        } else if let Some(talent) = self.talents.iter_mut().find(|t| t.row_name == row_name) {
+            talent.rank = Rank(rank);
+        } else {
+            self.talents.push_back(Talent::new(row_name, rank));
+            for required in TALENT_PREREQUISITES.get(row_name).into_iter().flatten() {
+                // Already unlocked talents are skipped, which also stops any loop in the data
+                if self.talent_rank(required) == 0 {
+                    self.set_talent_rank(required, 1);
+                }
+            }
+        }
+    }
+
+    fn missing_prerequisites(&self) -> Vec<(String, &'static str)> {
+        let mut missing = Vec::new();
+        for talent in self.talents.iter().filter(|t| t.rank.0 > 0) {
+            for required in TALENT_PREREQUISITES.get(talent.row_name.as_str()).into_iter().flatten() {
+                if self.talent_rank(required) == 0 {
+                    missing.push((talent.row_name.clone(), *required));
+                }
+            }
+        }
+        missing
+    }
+
+    fn blueprints_unlocked(&self) -> usize {
+        self.talents.iter().filter(|t| BLUEPRINTS.contains(t.row_name.as_str())).count()
+    }
+
+    fn talent_points_spent(&self) -> i64 {
+        self.talents.iter().filter(|t| TALENTS.contains(t.row_name.as_str())).map(|t| t.rank.0).sum()
+    }
+
+    fn talent_points_earned(&self) -> i64 {
+        (2..=self.level()).map(|level| TALENT_POINTS.get(&level).copied().unwrap_or(0)).sum()
+    }
+
+    fn trim_talent_points(&mut self) -> i64 {
+        let over = self.talent_points_spent() - self.talent_points_earned();
+        let mut removed = 0;
+        // Talents are added to the end as they're unlocked, so the newest go first
+        for idx in (0..self.talents.len()).rev() {
+            if removed >= over {
+                break;
+            }
+            let talent = &mut self.talents[idx];
+            if TALENTS.contains(talent.row_name.as_str()) && talent.rank > Rank(0) {
+                let taken = talent.rank.0.min(over - removed);
+                talent.rank = Rank(talent.rank.0 - taken);
+                removed += taken;
+            }
+        }
+        self.talents.retain(|t| !TALENTS.contains(t.row_name.as_str()) || t.rank > Rank(0));
+
+        removed
+    }
+
+    fn build(&self) -> Build {
+        Build {
+            talents: self.talents.iter().filter(|t| TALENTS.contains(t.row_name.as_str())).map(|t| (t.row_name.clone(), t.rank.0)).collect(),
+            blueprints: self.talents.iter().filter(|t| BLUEPRINTS.contains(t.row_name.as_str())).map(|t| t.row_name.clone()).collect(),
+        }
+    }
+
+    fn apply_build(&mut self, build: &Build) -> usize {
+        self.reset_talents();
+        self.reset_blueprints();
+        let mut unknown = 0;
+        for (row_name, rank) in &build.talents {
+            match TALENTS.get(row_name.as_str()) {
+                Some(row_name) => self.set_talent_rank(row_name, *rank),
+                None => unknown += 1,
+            }
+        }
+        for row_name in &build.blueprints {
+            match BLUEPRINTS.get(row_name.as_str()) {
+                Some(row_name) => UnlockLens { row_name }.put(&mut self.talents, true),
+                None => unknown += 1,
+            }
+        }
+        unknown
+    }
+
     fn reset_blueprints(&mut self) {
         self.talents.retain(|t| !BLUEPRINTS.contains(t.row_name.as_str()));
     }
@@ -272,60 +885,156 @@ impl Character {
     fn unlock_all_talents(&mut self) {
         self.talents.retain(|t| !TALENTS.contains(t.row_name.as_str()));
         for t in TALENT_LEVELS.iter() {
-            self.talents.push_back(Talent{row_name: (*t.0).to_owned(), rank: (*t.1) })
+            self.talents.push_back(Talent::new(t.0, *t.1))
         }
     }
 
     fn unlock_all_blueprints(&mut self) {
         self.talents.retain(|t| !BLUEPRINTS.contains(t.row_name.as_str()));
         for t in BLUEPRINTS.iter() {
-            self.talents.push_back(Talent{row_name: (*t).to_owned(), rank: 1.0 })
+            self.talents.push_back(Talent::new(t, 1))
         }
     }
 
-    fn restore(&mut self) -> Result<(), Box<dyn Error>> {
-        self.is_abandoned = false;
-        self.is_dead = false;
-
-        self.update_inventory()?;
-        self.update_loadout()?;
-
-        Ok(())
+    fn unlock_blueprints_up_to_level(&mut self) -> usize {
+        let level = self.level() as f64;
+        let mut unlocked = 0;
+        for row_name in BLUEPRINTS.iter().filter(|x| BLUEPRINT_LEVELS.get(*x).map(|l| *l <= level).unwrap_or(false)) {
+            if self.talent_rank(row_name) <= 0 {
+                UnlockLens { row_name }.put(&mut self.talents, true);
+                unlocked += 1;
+            }
+        }
+        unlocked
     }
+}
 
-    fn update_loadout(&self) -> Result<(), Box<dyn Error>> {
-        let mut file_io = OpenOptions::new().write(true).read(true).open(self.loadout_path.clone())?;
-        let mut file_contents = String::new();
-        file_io.read_to_string(&mut file_contents)?;
+/// Which of a character's location lists is open.
+#[derive(Clone, Copy, Data, PartialEq)]
+enum Picker {
+    Location,
+    LastProspect,
+    /// A cosmetic option's choices, by its field in the save
+    Cosmetic(&'static str),
+}
 
-        let mut key_values: HashMap<String, serde_json::Value> = serde_json::from_str(&file_contents)?;
-        key_values.insert("Valid".to_string(), serde_json::Value::Bool(true));
-        file_contents = serde_json::to_string(&key_values)?;
-        let file_contents_raw = file_contents.as_bytes();
-        file_io.set_len(file_contents_raw.len() as u64)?;
-        file_io.write_all(file_contents_raw)?;
-        file_io.flush()?;
+/// Which rows the profile talents tab lists.
+#[derive(Clone, Copy, Data, PartialEq)]
+enum ProfileTalentFilter {
+    All,
+    Prospects,
+    Workshop,
+}
 
-        Ok(())
-    }
+#[derive(Clone, Data, PartialEq)]
+enum MainView {
+    Error,
+    Data,
+}
 
-    fn update_inventory(&self) -> Result<(), Box<dyn Error>> {
-        let mut file_io = OpenOptions::new().write(true).open(self.inventory_path.clone())?;
+fn level_for_xp(xp: Xp) -> usize {
+    LEVEL_XP.iter().filter(|x| **x <= xp.0).count().max(1)
+}
 
-        let file_contents_raw = DEFAULT_INVENTORY.as_bytes();
-        file_io.set_len(file_contents_raw.len() as u64)?;
-        file_io.write_all(file_contents_raw)?;
-        file_io.flush()?;
+/// Changes that touch a lot of data at once, which are confirmed first unless the user has said
+/// not to ask.
+#[derive(Clone, Copy, Data, PartialEq)]
+enum BulkAction {
+    ResetTalents(usize),
+    ResetBlueprints(usize),
+    UnlockAllTalents(usize),
+    UnlockAllBlueprints(usize),
+    /// Only the blueprints available at the character's level
+    UnlockBlueprintsToLevel(usize),
+    /// Replaces a character's talents and blueprints with the build code on the clipboard
+    PasteBuild(usize),
+    /// Replaces a character's talents with a preset's, by its index in `talent_presets`
+    ApplyTalentPreset(usize, usize),
+    UnlockAllProspects,
+    UnlockAllWorkshopItems,
+    /// Everything from a DLC, by its index in `DLC_NAMES`
+    UnlockDlc(usize),
+    RestoreCharacter(usize),
+    RescueCharacter(usize),
+    /// By its index in `backups`
+    RestoreBackup(usize),
+    /// Replaces a character with a snapshot, by its index in `snapshots`
+    RollBackToSnapshot(usize, usize),
+}
 
-        Ok(())
+impl BulkAction {
+    /// The button that starts the action.
+    fn label(&self) -> String {
+        let label = match self {
+            BulkAction::ResetTalents(_) => "Reset Talents",
+            BulkAction::ResetBlueprints(_) => "Reset Blueprints",
+            BulkAction::UnlockAllTalents(_) => "Unlock All Talents",
+            BulkAction::UnlockAllBlueprints(_) => "Unlock All Blueprints",
+            BulkAction::UnlockBlueprintsToLevel(_) => "Unlock Blueprints Up to My Level",
+            BulkAction::PasteBuild(_) => "Paste Build",
+            BulkAction::ApplyTalentPreset(_, _) => "Apply",
+            BulkAction::UnlockAllProspects => "Unlock All Prospects",
+            BulkAction::UnlockAllWorkshopItems => "Unlock All Workshop Items",
+            BulkAction::UnlockDlc(dlc) => return format!("Unlock All {}", DLC_NAMES[*dlc]),
+            BulkAction::RestoreCharacter(_) => "Restore Character",
+            BulkAction::RescueCharacter(_) => "Rescue from Prospect",
+            BulkAction::RestoreBackup(_) => "Restore Backup",
+            BulkAction::RollBackToSnapshot(_, _) => "Roll Back to Snapshot",
+        };
+        label.to_string()
     }
 
+    /// What the action will do, to ask about before doing it.
+    fn question(&self, data: &UiState) -> String {
+        let name = |idx: &usize| data.characters.get(*idx).map(|c| c.character_name.clone()).unwrap_or_default();
+        let inventory = if data.wipe_inventory { " Their inventory will be wiped." } else { "" };
+        match self {
+            BulkAction::ResetTalents(idx) => format!("Remove all of {}'s talents?", name(idx)),
+            BulkAction::ResetBlueprints(idx) => format!("Remove all of {}'s blueprints?", name(idx)),
+            BulkAction::UnlockAllTalents(idx) => format!("Unlock every talent for {}?", name(idx)),
+            BulkAction::UnlockAllBlueprints(idx) => format!("Unlock every blueprint for {}?", name(idx)),
+            BulkAction::UnlockBlueprintsToLevel(idx) => {
+                let level = data.characters.get(*idx).map(|c| c.level()).unwrap_or(1);
+                format!("Unlock every blueprint up to level {} for {}?", level, name(idx))
+            }
+            BulkAction::PasteBuild(idx) => format!("Replace all of {}'s talents and blueprints with the build on the clipboard?", name(idx)),
+            BulkAction::ApplyTalentPreset(idx, preset) => {
+                let preset = data.talent_presets.get(*preset).map(|x| x.name.clone()).unwrap_or_default();
+                format!("Replace all of {}'s talents with the {} preset? Their blueprints are kept.", name(idx), preset)
+            }
+            BulkAction::UnlockAllProspects => "Unlock every prospect?".to_string(),
+            BulkAction::UnlockAllWorkshopItems => "Unlock every workshop item, without spending any credits?".to_string(),
+            BulkAction::UnlockDlc(dlc) => format!("Unlock every {} prospect and workshop item, and every {} talent and blueprint for all characters?", DLC_NAMES[*dlc], DLC_NAMES[*dlc]),
+            BulkAction::RestoreCharacter(idx) => format!("Restore {}? Their inventory and loadout files are rewritten straight away.{}", name(idx), inventory),
+            BulkAction::RescueCharacter(idx) => format!("Rescue {} from their prospect? Their inventory and loadout files are rewritten straight away.{}", name(idx), inventory),
+            BulkAction::RestoreBackup(backup) => {
+                let backup = data.backups.get(*backup).map(|x| x.name.clone()).unwrap_or_default();
+                format!("Restore backup {}? Every save file is replaced straight away and unsaved changes are lost. The current files are backed up first.", backup)
+            }
+            BulkAction::RollBackToSnapshot(idx, snapshot) => {
+                let snapshot = data.snapshots.get(*snapshot).map(|x| x.name.clone()).unwrap_or_default();
+                format!("Replace {} with snapshot {}? Changes since the snapshot are lost once you save.", name(idx), snapshot)
+            }
+        }
+    }
 }
 
 #[derive(Clone, Data, PartialEq)]
-enum MainView {
-    Error,
-    Data,
+enum Prompt {
+    Close,
+    DeleteCharacter(usize),
+    Revive(usize),
+    /// The game is running, asked about before a save, or before an action that writes straight away
+    GameRunning(Option<BulkAction>),
+    /// Picking which files to save
+    Save,
+    SwitchSaveDir(usize),
+    Reload,
+    Confirm(BulkAction),
+    Message(String),
+    /// Something that only takes effect after a restart, such as new data files
+    Restart(String),
+    About,
 }
 
 #[derive(Clone, Data, Lens)]
@@ -340,227 +1049,3753 @@ struct UiState {
     characters_file: PathBuf,
     #[lens(name = "characters_lens")]
     characters: Vector<Character>,
+    #[data(eq)]
+    #[lens(name = "characters_extra_lens")]
+    characters_extra: Map<String, Value>,
+    /// Raw JSON of characters that couldn't be read, with where they were in `Characters.json`,
+    /// written back untouched in the same place on save
+    #[lens(name = "skipped_characters_lens")]
+    skipped_characters: Vector<(usize, String)>,
+    #[lens(name = "load_warnings_lens")]
+    load_warnings: Vector<String>,
+    /// What the last "Validate Save" found, if it's been run
+    #[lens(name = "validation_issues_lens")]
+    validation_issues: Option<Vector<Issue>>,
+    /// Repairs worked out for save files that can't be read, waiting on the user's go-ahead
+    #[lens(name = "salvages_lens")]
+    salvages: Vector<Salvage>,
+    /// Likely reasons the save folder couldn't be opened, found when it failed to
+    #[lens(name = "access_problems_lens")]
+    access_problems: Vector<AccessProblem>,
+    /// Changes made since the last save, written to the change log when saving
+    #[lens(name = "audit_pending_lens")]
+    audit_pending: Vector<AuditEntry>,
+    /// The change log, while it's being viewed
+    #[lens(name = "audit_log_lens")]
+    audit_log: Option<Vector<AuditEntry>>,
+    #[lens(name = "prospects_lens")]
+    prospects: Vector<Prospect>,
+    #[lens(name = "inventory_search_lens")]
+    inventory_search: String,
+    /// How many of an item the spawner adds, split into stacks as needed
+    #[lens(name = "spawn_quantity_lens")]
+    spawn_quantity: u32,
+    /// The durability spawned items get; 0 leaves it to the game
+    #[lens(name = "spawn_durability_lens")]
+    spawn_durability: f64,
+    /// Filters the talent, blueprint, workshop and prospect lists
+    #[lens(name = "search_lens")]
+    search: String,
+    #[lens(name = "limit_talent_points_lens")]
+    limit_talent_points: bool,
+    #[lens(name = "purchase_with_credits_lens")]
+    purchase_with_credits: bool,
+    #[lens(name = "profile_merge_lens")]
+    profile_merge: MergeStrategy,
+    #[lens(name = "profile_talent_filter_lens")]
+    profile_talent_filter: ProfileTalentFilter,
     #[lens(name = "error_lens")]
     error: Option<String>,
+    #[data(eq)]
+    #[lens(name = "save_dir_lens")]
+    save_dir: PathBuf,
+    #[lens(name = "config_lens")]
+    config: Config,
+    #[lens(name = "backups_lens")]
+    backups: Vector<Backup>,
+    /// Every character snapshot taken for this save folder
+    #[lens(name = "snapshots_lens")]
+    snapshots: Vector<Snapshot>,
+    /// The character whose talents are being planned for a respec, if any
+    #[lens(name = "respec_character_lens")]
+    respec_character: Option<usize>,
+    /// Talents the respec plan removes
+    #[lens(name = "respec_talents_lens")]
+    respec_talents: Vector<String>,
+    /// Talents ticked in the talents list, to have their ranks set all at once
+    #[lens(name = "selected_talents_lens")]
+    selected_talents: Vector<String>,
+    /// The rank "Set Rank" gives the selected talents
+    #[lens(name = "selection_rank_lens")]
+    selection_rank: i64,
+    /// The two characters on the compare tab, by index
+    #[lens(name = "compare_left_lens")]
+    compare_left: usize,
+    #[lens(name = "compare_right_lens")]
+    compare_right: usize,
+    /// The name to give the next snapshot
+    #[lens(name = "snapshot_name_lens")]
+    snapshot_name: String,
+    /// The built in loadout templates and the user's own
+    #[lens(name = "loadout_templates_lens")]
+    loadout_templates: Vector<LoadoutTemplate>,
+    /// The name to save the next loadout template as
+    #[lens(name = "template_name_lens")]
+    template_name: String,
+    /// The built in talent presets and the user's own
+    #[lens(name = "talent_presets_lens")]
+    talent_presets: Vector<TalentPreset>,
+    /// The name to save the next talent preset as
+    #[lens(name = "preset_name_lens")]
+    preset_name: String,
+    /// What the raw JSON editor is open on, if anything
+    #[lens(name = "raw_json_target_lens")]
+    raw_json_target: Option<RawTarget>,
+    /// The text in the raw JSON editor
+    #[lens(name = "raw_json_lens")]
+    raw_json: String,
+    /// Why the raw JSON can't be used, while it can't
+    #[lens(name = "raw_json_error_lens")]
+    raw_json_error: Option<String>,
+    /// Whether other tabs changed the raw JSON editor's target while its text couldn't be read
+    #[lens(name = "raw_json_conflict_lens")]
+    raw_json_conflict: bool,
+    #[lens(name = "history_lens")]
+    history: History,
+    /// The first run wizard's step, while it's showing
+    #[lens(name = "wizard_lens")]
+    wizard: Option<WizardStep>,
+    /// The backup open in the backup window, once it's been read
+    #[lens(name = "backup_view_lens")]
+    backup_view: Option<BackupView>,
+    #[lens(name = "prompt_lens")]
+    prompt: Option<Prompt>,
+    /// The files that can be written, for the save dialog
+    #[lens(name = "save_choices_lens")]
+    save_choices: Vector<SaveChoice>,
+    /// The files waiting to be saved if the user says to save while Icarus is running
+    #[data(eq)]
+    #[lens(name = "pending_save_lens")]
+    pending_save: Vec<PathBuf>,
+    /// The character waiting on the export file dialog
+    #[lens(name = "pending_export_lens")]
+    pending_export: Option<usize>,
+    /// The character waiting on the file dialog for a CSV of talent ranks
+    #[lens(name = "pending_rank_import_lens")]
+    pending_rank_import: Option<usize>,
+    #[data(eq)]
+    #[lens(name = "pending_deletions_lens")]
+    pending_deletions: Vec<PathBuf>,
+    /// Icarus's Steam Cloud cache, if it disagrees with the save folder
+    #[data(eq)]
+    #[lens(name = "steam_cloud_lens")]
+    steam_cloud: Option<PathBuf>,
+    /// Every save folder found on this machine
+    #[data(eq)]
+    #[lens(name = "save_dirs_lens")]
+    save_dirs: Vec<PathBuf>,
+    /// When the profile and characters files were last changed, as of loading or saving
+    #[data(eq)]
+    #[lens(name = "disk_times_lens")]
+    disk_times: Vec<Option<SystemTime>>,
+    #[lens(name = "open_picker_lens")]
+    open_picker: Option<Picker>,
+    /// The open character, when there are too many for tabs and they're listed down the side
+    #[lens(name = "selected_character_lens")]
+    selected_character: usize,
+    /// Whether restoring or rescuing a character throws away their inventory
+    #[lens(name = "wipe_inventory_lens")]
+    wipe_inventory: bool,
+    /// A flag number typed in to add to a character
+    #[lens(name = "new_flag_lens")]
+    new_flag: String,
+    /// A slot typed in to move or swap a character to
+    #[lens(name = "target_slot_lens")]
+    target_slot: String,
+    /// The name typed in for a new character
+    #[lens(name = "new_character_name_lens")]
+    new_character_name: String,
+    /// What's wrong with the text in the XP boxes, shown beside them
+    #[lens(name = "xp_input_errors_lens")]
+    xp_input_errors: XpInputErrors,
+    /// Set when something else has written to the save since it was loaded
+    #[lens(name = "external_change_lens")]
+    external_change: bool,
+    /// When the save was last written this session
+    #[lens(name = "last_saved_lens")]
+    last_saved: Option<String>,
+    /// What's running in the background, for the progress indicator
+    #[lens(name = "busy_lens")]
+    busy: Vector<&'static str>,
+    /// A newer release than this build, once the update check has found one
+    #[lens(name = "available_update_lens")]
+    available_update: Option<Release>,
+    /// How the last thing the user asked for went
+    #[lens(name = "status_lens")]
+    status: String,
 }
 
 impl UiState {
-    pub fn new() -> Result<UiState, Box<dyn Error>> {
-        let dirs = BaseDirs::new().ok_or::<Box<dyn Error>>("Unable to find %APPDATA%\\Local\\".into())?;
-        let data_local_dir = dirs.data_local_dir().join("Icarus").join("Saved").join("PlayerData");
-        let mut steam_ids = data_local_dir.read_dir()?.collect::<Vec<_>>();
+    /// Finds the save folder in its default location under `%LOCALAPPDATA%`.
+    pub fn find_save_dir() -> Result<PathBuf, Box<dyn Error>> {
+        let mut steam_ids = UiState::find_save_dirs()?;
         let data_local_dir = if steam_ids.is_empty() {
             return Err("Unable to find PlayerData folder".into());
         } else {
             if steam_ids.len() > 1 {
                 println!("Found {} Steam IDs - picking the first one in list", steam_ids.len())
             }
-            steam_ids.remove(0)?.path()
+            steam_ids.remove(0)
         };
 
-        let profile_file = data_local_dir.join("Profile.json");
-        let characters_file = data_local_dir.join("Characters.json");
-
-        if !profile_file.exists() || !characters_file.exists() {
-            Err(format!(
-                "One or both of [{}] and [{}] do not exist - please open Icarus and create an Offline character before running this tool",
-                profile_file.to_string_lossy(),
-                characters_file.to_string_lossy()
-            ))?
-        }
-
-        let mut profile_file_io = OpenOptions::new().write(true).read(true).open(profile_file.clone())?;
-        let mut profile_string = String::new();
-        profile_file_io.read_to_string(&mut profile_string)?;
-        let profile: Profile = serde_json::from_str(&profile_string)?;
-
-        let mut character_string = String::new();
-        let mut character_file_io = OpenOptions::new().write(true).read(true).open(characters_file.clone())?;
-        character_file_io.read_to_string(&mut character_string)?;
-
-        let chars: Characters = serde_json::from_str(&character_string)?;
-        let mut characters = Vec::<Character>::with_capacity(chars.characters_json.len());
-        for c in chars.characters_json {
-            let mut character: Character = serde_json::from_str(&c)?;
-            character.inventory_path = data_local_dir.join("Inventory").join(format!("InventoryID_{}.json", character.character_slot as i8));
-            character.loadout_path = data_local_dir.join("Loadout").join(format!("Slot_{}.json", character.character_slot as i8));
-            characters.push(character);
-        }
-        characters.sort_by(|a, b|{
-            if let Some(c) = a.character_slot.partial_cmp(&b.character_slot) {
-                c
-            } else {
-                panic!("Could not compare floating points")
+        Ok(data_local_dir)
+    }
+
+    /// Finds every save folder under `%LOCALAPPDATA%` - one per Steam or GOG account that has
+    /// played Icarus on this machine, then any from the Game Pass version.
+    pub fn find_save_dirs() -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let dirs = BaseDirs::new().ok_or::<Box<dyn Error>>("Unable to find %APPDATA%\\Local\\".into())?;
+        let data_local_dir = save_locations::player_data_dir(dirs.data_local_dir());
+        let mut steam_ids = Vec::new();
+        let steam_error = match data_local_dir.read_dir() {
+            Ok(entries) => {
+                for entry in entries {
+                    let entry = entry?;
+                    if entry.file_type()?.is_dir() {
+                        steam_ids.push(entry.path());
+                    }
+                }
+                None
             }
-        });
-        let data = UiState {
-            profile_file,
-            profile,
-            characters_file,
-            characters: Vector::from(characters),
-            error: None,
+            Err(e) => Some(e),
         };
+        steam_ids.sort();
+        for root in save_locations::packaged_save_roots(dirs.data_local_dir()) {
+            steam_ids.extend(UiState::find_player_dirs(&root, 2));
+        }
+        // Only an error if there's no save anywhere, so the Steam folder's error says why
+        if let (true, Some(e)) = (steam_ids.is_empty(), steam_error) {
+            Err(e)?
+        }
+
+        Ok(steam_ids)
+    }
+
+    /// Player folders in or under `root`, going at most `depth` folders down. A folder counts if it
+    /// has a `Characters.json` in it. A dedicated server keeps a folder like this for each player,
+    /// named after their Steam ID, with the same files in it as a save on a player's own machine.
+    fn find_player_dirs(root: &Path, depth: usize) -> Vec<PathBuf> {
+        if root.join(CHARACTERS_FILE).exists() {
+            return vec![root.to_path_buf()];
+        }
+        let mut dirs = Vec::new();
+        if let (true, Ok(entries)) = (depth > 0, root.read_dir()) {
+            for entry in entries.flatten().filter(|x| x.file_type().map(|x| x.is_dir()).unwrap_or(false)) {
+                dirs.extend(UiState::find_player_dirs(&entry.path(), depth - 1));
+            }
+        }
+        dirs.sort();
+
+        dirs
+    }
+
+    /// Opens the first player's save in a dedicated server's folder. The other players' saves are
+    /// then listed alongside it to switch between.
+    pub fn open_server_dir(&mut self, root: &Path) -> Result<(), Box<dyn Error>> {
+        let dirs = UiState::find_player_dirs(root, SERVER_SEARCH_DEPTH);
+        let first = dirs.first().ok_or_else(|| format!("[{}] doesn't have any player saves in it", root.to_string_lossy()))?;
+        self.change_save_dir(first.clone());
+
+        Ok(())
+    }
+
+    /// The save folders the editor can switch between, which always includes the current one, and
+    /// the other player folders next to it, as with a dedicated server's saves.
+    fn candidate_save_dirs(current: &Path) -> Vec<PathBuf> {
+        let mut save_dirs = UiState::find_save_dirs().unwrap_or_default();
+        if !current.as_os_str().is_empty() && !save_dirs.iter().any(|x| x == current) {
+            save_dirs.insert(0, current.to_path_buf());
+        }
+        for dir in current.parent().map(|x| UiState::find_player_dirs(x, 1)).unwrap_or_default() {
+            if !save_dirs.contains(&dir) {
+                save_dirs.push(dir);
+            }
+        }
+
+        save_dirs
+    }
+
+    pub fn load(data_local_dir: PathBuf) -> Result<UiState, Box<dyn Error>> {
+        UiState::load_with(data_local_dir, false)
+    }
+
+    /// Loads the save in `data_local_dir`. Characters that can't be read are an error, unless
+    /// `skip_broken` is set, in which case they're left out of the editor but kept in the file.
+    pub fn load_with(data_local_dir: PathBuf, skip_broken: bool) -> Result<UiState, Box<dyn Error>> {
+        let store = CharacterStore::load_with(&data_local_dir, skip_broken)?;
+        let mut load_warnings = check_data_files();
+        load_warnings.extend(store.warnings);
+        if save_locations::is_packaged_storage(&data_local_dir) {
+            load_warnings.push("This is a Game Pass save, which the Xbox app syncs with the cloud - close Icarus and the Xbox app while editing, or the sync may put the old files back".to_string());
+        }
+        // Backups and snapshots are only a convenience, so not being able to list them doesn't stop
+        // the save opening
+        let backups = backup::list_backups(&data_local_dir).unwrap_or_else(|e| {
+            load_warnings.push(format!("Unable to list backups: {}", e));
+            Vector::new()
+        });
+        let snapshots = snapshots::list_snapshots(&data_local_dir).unwrap_or_else(|e| {
+            load_warnings.push(format!("Unable to list snapshots: {}", e));
+            Vector::new()
+        });
+        let mut data = UiState {
+            profile_file: store.profile_file,
+            profile: store.profile,
+            characters_file: store.characters_file,
+            characters: store.characters,
+            characters_extra: store.characters_extra,
+            skipped_characters: store.skipped_characters,
+            load_warnings: Vector::from(load_warnings),
+            validation_issues: None,
+            salvages: Vector::new(),
+            access_problems: Vector::new(),
+            audit_pending: Vector::new(),
+            audit_log: None,
+            prospects: store.prospects,
+            inventory_search: String::new(),
+            spawn_quantity: 1,
+            spawn_durability: 0.0,
+            search: String::new(),
+            limit_talent_points: false,
+            purchase_with_credits: false,
+            profile_merge: MergeStrategy::Union,
+            profile_talent_filter: ProfileTalentFilter::All,
+            error: None,
+            backups,
+            snapshots,
+            snapshot_name: String::new(),
+            loadout_templates: loadout_templates::list_templates()?,
+            template_name: String::new(),
+            talent_presets: presets::list_presets()?,
+            preset_name: String::new(),
+            raw_json_target: None,
+            raw_json: String::new(),
+            raw_json_error: None,
+            raw_json_conflict: false,
+            respec_character: None,
+            respec_talents: Vector::new(),
+            selected_talents: Vector::new(),
+            selection_rank: 1,
+            compare_left: 0,
+            compare_right: 1,
+            save_dir: data_local_dir.clone(),
+            config: Config::load(),
+            history: Default::default(),
+            wizard: None,
+            backup_view: None,
+            prompt: None,
+            save_choices: Vector::new(),
+            pending_save: Vec::new(),
+            pending_export: None,
+            pending_rank_import: None,
+            pending_deletions: Vec::new(),
+            steam_cloud: None,
+            save_dirs: UiState::candidate_save_dirs(&data_local_dir),
+            disk_times: Vec::new(),
+            open_picker: None,
+            selected_character: 0,
+            wipe_inventory: false,
+            new_flag: String::new(),
+            target_slot: String::new(),
+            new_character_name: String::new(),
+            xp_input_errors: XpInputErrors::default(),
+            external_change: false,
+            last_saved: None,
+            busy: Vector::new(),
+            available_update: None,
+            status: "Loaded save".to_string(),
+        };
+        data.check_steam_cloud();
+        data.remember_disk_times();
+        data.mark_saved();
+
+        Ok(data)
+    }
+
+    /// Switches the editor over to another save folder and remembers it for next time.
+    pub fn change_save_dir(&mut self, save_dir: PathBuf) {
+        self.open_save_dir(save_dir, false);
+    }
+
+    /// Opens the current save folder again, leaving out any characters that can't be read.
+    pub fn reload_skipping_broken(&mut self) {
+        self.open_save_dir(self.save_dir.clone(), true);
+    }
+
+    /// Loads `save_dir` in the background. Failing to load isn't an error as such - the error screen
+    /// takes over, so the user can pick another folder or try a repair.
+    fn open_save_dir(&mut self, save_dir: PathBuf, skip_broken: bool) {
+        let dir = save_dir.clone();
+        self.run_in_background("load save", "Loading", move || Ok(UiState::load_with(dir, skip_broken).map_err(|e| e.to_string())), move |t, loaded| {
+            match loaded {
+                Ok(data) => {
+                    t.replace_with_loaded(data);
+                    t.config.save_dir = Some(save_dir);
+                    if let Err(e) = t.config.save() {
+                        println!("Unable to save config: {}", e);
+                    }
+                }
+                Err(e) => {
+                    t.error = Some(format!("Error: {}", e));
+                    t.access_problems = access::diagnose(&save_dir);
+                    t.save_dir = save_dir;
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    /// Switches to `loaded`, a save that's just been read, keeping what belongs to the editor rather
+    /// than the save: the config, any update found and the wizard.
+    fn replace_with_loaded(&mut self, loaded: UiState) {
+        let config = self.config.clone();
+        let available_update = self.available_update.take();
+        let wizard = self.wizard.take();
+        // A backup being looked at still belongs to the save after a reload or restore
+        let backup_view = self.backup_view.take().filter(|_| loaded.save_dir == self.save_dir);
+        *self = loaded;
+        self.config = config;
+        self.available_update = available_update;
+        self.wizard = wizard;
+        self.backup_view = backup_view;
+    }
+
+    pub fn from_error(error: String) -> UiState {
+        UiState {
+            profile_file: Default::default(),
+            profile: Profile {
+                user_id: "".to_string(),
+                meta_resources: Default::default(),
+                unlocked_flags: Default::default(),
+                talents: Default::default(),
+                completed_missions: Default::default(),
+                mission_best_times: Default::default(),
+                extra: Default::default(),
+            },
+            characters_file: Default::default(),
+            characters: Default::default(),
+            characters_extra: Default::default(),
+            skipped_characters: Default::default(),
+            load_warnings: Default::default(),
+            validation_issues: None,
+            salvages: Vector::new(),
+            access_problems: Vector::new(),
+            audit_pending: Vector::new(),
+            audit_log: None,
+            prospects: Default::default(),
+            inventory_search: Default::default(),
+            spawn_quantity: 1,
+            spawn_durability: 0.0,
+            search: Default::default(),
+            limit_talent_points: false,
+            purchase_with_credits: false,
+            profile_merge: MergeStrategy::Union,
+            profile_talent_filter: ProfileTalentFilter::All,
+            error: Some(error),
+            save_dir: Default::default(),
+            config: Config::load(),
+            backups: Default::default(),
+            snapshots: Default::default(),
+            snapshot_name: String::new(),
+            loadout_templates: Default::default(),
+            template_name: String::new(),
+            talent_presets: Default::default(),
+            preset_name: String::new(),
+            raw_json_target: None,
+            raw_json: String::new(),
+            raw_json_error: None,
+            raw_json_conflict: false,
+            respec_character: None,
+            respec_talents: Vector::new(),
+            selected_talents: Vector::new(),
+            selection_rank: 1,
+            compare_left: 0,
+            compare_right: 1,
+            history: Default::default(),
+            wizard: None,
+            backup_view: None,
+            prompt: None,
+            save_choices: Vector::new(),
+            pending_save: Vec::new(),
+            pending_export: None,
+            pending_rank_import: None,
+            pending_deletions: Vec::new(),
+            steam_cloud: None,
+            save_dirs: UiState::candidate_save_dirs(Path::new("")),
+            disk_times: Vec::new(),
+            open_picker: None,
+            selected_character: 0,
+            wipe_inventory: false,
+            new_flag: String::new(),
+            target_slot: String::new(),
+            new_character_name: String::new(),
+            xp_input_errors: XpInputErrors::default(),
+            external_change: false,
+            last_saved: None,
+            busy: Vector::new(),
+            available_update: None,
+            status: String::new(),
+        }
+    }
+
+    /// Warns about save files that Steam Cloud has a different idea of.
+    fn check_steam_cloud(&mut self) {
+        let path = match steam_cloud::find_remote_cache(&self.save_dir) {
+            Some(path) => path,
+            None => return,
+        };
+        match RemoteCache::load(&path) {
+            Ok(cache) => {
+                let warnings = cache.warnings(&self.save_dir, &self.save_files());
+                if !warnings.is_empty() {
+                    self.load_warnings.extend(warnings);
+                    self.steam_cloud = Some(path);
+                }
+            }
+            Err(e) => self.load_warnings.push_back(format!("Unable to read Steam Cloud cache [{}]: {}", path.to_string_lossy(), e)),
+        }
+    }
+
+    /// Tells Steam to keep the local save files over the copies in the cloud.
+    pub fn prefer_local_in_steam_cloud(&mut self) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        if let Some(path) = &self.steam_cloud {
+            let mut cache = RemoteCache::load(path)?;
+            cache.prefer_local(&self.save_dir, &self.save_files());
+            cache.save()?;
+            self.steam_cloud = None;
+            self.status = "Updated the Steam Cloud cache".to_string();
+        }
+
+        Ok(())
+    }
+
+    /// Fails if safe mode is on. Anything that writes to the save folder checks this first.
+    fn check_writable(&self) -> Result<(), Box<dyn Error>> {
+        if self.config.safe_mode {
+            Err("safe mode is on, so nothing can be written to the save folder")?
+        }
+
+        Ok(())
+    }
+
+    fn backup(&mut self, files: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        backup::create_backup(&self.save_dir, files)?;
+        backup::prune_backups(&self.save_dir, self.config.backup_retention)?;
+        self.backups = backup::list_backups(&self.save_dir)?;
+
+        Ok(())
+    }
+
+    /// The save as the editor has it, for writing out.
+    fn store(&self) -> CharacterStore {
+        CharacterStore {
+            save_dir: self.save_dir.clone(),
+            profile_file: self.profile_file.clone(),
+            profile: self.profile.clone(),
+            characters_file: self.characters_file.clone(),
+            characters: self.characters.clone(),
+            characters_extra: self.characters_extra.clone(),
+            skipped_characters: self.skipped_characters.clone(),
+            prospects: self.prospects.clone(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Every file a save touches.
+    fn save_files(&self) -> Vec<PathBuf> {
+        let mut files = self.store().files();
+        files.extend(self.pending_deletions.iter().cloned());
+
+        files
+    }
+
+    fn backup_all(&mut self) -> Result<(), Box<dyn Error>> {
+        let files = self.save_files();
+        self.backup(&files)
+    }
+
+    /// Writes `files`, unless one of them is locked or the game is running, in which case the user
+    /// is told why (and, for the game, asked whether to save anyway). Then does `then` once the save
+    /// has been written.
+    pub fn request_save_of(&mut self, files: Vec<PathBuf>, then: impl FnOnce(&mut UiState) + Send + 'static) {
+        if !self.run_action("save", |t| t.check_writable()) {
+            return;
+        }
+        if files.is_empty() {
+            self.status = "Nothing to save".to_string();
+            then(self);
+            return;
+        }
+        let mut check = files.clone();
+        check.extend(self.pending_deletions.iter().cloned());
+        let check_game = self.config.confirm_game_running;
+        // Looking for the game means listing every process, which can take a moment too
+        self.run_in_background("save", "Checking save files", move || Ok((guard::locked_files(&check), check_game && guard::game_running())), move |t, (locked, game_running)| {
+            if !locked.is_empty() {
+                let files = locked.iter().map(|x| x.to_string_lossy().to_string()).collect::<Vec<_>>().join("\n");
+                t.prompt = Some(Prompt::Message(format!("These files are in use by another program and can't be saved:\n{}", files)));
+            } else if game_running {
+                t.pending_save = files;
+                t.prompt = Some(Prompt::GameRunning(None));
+            } else {
+                t.save_in_background(files, then);
+            }
+
+            Ok(())
+        });
+    }
+
+    /// Asks before doing `action`, unless the user has turned that off.
+    pub fn request_bulk_action(&mut self, action: BulkAction) {
+        if self.config.confirm_bulk_changes {
+            self.prompt = Some(Prompt::Confirm(action));
+        } else {
+            self.bulk_action(action);
+        }
+    }
+
+    fn bulk_action(&mut self, action: BulkAction) {
+        match action {
+            BulkAction::ResetTalents(idx) => self.characters[idx].reset_talents(),
+            BulkAction::ResetBlueprints(idx) => self.characters[idx].reset_blueprints(),
+            BulkAction::UnlockAllTalents(idx) => self.characters[idx].unlock_all_talents(),
+            BulkAction::UnlockAllBlueprints(idx) => self.characters[idx].unlock_all_blueprints(),
+            BulkAction::UnlockBlueprintsToLevel(idx) => {
+                let c = &mut self.characters[idx];
+                let unlocked = c.unlock_blueprints_up_to_level();
+                self.status = format!("Unlocked {} blueprints up to level {}", unlocked, c.level());
+            }
+            BulkAction::PasteBuild(idx) => { self.run_action("paste build", |t| t.paste_build(idx)); }
+            BulkAction::ApplyTalentPreset(idx, preset) => { self.run_action("apply preset", |t| t.apply_talent_preset(idx, preset)); }
+            BulkAction::UnlockAllProspects => self.profile.unlock_all_prospects(),
+            BulkAction::UnlockAllWorkshopItems => self.profile.unlock_all_workshop_items(),
+            BulkAction::UnlockDlc(dlc) => self.unlock_dlc(DLC_NAMES[dlc]),
+            BulkAction::RestoreCharacter(idx) | BulkAction::RescueCharacter(idx) => self.request_write(idx, action),
+            BulkAction::RestoreBackup(backup) => {
+                if let Some(backup) = self.backups.get(backup).cloned() {
+                    self.restore_backup(&backup);
+                }
+            }
+            BulkAction::RollBackToSnapshot(idx, snapshot) => {
+                if let Some(snapshot) = self.snapshots.get(snapshot).cloned() {
+                    self.run_action("roll back to snapshot", |t| t.roll_back_to_snapshot(idx, &snapshot));
+                }
+            }
+        }
+    }
+
+    /// Does `action`, which writes character `idx`'s inventory and loadout straight away, behind the
+    /// same checks as a save: locked files refuse it, and a running game asks first.
+    fn request_write(&mut self, idx: usize, action: BulkAction) {
+        if !self.run_action("write", |t| t.check_writable()) {
+            return;
+        }
+        let check = match self.characters.get(idx) {
+            Some(c) => vec![c.inventory_path.clone(), c.loadout_path.clone()],
+            None => return,
+        };
+        let check_game = self.config.confirm_game_running;
+        self.run_in_background("write", "Checking save files", move || Ok((guard::locked_files(&check), check_game && guard::game_running())), move |t, (locked, game_running)| {
+            if !locked.is_empty() {
+                let files = locked.iter().map(|x| x.to_string_lossy().to_string()).collect::<Vec<_>>().join("\n");
+                t.prompt = Some(Prompt::Message(format!("These files are in use by another program and can't be written:\n{}", files)));
+            } else if game_running {
+                t.prompt = Some(Prompt::GameRunning(Some(action)));
+            } else {
+                t.write_now(action);
+            }
+
+            Ok(())
+        });
+    }
+
+    /// Does an action that `request_write` has checked it's safe to write for.
+    fn write_now(&mut self, action: BulkAction) {
+        match action {
+            BulkAction::RestoreCharacter(idx) => { self.run_action("restore character", |t| t.restore_character(idx)); }
+            BulkAction::RescueCharacter(idx) => { self.run_action("rescue character", |t| t.rescue_character(idx)); }
+            _ => self.bulk_action(action),
+        }
+    }
+
+    /// Fetches the latest data files from the configured URL, for new game content between releases.
+    pub fn download_data_files(&mut self) {
+        let url = match self.config.data_url.trim() {
+            "" => game_data::DEFAULT_DATA_URL.to_string(),
+            url => url.to_string(),
+        };
+        self.run_in_background("download data files", "Downloading data files", move || game_data::download(&url, &DATA_FILES), |t, results| {
+            t.prompt = Some(Prompt::Restart(format!("Downloaded the data files - restart the editor to use them.\n{}", results.join("\n"))));
+            Ok(())
+        });
+    }
+
+    /// Runs something the user asked for, showing any error in a dialog instead of bringing down
+    /// the whole editor. Returns whether it worked.
+    pub fn run_action<F: FnOnce(&mut UiState) -> Result<(), Box<dyn Error>>>(&mut self, what: &str, action: F) -> bool {
+        match action(self) {
+            Ok(()) => true,
+            Err(e) => {
+                self.status = format!("Unable to {}", what);
+                self.prompt = Some(Prompt::Message(format!("Unable to {}: {}", what, e)));
+                false
+            }
+        }
+    }
+
+    pub fn restore_character(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        let files = [self.characters[idx].inventory_path.clone(), self.characters[idx].loadout_path.clone()];
+        self.backup(&files)?;
+
+        let wipe_inventory = self.wipe_inventory;
+        self.characters[idx].restore(wipe_inventory)?;
+        self.status = format!("Restored character in slot {}", self.characters[idx].character_slot);
+
+        Ok(())
+    }
+
+    /// A prospect ID as the player would know it: the lobby name and which prospect it is, if its
+    /// save is in the folder.
+    pub fn prospect_name(&self, prospect_id: &str) -> String {
+        if prospect_id.is_empty() {
+            return "Not on a prospect".to_string();
+        }
+        match self.prospects.iter().map(|p| &p.data.info).find(|x| x.prospect_id == prospect_id) {
+            Some(info) if info.lobby_name.is_empty() => display_name(&info.prospect_dt_key).to_string(),
+            Some(info) => format!("{} - {}", info.lobby_name, display_name(&info.prospect_dt_key)),
+            None => "Unknown prospect, with no save in the Prospects folder".to_string(),
+        }
+    }
+
+    pub fn rescue_character(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        let files = [self.characters[idx].inventory_path.clone(), self.characters[idx].loadout_path.clone()];
+        self.backup(&files)?;
+
+        let wipe_inventory = self.wipe_inventory;
+        self.characters[idx].rescue(wipe_inventory)?;
+        self.status = format!("Rescued character in slot {}", self.characters[idx].character_slot);
+
+        Ok(())
+    }
+
+    pub fn take_snapshot(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        let snapshot = snapshots::take_snapshot(&self.save_dir, &self.characters[idx], &self.snapshot_name)?;
+        self.snapshots = snapshots::list_snapshots(&self.save_dir)?;
+        self.snapshot_name.clear();
+        self.status = format!("Took snapshot {}", snapshot.name);
+
+        Ok(())
+    }
+
+    /// Puts a character back the way it was in a snapshot, keeping its current slot. Nothing is
+    /// written until the next save.
+    pub fn roll_back_to_snapshot(&mut self, idx: usize, snapshot: &Snapshot) -> Result<(), Box<dyn Error>> {
+        let slot = match self.characters.get(idx) {
+            Some(c) => c.character_slot,
+            None => return Ok(()),
+        };
+        let export = export::import_character(&snapshot.path)?;
+        let mut character = export.character;
+        character.character_slot = slot;
+        character.set_file_paths(&self.save_dir);
+        character.inventory_loaded = export.inventory.is_some();
+        character.inventory = export.inventory.unwrap_or_default();
+        character.loadout_loaded = export.loadout.is_some();
+        character.loadout = export.loadout.unwrap_or_default();
+        self.characters[idx] = character;
+        self.status = format!("Rolled back to snapshot {}", snapshot.name);
+
+        Ok(())
+    }
+
+    pub fn delete_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), Box<dyn Error>> {
+        snapshots::delete_snapshot(snapshot)?;
+        self.snapshots = snapshots::list_snapshots(&self.save_dir)?;
+        self.status = format!("Deleted snapshot {}", snapshot.name);
+
+        Ok(())
+    }
+
+    pub fn save_loadout_template(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        let template = loadout_templates::save_template(&self.template_name, &self.characters[idx].loadout)?;
+        self.loadout_templates = loadout_templates::list_templates()?;
+        self.template_name.clear();
+        self.status = format!("Saved loadout template {}", template.name);
+
+        Ok(())
+    }
+
+    /// Replaces a character's loadout with a template's. It's written to the character's own
+    /// `Slot_<n>.json` on the next save, even if the old one couldn't be read.
+    pub fn apply_loadout_template(&mut self, idx: usize, template: &LoadoutTemplate) -> Result<(), Box<dyn Error>> {
+        let c = match self.characters.get_mut(idx) {
+            Some(c) => c,
+            None => return Ok(()),
+        };
+        c.loadout = template.loadout()?;
+        c.loadout_loaded = true;
+        self.status = format!("Gave {} the {} loadout", c.character_name, template.name);
+
+        Ok(())
+    }
+
+    pub fn delete_loadout_template(&mut self, template: &LoadoutTemplate) -> Result<(), Box<dyn Error>> {
+        loadout_templates::delete_template(template)?;
+        self.loadout_templates = loadout_templates::list_templates()?;
+        self.status = format!("Deleted loadout template {}", template.name);
+
+        Ok(())
+    }
+
+    /// Removes the talents marked in the respec plan, leaving their points free to spend again.
+    pub fn apply_respec(&mut self) {
+        let talents = std::mem::take(&mut self.respec_talents);
+        if let Some(c) = self.respec_character.take().and_then(|idx| self.characters.get_mut(idx)) {
+            let refund = c.respec_refund(&talents);
+            c.remove_talents(&talents);
+            self.status = format!("Refunded {} talent points", refund);
+        }
+    }
+
+    /// Sets each selected talent to `rank`, or to its max rank when `None`, as far as that talent
+    /// goes. With the talent point limit on, talents stop going up once the points run out.
+    pub fn set_selected_talent_ranks(&mut self, idx: usize, rank: Option<i64>) {
+        let c = match self.characters.get_mut(idx) {
+            Some(c) => c,
+            None => return,
+        };
+        let mut changed = 0;
+        let mut held_back = 0;
+        for talent in self.selected_talents.iter().filter(|x| TALENTS.contains(x.as_str())) {
+            let max_rank = TALENT_LEVELS.get(talent.as_str()).copied().unwrap_or(1);
+            let current = c.talent_rank(talent);
+            let mut target = rank.unwrap_or(max_rank).clamp(0, max_rank);
+            if self.limit_talent_points && target > current {
+                let available = (c.talent_points_earned() - c.talent_points_spent()).max(0);
+                if target - current > available {
+                    held_back += 1;
+                    target = current + available;
+                }
+            }
+            if target != current {
+                c.set_talent_rank(talent, target);
+                changed += 1;
+            }
+        }
+
+        self.status = match held_back {
+            0 => format!("Set the rank of {} talents", changed),
+            n => format!("Set the rank of {} talents - {} couldn't go any higher with the points left", changed, n),
+        };
+    }
+
+    /// Adds the spawner's quantity of an item to a character's inventory, in stacks no bigger than
+    /// `items.txt` allows, with the spawner's durability if one is set.
+    pub fn spawn_item(&mut self, idx: usize, row_name: &str) {
+        let c = match self.characters.get_mut(idx) {
+            Some(c) => c,
+            None => return,
+        };
+        let max_stack = ITEM_STACK_SIZES.get(row_name).copied().unwrap_or(1.0) as u32;
+        let durability = Some(self.spawn_durability).filter(|x| *x > 0.0);
+        let stacks = c.inventory.spawn_item(row_name, self.spawn_quantity, max_stack, durability);
+        self.status = match stacks {
+            0 => "Nothing to add - the quantity is 0".to_string(),
+            1 => format!("Added {} {}", self.spawn_quantity, display_name(row_name)),
+            n => format!("Added {} {} in {} stacks", self.spawn_quantity, display_name(row_name), n),
+        };
+    }
+
+    /// Puts a character's build on the clipboard as a build code.
+    pub fn copy_build(&mut self, idx: usize) {
+        let code = build_code::encode(&self.characters[idx].build());
+        Application::global().clipboard().put_string(&code);
+        self.status = "Copied build to the clipboard".to_string();
+    }
+
+    pub fn paste_build(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        let code = Application::global().clipboard().get_string().ok_or("the clipboard doesn't have any text on it")?;
+        let build = build_code::decode(&code)?;
+        let unknown = self.characters[idx].apply_build(&build);
+        self.status = match unknown {
+            0 => "Pasted build".to_string(),
+            n => format!("Pasted build, leaving out {} talents and blueprints this editor doesn't know", n),
+        };
+
+        Ok(())
+    }
+
+    pub fn save_talent_preset(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        let preset = presets::save_preset(&self.preset_name, &self.characters[idx].build().talents)?;
+        self.talent_presets = presets::list_presets()?;
+        self.preset_name.clear();
+        self.status = format!("Saved talent preset {}", preset.name);
+
+        Ok(())
+    }
+
+    /// Replaces a character's talents with a preset's, keeping their blueprints.
+    pub fn apply_talent_preset(&mut self, idx: usize, preset: usize) -> Result<(), Box<dyn Error>> {
+        let preset = self.talent_presets.get(preset).cloned().ok_or("that preset no longer exists")?;
+        let c = &mut self.characters[idx];
+        let mut build = c.build();
+        build.talents = preset.talents()?;
+        let unknown = c.apply_build(&build);
+        self.status = match unknown {
+            0 => format!("Applied the {} preset", preset.name),
+            n => format!("Applied the {} preset, leaving out {} talents this editor doesn't know", preset.name, n),
+        };
+
+        Ok(())
+    }
+
+    pub fn delete_talent_preset(&mut self, preset: &TalentPreset) -> Result<(), Box<dyn Error>> {
+        presets::delete_preset(preset)?;
+        self.talent_presets = presets::list_presets()?;
+        self.status = format!("Deleted talent preset {}", preset.name);
+
+        Ok(())
+    }
+
+    /// Sets a character's talents and blueprints to the ranks in a CSV file of `row_name,rank` lines,
+    /// leaving anything the file doesn't mention alone. Rows can be given by display name too. A
+    /// rank of 0 removes the row.
+    pub fn import_ranks(&mut self, idx: usize, path: &Path) -> Result<(), Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let ranks = build_code::parse_ranks_csv(&text).map_err(|e| format!("[{}]: {}", path.to_string_lossy(), e))?;
+        let c = &mut self.characters[idx];
+        let mut unknown = Vec::new();
+        for (name, rank) in &ranks {
+            let row_name = TALENTS.iter().chain(BLUEPRINTS.iter())
+                .find(|x| x.eq_ignore_ascii_case(name) || display_name(x).eq_ignore_ascii_case(name))
+                .copied();
+            match row_name {
+                Some(row_name) if BLUEPRINTS.contains(row_name) => c.set_talent_rank(row_name, (*rank).min(1)),
+                Some(row_name) => c.set_talent_rank(row_name, *rank),
+                None => unknown.push(name.as_str()),
+            }
+        }
+        self.status = match unknown.len() {
+            0 => format!("Set {} talents and blueprints from the CSV", ranks.len()),
+            _ => format!("Set {} talents and blueprints from the CSV, leaving out ones this editor doesn't know: {}", ranks.len() - unknown.len(), unknown.join(", ")),
+        };
+
+        Ok(())
+    }
+
+    /// Adds the flag typed into `new_flag` to a character.
+    pub fn add_flag(&mut self, idx: usize) {
+        if let (Ok(flag), Some(c)) = (Flag::from_str(&self.new_flag), self.characters.get_mut(idx)) {
+            if !c.unlocked_flags.contains(&flag) {
+                c.unlocked_flags.push_back(flag);
+            }
+            self.new_flag.clear();
+        }
+    }
+
+    /// Who has `slot`, if anyone - including characters that couldn't be read.
+    fn slot_owner(&self, slot: Slot) -> Option<String> {
+        if let Some(c) = self.characters.iter().find(|c| c.character_slot == slot) {
+            return Some(format!("\"{}\"", c.character_name));
+        }
+        self.skipped_characters.iter()
+            .find(|(_, c)| validation::character_slot(c) == Some(slot))
+            .map(|(idx, c)| validation::describe_character(*idx, c))
+    }
+
+    /// A character's files can only be moved or copied if the editor has them in memory to write
+    /// under the new names, or they don't exist at all. `action` is what's being done, for the error.
+    fn check_files_movable(&self, idx: usize, action: &str) -> Result<(), Box<dyn Error>> {
+        let c = &self.characters[idx];
+        if !c.inventory_loaded && c.inventory_path.exists() {
+            Err(format!("\"{}\" has an inventory file that couldn't be read, so it can't be {}", c.character_name, action))?
+        }
+        if !c.loadout_loaded && c.loadout_path.exists() {
+            Err(format!("\"{}\" has a loadout file that couldn't be read, so it can't be {}", c.character_name, action))?
+        }
+
+        Ok(())
+    }
+
+    /// Moves a character to an empty slot. Its inventory and loadout are written under the new
+    /// slot's file names on the next save, and the old files removed then.
+    pub fn move_character(&mut self, idx: usize, slot: Slot) -> Result<(), Box<dyn Error>> {
+        if let Some(owner) = self.slot_owner(slot) {
+            Err(format!("slot {} is already used by {} - swap them instead", slot, owner))?
+        }
+        self.check_files_movable(idx, "moved")?;
+
+        let c = &mut self.characters[idx];
+        let old_files = [c.inventory_path.clone(), c.loadout_path.clone()];
+        c.character_slot = slot;
+        c.set_file_paths(&self.save_dir);
+        self.pending_deletions.extend(old_files);
+        self.status = format!("Moved character to slot {}", slot);
+
+        Ok(())
+    }
+
+    /// Swaps the slots, and with them the inventory and loadout file names, of a character and
+    /// whoever is in `slot`.
+    pub fn swap_characters(&mut self, idx: usize, slot: Slot) -> Result<(), Box<dyn Error>> {
+        let other = match self.characters.iter().position(|c| c.character_slot == slot) {
+            Some(other) => other,
+            None => match self.slot_owner(slot) {
+                Some(owner) => Err(format!("{} couldn't be read, so it can't be swapped", owner))?,
+                None => Err(format!("there's no character in slot {} - move to it instead", slot))?,
+            },
+        };
+        // Each character's files end up where the other's were, so both must be written out
+        for i in [idx, other] {
+            let c = &self.characters[i];
+            if !c.inventory_loaded || !c.loadout_loaded {
+                Err(format!("\"{}\" doesn't have both an inventory and a loadout loaded, so it can't be swapped", c.character_name))?
+            }
+        }
+
+        self.characters[other].character_slot = self.characters[idx].character_slot;
+        self.characters[idx].character_slot = slot;
+        for i in [idx, other] {
+            self.characters[i].set_file_paths(&self.save_dir);
+        }
+        self.status = format!("Swapped characters in slots {} and {}", self.characters[other].character_slot, slot);
+
+        Ok(())
+    }
+
+    /// Moves a character's tab `offset` places along, which is also where the character is listed
+    /// in `Characters.json`. Returns where the tab ends up, if it could move.
+    pub fn move_character_tab(&mut self, idx: usize, offset: isize) -> Option<usize> {
+        let target = idx.checked_add_signed(offset).filter(|x| *x < self.characters.len())?;
+        self.characters.swap(idx, target);
+        // A respec plan is tied to a tab, so it would otherwise follow the wrong character
+        self.respec_character = None;
+        self.respec_talents.clear();
+        self.status = format!("Moved \"{}\" to tab {}", self.characters[target].character_name, target + 1);
+
+        Some(target)
+    }
+
+    fn next_free_slot(&self) -> Slot {
+        let last = self.characters.iter().map(|c| c.character_slot)
+            .chain(self.skipped_characters.iter().filter_map(|(_, c)| validation::character_slot(c)))
+            .max();
+        // The game starts at slot 0
+        last.map(|x| Slot(x.0 + 1)).unwrap_or(Slot(0))
+    }
+
+    /// Adds a new level 1 character named `new_character_name` in the next free slot.
+    pub fn create_character(&mut self) -> Result<(), Box<dyn Error>> {
+        let name = self.new_character_name.trim().to_string();
+        if name.is_empty() {
+            Err("The new character needs a name")?
+        }
+        let character = Character::new(&name, self.next_free_slot(), &self.save_dir);
+        let slot = character.character_slot;
+        self.characters.push_back(character);
+        self.new_character_name = String::new();
+        self.status = format!("Created {} in slot {} - save to write them", name, slot);
+
+        Ok(())
+    }
+
+    /// Whether the save has anything from `dlc`, which is the only sign the save gives of owning it.
+    fn dlc_owned(&self, dlc: &str) -> bool {
+        let from_dlc = |t: &Talent| DLC.get(t.row_name.as_str()) == Some(&dlc);
+        self.profile.talents.iter().any(from_dlc) || self.characters.iter().any(|c| c.talents.iter().any(from_dlc))
+    }
+
+    /// The DLC whose rows are left out of the lists.
+    fn hidden_dlc(&self) -> Vector<String> {
+        if self.config.show_unowned_dlc {
+            return Vector::new();
+        }
+        DLC_NAMES.iter().filter(|x| !self.dlc_owned(x)).map(|x| x.to_string()).collect()
+    }
+
+    fn unlock_dlc(&mut self, dlc: &str) {
+        let rows = DLC.iter().filter(|(_, x)| **x == dlc).map(|(row_name, _)| *row_name).collect::<Vec<&'static str>>();
+        for row_name in rows.iter().copied().filter(|x| PROSPECTS.contains(x) || WORKSHOP_ITEMS.contains(x)) {
+            UnlockLens { row_name }.put(&mut self.profile.talents, true);
+        }
+        for c in self.characters.iter_mut() {
+            for row_name in rows.iter().copied() {
+                if TALENTS.contains(row_name) {
+                    c.set_talent_rank(row_name, TALENT_LEVELS.get(row_name).copied().unwrap_or(1));
+                } else if BLUEPRINTS.contains(row_name) {
+                    UnlockLens { row_name }.put(&mut c.talents, true);
+                }
+            }
+        }
+        self.status = format!("Unlocked everything from {}", dlc);
+    }
+
+    pub fn import_profile(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let imported = export::import_profile(path)?;
+        export::merge_profile(&mut self.profile, imported, self.profile_merge);
+        self.status = format!("Imported profile from [{}]", path.to_string_lossy());
+
+        Ok(())
+    }
+
+    pub fn export_character(&mut self, idx: usize, path: &Path) -> Result<(), Box<dyn Error>> {
+        export::export_character(&self.characters[idx], path)?;
+        self.status = format!("Exported character in slot {} to [{}]", self.characters[idx].character_slot, path.to_string_lossy());
+
+        Ok(())
+    }
+
+    /// Adds a character exported with "Export Character" in the next free slot. Its inventory and
+    /// loadout files are written on the next save.
+    pub fn import_character(&mut self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let export = export::import_character(path)?;
+        let mut character = export.character;
+        character.character_slot = self.next_free_slot();
+        character.set_file_paths(&self.save_dir);
+        character.inventory_loaded = export.inventory.is_some();
+        character.inventory = export.inventory.unwrap_or_default();
+        character.loadout_loaded = export.loadout.is_some();
+        character.loadout = export.loadout.unwrap_or_default();
+        self.status = format!("Imported character into slot {}", character.character_slot);
+        self.characters.push_back(character);
+
+        Ok(())
+    }
+
+    /// Copies a character into the next free slot, along with its inventory and loadout. Nothing is
+    /// written until the save is, when the copy's inventory and loadout files are written too.
+    pub fn duplicate_character(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        self.check_files_movable(idx, "duplicated")?;
+        let slot = self.next_free_slot();
+        let mut character = self.characters[idx].clone();
+        character.character_name = format!("{} (Copy)", character.character_name);
+        character.character_slot = slot;
+        character.set_file_paths(&self.save_dir);
+
+        self.status = format!("Duplicated character into slot {}", slot);
+        self.characters.push_back(character);
+
+        Ok(())
+    }
+
+    /// Removes a character. The change only reaches `Characters.json` on save; if `remove_files` is
+    /// set, the character's inventory and loadout files are also deleted then, after being backed up.
+    pub fn delete_character(&mut self, idx: usize, remove_files: bool) {
+        if idx >= self.characters.len() {
+            return;
+        }
+        let character = self.characters.remove(idx);
+        if remove_files {
+            self.pending_deletions.push(character.inventory_path);
+            self.pending_deletions.push(character.loadout_path);
+        }
+    }
+
+    /// Backs up every file in the save as it is on disk.
+    pub fn back_up_now(&mut self) {
+        let mut job = self.clone();
+        self.run_in_background("back up the save", "Backing up", move || {
+            job.check_writable()?;
+            job.backup_all()?;
+            Ok(job.backups)
+        }, |t, backups| {
+            t.backups = backups;
+            t.status = "Backed up every save file".to_string();
+
+            Ok(())
+        });
+    }
+
+    /// Asks before restoring `backup`, like any other bulk change.
+    pub fn request_restore_backup(&mut self, backup: &Backup) {
+        if let Some(backup) = self.backups.iter().position(|x| x == backup) {
+            self.request_bulk_action(BulkAction::RestoreBackup(backup));
+        }
+    }
+
+    pub fn restore_backup(&mut self, backup: &Backup) {
+        let mut job = self.clone();
+        let backup = backup.clone();
+        self.run_in_background("restore backup", "Restoring backup", move || {
+            job.check_writable()?;
+            // Take a backup of the current state first so restoring is never a one-way trip. Nothing
+            // is pruned here, as that could delete the very backup being restored
+            backup::create_backup(&job.save_dir, &job.save_files())?;
+            backup::restore_backup(&job.save_dir, &backup)?;
+            audit::append(&job.save_dir, &Vector::from(vec![AuditEntry::now("Save folder", "(current files)", &format!("restored backup {}", backup.name))]))?;
+            let restored = UiState::load_with(job.save_dir.clone(), true).map_err(|e| e.to_string())?;
+            Ok((restored, backup.name))
+        }, |t, (restored, name)| {
+            t.replace_with_loaded(restored);
+            t.status = format!("Restored backup {}", name);
+
+            Ok(())
+        });
+    }
+
+    /// Saves every file that's changed straight away, on this thread.
+    pub fn save(&mut self) -> Result<(), Box<dyn Error>> {
+        let files = self.changed_files();
+        if files.is_empty() {
+            return Ok(());
+        }
+        let mut job = self.clone();
+        job.write_save(&files)?;
+        self.finish_save(&job, &files);
+
+        Ok(())
+    }
+
+    /// Writes `files` on a background thread, then does `then`. Edits made while the save is being
+    /// written are kept, and still count as unsaved.
+    pub fn save_in_background(&mut self, files: Vec<PathBuf>, then: impl FnOnce(&mut UiState) + Send + 'static) {
+        let mut job = self.clone();
+        self.run_in_background("save", "Saving", move || {
+            job.write_save(&files).map_err(|e| e.to_string())?;
+            Ok((job, files))
+        }, move |t, (job, files)| {
+            t.finish_save(&job, &files);
+            then(t);
+
+            Ok(())
+        });
+    }
+
+    /// Writes the save files in `files`, after backing up all of them. Characters' files waiting to
+    /// be deleted are only removed once `Characters.json` and every character's inventory and
+    /// loadout are written, as a moved character's old files may be the only copy of their items
+    /// until then. Run on a copy of the editor's state, which may be on another thread, so nothing
+    /// here may be needed by the editor afterwards except through `finish_save`.
+    fn write_save(&mut self, files: &[PathBuf]) -> Result<(), Box<dyn Error>> {
+        self.check_writable()?;
+        let characters = files.contains(&self.characters_file);
+        let changed = self.changed_files();
+        let left_unsaved = |path: &PathBuf| changed.contains(path) && !files.contains(path);
+        let keep_old_files = left_unsaved(&self.characters_file)
+            || self.characters.iter().any(|c| left_unsaved(&c.inventory_path) || left_unsaved(&c.loadout_path));
+        if characters {
+            for c in &self.characters {
+                if let Some(problem) = validation::character_name_problem(&c.character_name) {
+                    Err(format!("character in slot {}: {}", c.character_slot, problem))?
+                }
+            }
+        }
+        self.backup_all()?;
+        self.store().save_only(files)?;
+        if keep_old_files {
+            self.pending_deletions.clear();
+        }
+        // A deletion may have been undone since it was requested, so never remove files that are in use
+        for path in &self.pending_deletions {
+            let in_use = self.characters.iter().any(|c| &c.inventory_path == path || &c.loadout_path == path);
+            if !in_use && path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        audit::append(&self.save_dir, &self.audit_pending)?;
+        self.remember_disk_times();
+
+        Ok(())
+    }
+
+    /// Brings the editor up to date with a save of `files` that `job` has written. The editor may
+    /// have moved on since the job was started, so only what the job actually wrote is marked as
+    /// saved.
+    fn finish_save(&mut self, job: &UiState, files: &[PathBuf]) {
+        let saved = self.saved_after_writing(&job.snapshot(), files);
+        self.history.mark_saved_as(saved);
+        self.audit_pending = self.audit_pending.skip(job.audit_pending.len());
+        self.pending_deletions.retain(|x| !job.pending_deletions.contains(x));
+        self.backups = job.backups.clone();
+        self.disk_times = job.disk_times.clone();
+        self.external_change = false;
+        self.last_saved = Some(Local::now().format("%H:%M:%S").to_string());
+        self.status = match files.len() {
+            1 => "Saved 1 file".to_string(),
+            n => format!("Saved {} files", n),
+        };
+    }
+}
+
+/// Above this many characters the tab names no longer fit across the window, so they're listed down
+/// the side instead, in a list that scrolls once there are too many for the window as well.
+const MAX_CHARACTER_TABS_ACROSS: usize = 8;
+
+fn character_tabs() -> impl Widget<UiState> {
+    let tabs = ViewSwitcher::new(
+        |data: &UiState, _env| data.characters.len() > MAX_CHARACTER_TABS_ACROSS,
+        |down_the_side, _data: &UiState, _env| -> Box<dyn Widget<UiState>> {
+            if *down_the_side {
+                Box::new(character_list())
+            } else {
+                Box::new(Tabs::for_policy(CharTabs{}).controller(CharacterTabShortcuts))
+            }
+        }
+    );
+    Either::new(|data: &UiState, _env| data.characters.is_empty(), no_characters_panel(), tabs)
+}
+
+/// The character tabs as a scrolling list down the side, with the open character's tab next to it.
+fn character_list() -> impl Widget<UiState> {
+    let names = ViewSwitcher::new(
+        |data: &UiState, _env| data.characters.len(),
+        |count, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for idx in 0..*count {
+                list.add_child(Button::new(move |t: &UiState, _env: &Env| {
+                    let name = character_tab_label(t, idx);
+                    if t.selected_character == idx { format!("▶ {}", name) } else { name }
+                }).on_click(move |_ctx, t: &mut UiState, _env| t.selected_character = idx));
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+    let body = ViewSwitcher::new(
+        |data: &UiState, _env| {
+            let idx = data.selected_character.min(data.characters.len().saturating_sub(1));
+            data.characters.get(idx).map(|c| (idx, c.character_slot))
+        },
+        |key, data: &UiState, _env| match key {
+            Some(key) => CharTabs{}.tab_body(*key, data),
+            None => Box::new(Flex::column()),
+        }
+    );
+
+    Flex::row()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(names.fix_width(200.0))
+        .with_default_spacer()
+        .with_flex_child(body, 1.0)
+        .controller(CharacterListShortcuts)
+}
+
+/// In place of the character tabs for a save with no characters yet, with a way to make one.
+fn no_characters_panel() -> impl Widget<UiState> {
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("There are no characters in this save yet").with_text_size(theme::HEADING_TEXT_SIZE))
+        .with_default_spacer()
+        .with_child(Label::new("Icarus only writes Characters.json once the first offline character has been made. \
+            Make one in the game and reload, or make one here - it's written, along with an empty inventory and loadout, when you save.")
+            .with_line_break_mode(LineBreaking::WordWrap)
+            .fix_width(600.0))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(TextBox::new().with_placeholder("Character name").fix_width(200.0).lens(UiState::new_character_name_lens))
+            .with_default_spacer()
+            .with_child(edit_button("Create Character", Edit::CreateCharacter)
+                .disabled_if(|t: &UiState, _env| t.new_character_name.trim().is_empty() || t.config.safe_mode)))
+}
+
+#[derive(Clone, Data)]
+struct CharTabs {
+
+}
+
+/// A character's name for their tab. A character with no name would otherwise get a blank tab
+/// that there's no way to tell apart or describe.
+fn character_tab_name(c: &Character) -> String {
+    if c.character_name.trim().is_empty() {
+        format!("Unnamed (slot {})", c.character_slot)
+    } else {
+        c.character_name.clone()
+    }
+}
+
+/// What a character's tab says, with a warning sign if their files have a problem.
+fn character_tab_label(state: &UiState, idx: usize) -> String {
+    match state.characters.get(idx) {
+        Some(c) if file_warning(c).is_some() => format!("⚠ {}", character_tab_name(c)),
+        Some(c) => character_tab_name(c),
+        None => String::new(),
+    }
+}
+
+impl TabsPolicy for CharTabs {
+    // Tab bodies are built around a character's index, so it's part of the key to make sure tabs
+    // are rebuilt when characters move
+    type Key = (usize, Slot);
+    type Input = UiState;
+    type BodyWidget = Box<dyn Widget<UiState>>;
+    type LabelWidget = Label<UiState>;
+    type Build = ();
+
+    fn tabs_changed(&self, old_data: &Self::Input, data: &Self::Input) -> bool {
+        !old_data.characters.eq(&data.characters)
+    }
+
+    fn tabs(&self, data: &Self::Input) -> Vec<Self::Key> {
+        data.characters.iter().enumerate().map(|(idx, x)| (idx, x.character_slot)).collect()
+    }
+
+    fn tab_info(&self, (idx, _slot): Self::Key, _: &Self::Input) -> TabInfo<Self::Input> {
+        println!("Loading tab info for index {}", idx);
+        TabInfo::new(LabelText::from(move |state: &UiState, _: &Env| character_tab_label(state, idx)), false)
+    }
+
+    #[cfg(feature = "full")]
+    fn tab_body(&self, (idx, _slot): Self::Key, data: &Self::Input) -> Self::BodyWidget {
+        println!("Loading tab body for index {}", idx);
+        let character_lens = UiState::characters_lens.index(idx);
+        let character = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(file_warning_row(idx))
+            .with_child(summary_header(idx))
+            .with_default_spacer()
+            .with_child(name_row(idx))
+            .with_default_spacer()
+            .with_child(slot_row(idx))
+            .with_default_spacer()
+            .with_child(location_rows(idx, data))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Label::new("XP"))
+                .with_default_spacer()
+                .with_child(xp_box("XP", idx, XpField::Xp, character_lens.clone().then(Character::xp)))
+                .with_default_spacer()
+                .with_child(edit_button("Max Level", Edit::MaxLevel(idx)))
+                .with_default_spacer()
+                .with_child(xp_error_label(idx, XpField::Xp))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Label::new("Level"))
+                .with_default_spacer()
+                .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Level"), ParseFormatter::<usize>::new()).fix_width(60.0).lens(character_lens.clone().then(LevelLens)))
+                .with_default_spacer()
+                .with_child(Label::new(|c: &Character, _env: &Env| {
+                    match LEVEL_XP.get(c.level()) {
+                        Some(next) => format!("{} XP to level {}", next - c.xp.0, c.level() + 1),
+                        None => "Max level".to_string(),
+                    }
+                }).lens(character_lens.clone()))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Label::new("XP Debt"))
+                .with_default_spacer()
+                .with_child(xp_box("XP debt", idx, XpField::XpDebt, character_lens.clone().then(Character::xp_debt)))
+                .with_default_spacer()
+                .with_child(edit_button("Clear XP Debt", Edit::ClearXpDebt(idx)))
+                .with_default_spacer()
+                .with_child(edit_button("Take Debt from XP", Edit::PayXpDebt(idx)))
+                .with_default_spacer()
+                .with_child(xp_error_label(idx, XpField::XpDebt))
+            ).with_default_spacer()
+            .with_child(Label::new(|c: &Character, _env: &Env| {
+                if c.xp_debt.0 <= 0 {
+                    "No XP debt".to_string()
+                } else {
+                    let levels = c.level() - c.level_after_debt();
+                    format!("Taking the debt from XP would leave level {} (a loss of {} level{})", c.level_after_debt(), levels, if levels == 1 { "" } else { "s" })
+                }
+            }).lens(character_lens.clone()))
+            .with_default_spacer()
+            .with_child(dead_row(idx))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Checkbox::new("Abandoned")
+                    .disabled_if(|state: &bool, _ctx| !*state)
+                    .lens(character_lens.clone().then(Character::is_abandoned)))
+                .with_child(Button::new("Restore Character")
+                    .on_click(move |_ctx, t: &mut UiState, _env| t.request_bulk_action(BulkAction::RestoreCharacter(idx)))
+                    .disabled_if(move |state: &UiState, _ctx| !state.characters[idx].is_abandoned || state.config.safe_mode))
+            ).with_default_spacer()
+            .with_child(rescue_row(idx))
+            .with_default_spacer()
+            .with_child(snapshots_panel(idx))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::ResetTalents(idx)))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::ResetBlueprints(idx)))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::UnlockAllTalents(idx)))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::UnlockAllBlueprints(idx)))
+                .with_default_spacer()
+                .with_child(bulk_action_button(BulkAction::UnlockBlueprintsToLevel(idx)))
+            ).with_default_spacer()
+            .with_child(level_cap_panel(idx))
+            .with_default_spacer()
+            .with_child(flags_panel(idx))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(edit_button("Duplicate Character", Edit::DuplicateCharacter(idx))
+                    .disabled_if(|t: &UiState, _env| t.config.safe_mode))
+                .with_default_spacer()
+                .with_child(Button::new("Delete Character")
+                    .on_click(move |_ctx, t: &mut UiState, _env| t.prompt = Some(Prompt::DeleteCharacter(idx))))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Button::new("Export Character...").on_click(move |ctx, t: &mut UiState, _env| {
+                    t.pending_export = Some(idx);
+                    let options = FileDialogOptions::new()
+                        .allowed_types(vec![CHARACTER_EXPORT_FILE])
+                        .default_name(format!("{}.json", t.characters[idx].character_name))
+                        .accept_command(EXPORT_CHARACTER);
+                    ctx.submit_command(commands::SHOW_SAVE_PANEL.with(options));
+                }))
+                .with_default_spacer()
+                .with_child(Button::new("Import Character...").on_click(|ctx, _t: &mut UiState, _env| {
+                    let options = FileDialogOptions::new()
+                        .allowed_types(vec![CHARACTER_EXPORT_FILE])
+                        .accept_command(IMPORT_CHARACTER);
+                    ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+                }))
+            ).with_default_spacer()
+            .with_child(character_files_row(idx));
+
+        Tabs::new()
+            .with_tab("Character", character)
+            .with_tab("Talents", talents_tab(idx))
+            .with_tab("Blueprints", blueprints_tab(idx))
+            .with_tab("Inventory", inventory_tab(idx))
+            .with_tab("Loadout", loadout_tab(idx))
+            .with_tab("Appearance", appearance_tab(idx))
+            .with_tab("Advanced", advanced_tab(RawTarget::Character(idx)))
+            .boxed()
+    }
+
+    #[cfg(feature = "minimal")]
+    fn tab_body(&self, (idx, _slot): Self::Key, data: &Self::Input) -> Self::BodyWidget {
+        println!("Loading tab body for index {}", idx);
+        let character_lens = UiState::characters_lens.index(idx);
+        let character = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(file_warning_row(idx))
+            .with_child(summary_header(idx))
+            .with_default_spacer()
+            .with_child(name_row(idx))
+            .with_default_spacer()
+            .with_child(slot_row(idx))
+            .with_default_spacer()
+            .with_child(location_rows(idx, data))
+            .with_default_spacer()
+            .with_child(dead_row(idx))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Checkbox::new("Abandoned")
+                    .disabled_if(|state: &bool, _ctx| !*state)
+                    .lens(character_lens.clone().then(Character::is_abandoned)))
+                .with_child(Button::new("Restore Character")
+                    .on_click(move |_ctx, t: &mut UiState, _env| t.request_bulk_action(BulkAction::RestoreCharacter(idx)))
+                    .disabled_if(move |state: &UiState, _ctx| !state.characters[idx].is_abandoned || state.config.safe_mode))
+            ).with_default_spacer()
+            .with_child(rescue_row(idx))
+            .with_default_spacer()
+            .with_child(snapshots_panel(idx))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::ResetTalents(idx)))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(bulk_action_button(BulkAction::ResetBlueprints(idx)))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(edit_button("Duplicate Character", Edit::DuplicateCharacter(idx))
+                    .disabled_if(|t: &UiState, _env| t.config.safe_mode))
+                .with_default_spacer()
+                .with_child(Button::new("Delete Character")
+                    .on_click(move |_ctx, t: &mut UiState, _env| t.prompt = Some(Prompt::DeleteCharacter(idx))))
+            ).with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Button::new("Export Character...").on_click(move |ctx, t: &mut UiState, _env| {
+                    t.pending_export = Some(idx);
+                    let options = FileDialogOptions::new()
+                        .allowed_types(vec![CHARACTER_EXPORT_FILE])
+                        .default_name(format!("{}.json", t.characters[idx].character_name))
+                        .accept_command(EXPORT_CHARACTER);
+                    ctx.submit_command(commands::SHOW_SAVE_PANEL.with(options));
+                }))
+                .with_default_spacer()
+                .with_child(Button::new("Import Character...").on_click(|ctx, _t: &mut UiState, _env| {
+                    let options = FileDialogOptions::new()
+                        .allowed_types(vec![CHARACTER_EXPORT_FILE])
+                        .accept_command(IMPORT_CHARACTER);
+                    ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+                }))
+            ).with_default_spacer()
+            .with_child(character_files_row(idx));
+
+        Tabs::new()
+            .with_tab("Character", character)
+            .with_tab("Loadout", loadout_tab(idx))
+            .boxed()
+    }
+
+    fn tab_label(&self, _: Self::Key, info: TabInfo<Self::Input>, _: &Self::Input) -> Self::LabelWidget {
+        Self::default_make_label(info)
+    }
+}
+
+#[cfg(feature = "full")]
+fn talents_tab(idx: usize) -> impl Widget<UiState> {
+    let character_lens = UiState::characters_lens.index(idx);
+    let tree_lens = character_lens.clone();
+    let tree = ViewSwitcher::new(
+        |data: &UiState, _env| (data.search.clone(), data.hidden_dlc()),
+        move |(search, hidden), _data: &UiState, _env| {
+            let character_lens = tree_lens.clone();
+            let mut tree = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (category, talents) in TALENT_CATEGORIES.iter() {
+                let matching = talents.iter().copied().filter(|x| row_matches(search, x) && !dlc_hidden(hidden, x)).collect::<Vec<&'static str>>();
+                if matching.is_empty() {
+                    continue;
+                }
+                let in_category = matching.clone();
+                tree.add_child(Flex::row()
+                    .with_child(Label::new(*category).with_text_size(theme::HEADING_TEXT_SIZE))
+                    .with_default_spacer()
+                    .with_child(Button::new("Select All").on_click(move |_ctx, t: &mut UiState, _env| {
+                        for talent in &in_category {
+                            if !t.selected_talents.iter().any(|x| x == talent) {
+                                t.selected_talents.push_back(talent.to_string());
+                            }
+                        }
+                    }))
+                );
+                for talent in matching {
+                    let max_rank = TALENT_LEVELS.get(talent).copied().unwrap_or(1);
+                    let respec = Either::new(
+                        move |t: &UiState, _env| t.respec_character == Some(idx),
+                        Checkbox::new("Refund")
+                            .lens(UiState::respec_talents_lens.then(ListedLens { row_name: talent }))
+                            .disabled_if(move |t: &UiState, _env| t.characters[idx].talent_rank(talent) <= 0),
+                        Checkbox::new("Select").lens(UiState::selected_talents_lens.then(ListedLens { row_name: talent })),
+                    );
+                    tree.add_child(Flex::row()
+                        .with_child(respec)
+                        .with_child(Label::new(display_name(talent)).fix_width(200.0))
+                        .with_child(row_name_label(talent).fix_width(200.0))
+                        .with_default_spacer()
+                        .with_child(Label::new(move |c: &Character, _env: &Env| format!("{} / {}", c.talent_rank(talent), max_rank)).fix_width(60.0).lens(character_lens.clone()))
+                        .with_default_spacer()
+                        .with_child(edit_button("-", Edit::ChangeTalentRank(idx, talent, -1))
+                            .disabled_if(move |t: &Character, _env| t.talent_rank(talent) <= 0)
+                            .lens(character_lens.clone()))
+                        .with_child(edit_button("+", Edit::ChangeTalentRank(idx, talent, 1))
+                            .disabled_if(move |t: &UiState, _env| {
+                                let c = &t.characters[idx];
+                                c.talent_rank(talent) >= max_rank || (t.limit_talent_points && c.talent_points_spent() >= c.talent_points_earned())
+                            }))
+                    );
+                }
+                tree.add_default_spacer();
+            }
+            Box::new(Scroll::new(tree).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(|c: &Character, _env: &Env| format!("Talent points spent: {} of {} earned at level {}", c.talent_points_spent(), c.talent_points_earned(), c.level())).lens(character_lens.clone()))
+        .with_child(Label::new(|c: &Character, _env: &Env| {
+            let over = c.talent_points_spent() - c.talent_points_earned();
+            if over > 0 {
+                format!("Warning: {} more talent points spent than this level allows", over)
+            } else {
+                String::new()
+            }
+        }).with_text_color(Color::rgb8(0xE0, 0x40, 0x40)).lens(character_lens))
+        .with_child(edit_button("Remove Excess Talent Points", Edit::TrimTalentPoints(idx))
+            .disabled_if(move |t: &UiState, _env| t.characters[idx].talent_points_spent() <= t.characters[idx].talent_points_earned()))
+        .with_child(Label::new(|c: &Character, _env: &Env| {
+            c.missing_prerequisites().iter()
+                .map(|(talent, required)| format!("Warning: {} needs {}", display_name(talent), display_name(required)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }).with_text_color(Color::rgb8(0xE0, 0x40, 0x40)).lens(UiState::characters_lens.index(idx)))
+        .with_child(Checkbox::new("Don't allow spending more talent points than earned").lens(UiState::limit_talent_points_lens))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Copy Build").on_click(move |_ctx, t: &mut UiState, _env| t.copy_build(idx)))
+            .with_default_spacer()
+            .with_child(bulk_action_button(BulkAction::PasteBuild(idx)))
+            .with_default_spacer()
+            .with_child(Button::new("Import Ranks from CSV...").on_click(move |ctx, t: &mut UiState, _env| {
+                t.pending_rank_import = Some(idx);
+                let options = FileDialogOptions::new()
+                    .allowed_types(vec![CSV_FILE])
+                    .title("Choose a CSV of row_name,rank lines")
+                    .accept_command(IMPORT_RANKS);
+                ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+            }))
+        )
+        .with_default_spacer()
+        .with_child(presets_panel(idx))
+        .with_default_spacer()
+        .with_child(respec_row(idx))
+        .with_default_spacer()
+        .with_child(selection_row(idx))
+        .with_default_spacer()
+        .with_flex_child(tree, 1.0)
+}
+
+/// Talent presets, built in or saved from a character, to give to this character in one go.
+#[cfg(feature = "full")]
+fn presets_panel(idx: usize) -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        |data: &UiState, _env| data.talent_presets.clone(),
+        move |presets, _data: &UiState, _env| {
+            let mut list = Flex::row();
+            for (preset_idx, preset) in presets.iter().enumerate() {
+                let delete = preset.clone();
+                let action = BulkAction::ApplyTalentPreset(idx, preset_idx);
+                let mut row = Flex::row()
+                    .with_child(Label::new(preset.name.clone()))
+                    .with_default_spacer()
+                    .with_child(Button::new(action.label()).on_click(move |_ctx, t: &mut UiState, _env| t.request_bulk_action(action)));
+                if !preset.is_built_in() {
+                    row.add_child(Button::new("Delete").on_click(move |_ctx, t: &mut UiState, _env| {
+                        t.run_action("delete talent preset", |t| t.delete_talent_preset(&delete));
+                    }));
+                }
+                list.add_child(row);
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).horizontal())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(Label::new("Presets"))
+            .with_default_spacer()
+            .with_child(TextBox::new().with_placeholder("Preset name").fix_width(200.0).lens(UiState::preset_name_lens))
+            .with_default_spacer()
+            .with_child(Button::new("Save Talents as Preset")
+                .on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("save talent preset", |t| t.save_talent_preset(idx)); })))
+        .with_child(list)
+}
+
+/// Sets the rank of every talent ticked in the list below at once.
+#[cfg(feature = "full")]
+fn selection_row(idx: usize) -> impl Widget<UiState> {
+    let no_selection = |t: &UiState, _env: &Env| t.selected_talents.is_empty();
+    Flex::row()
+        .with_child(Label::new(|t: &UiState, _env: &Env| format!("{} selected", t.selected_talents.len())))
+        .with_default_spacer()
+        .with_child(Label::new("Rank"))
+        .with_default_spacer()
+        .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Rank"), ParseFormatter::<i64>::new()).fix_width(50.0).lens(UiState::selection_rank_lens))
+        .with_default_spacer()
+        .with_child(Button::new("Set Rank")
+            .on_click(move |ctx, t: &mut UiState, _env| ctx.submit_command(EDIT.with(Edit::SetSelectedTalentRanks(idx, Some(t.selection_rank)))))
+            .disabled_if(no_selection))
+        .with_default_spacer()
+        .with_child(edit_button("Max Rank", Edit::SetSelectedTalentRanks(idx, None))
+            .disabled_if(no_selection))
+        .with_default_spacer()
+        .with_child(Button::new("Clear Selection")
+            .on_click(|_ctx, t: &mut UiState, _env| t.selected_talents.clear())
+            .disabled_if(no_selection))
+}
+
+/// The profile or a character as raw JSON, for fields the rest of the editor doesn't cover.
+#[cfg(feature = "full")]
+fn advanced_tab(target: RawTarget) -> impl Widget<UiState> {
+    let what = match target {
+        RawTarget::Profile => "the profile",
+        RawTarget::Character(_) => "this character",
+    };
+    let conflict = Flex::row()
+        .with_child(Label::new("This was changed on another tab while the JSON had a mistake in it. Keep which?")
+            .with_text_color(Color::rgb8(0xE0, 0xA0, 0x20)))
+        .with_default_spacer()
+        .with_child(Button::new("Keep the JSON")
+            .on_click(|_ctx, t: &mut UiState, _env| t.keep_raw_json())
+            .disabled_if(|t: &UiState, _env| t.raw_json_error.is_some()))
+        .with_default_spacer()
+        .with_child(Button::new("Keep the Other Changes").on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("show raw JSON", |t| t.edit_raw_json(target)); }));
+    let editor = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(Button::new("Reload").on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("show raw JSON", |t| t.edit_raw_json(target)); }))
+            .with_default_spacer()
+            .with_child(Button::new("Close").on_click(|_ctx, t: &mut UiState, _env| t.close_raw_json())))
+        .with_child(Label::new(|t: &UiState, _env: &Env| t.raw_json_error.clone().map(|e| format!("Not used until fixed: {}", e)).unwrap_or_default())
+            .with_text_color(Color::rgb8(0xE0, 0x40, 0x40)))
+        .with_child(Either::new(|t: &UiState, _env| t.raw_json_conflict, conflict, Flex::row()))
+        .with_default_spacer()
+        .with_flex_child(TextBox::multiline().with_placeholder("JSON").expand().lens(UiState::raw_json_lens), 1.0);
+
+    Either::new(
+        move |t: &UiState, _env| t.raw_json_target == Some(target),
+        editor,
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(Label::new(format!("Edit {} as JSON, including fields the other tabs don't show.", what)))
+            .with_child(Label::new("Valid changes show up on the other tabs as you type and, like any other edit, are only written when you save."))
+            .with_default_spacer()
+            .with_child(Button::new("Edit Raw JSON").on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("show raw JSON", |t| t.edit_raw_json(target)); })),
+    )
+}
+
+/// Two characters side by side, showing only what differs between them.
+#[cfg(feature = "full")]
+fn compare_tab() -> impl Widget<UiState> {
+    let pickers = ViewSwitcher::new(
+        |data: &UiState, _env| data.characters.iter().map(|c| c.character_name.clone()).collect::<Vector<String>>(),
+        |names, _data: &UiState, _env| {
+            let choices = || names.iter().enumerate().map(|(idx, name)| (name.clone(), idx)).collect::<Vec<(String, usize)>>();
+            Box::new(Flex::row()
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(RadioGroup::column(choices()).lens(UiState::compare_left_lens).fix_width(250.0))
+                .with_default_spacer()
+                .with_child(RadioGroup::column(choices()).lens(UiState::compare_right_lens).fix_width(250.0)))
+        }
+    );
+
+    let differences = ViewSwitcher::new(
+        |data: &UiState, _env| (data.characters.get(data.compare_left).cloned(), data.characters.get(data.compare_right).cloned()),
+        |(left, right), _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            let (left, right) = match (left, right) {
+                (Some(left), Some(right)) => (left, right),
+                _ => return Box::new(Label::new("Pick two characters to compare")),
+            };
+            list.add_child(Flex::row()
+                .with_child(Label::new("").fix_width(250.0))
+                .with_child(Label::new(left.character_name.clone()).fix_width(150.0))
+                .with_child(Label::new(right.character_name.clone()).fix_width(150.0))
+            );
+            let differences = compare::compare(left, right);
+            if differences.is_empty() {
+                list.add_child(Label::new("No differences in XP, talents, blueprints or flags"));
+            }
+            let mut section = "";
+            for difference in differences {
+                if difference.section != section {
+                    section = difference.section;
+                    list.add_child(Label::new(section).with_text_size(theme::HEADING_TEXT_SIZE));
+                }
+                list.add_child(Flex::row()
+                    .with_child(Label::new(difference.name).fix_width(250.0))
+                    .with_child(Label::new(difference.left).fix_width(150.0))
+                    .with_child(Label::new(difference.right).fix_width(150.0))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(pickers)
+        .with_default_spacer()
+        .with_flex_child(differences, 1.0)
+}
+
+/// Starts a respec plan, or while planning, shows what it refunds and applies it. Talents are
+/// ticked for removal in the list below.
+#[cfg(feature = "full")]
+fn respec_row(idx: usize) -> impl Widget<UiState> {
+    let planning = Flex::row()
+        .with_child(Label::new(move |t: &UiState, _env: &Env| {
+            let c = &t.characters[idx];
+            let refund = c.respec_refund(&t.respec_talents);
+            let available = c.talent_points_earned() - c.talent_points_spent() + refund;
+            format!("Tick talents to remove. Refunds {} points, leaving {} to spend", refund, available)
+        }))
+        .with_default_spacer()
+        .with_child(edit_button("Apply Respec", Edit::ApplyRespec)
+            .disabled_if(|t: &UiState, _env| t.respec_talents.is_empty()))
+        .with_default_spacer()
+        .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| {
+            t.respec_character = None;
+            t.respec_talents.clear();
+        }));
+    let start = Button::new("Plan Respec...").on_click(move |_ctx, t: &mut UiState, _env| {
+        t.respec_character = Some(idx);
+        t.respec_talents.clear();
+    });
+
+    Either::new(move |t: &UiState, _env| t.respec_character == Some(idx), planning, start)
+}
+
+/// A checkbox for each flag in `flags.txt`, then any other flags the character has by number.
+#[cfg(feature = "full")]
+fn flags_panel(idx: usize) -> impl Widget<UiState> {
+    let flags_lens = UiState::characters_lens.index(idx).then(Character::unlocked_flags);
+    let mut known = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for (flag, description, help) in FLAGS.iter() {
+        known.add_child(Flex::row()
+            .with_child(Checkbox::new(*description).lens(flags_lens.clone().then(FlagLens { flag: *flag })))
+            .with_default_spacer()
+            .with_child(help_label(help)));
+    }
+
+    let other = ViewSwitcher::new(
+        move |data: &UiState, _env| data.characters[idx].unlocked_flags.clone(),
+        move |flags, _data: &UiState, _env| {
+            let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for flag in flags.iter().copied().filter(|flag| !FLAGS.iter().any(|(known, _, _)| known == flag)) {
+                column.add_child(Flex::row()
+                    .with_child(Label::new(format!("Flag {}", flag)))
+                    .with_default_spacer()
+                    .with_child(edit_button("Remove", Edit::RemoveFlag(idx, flag))));
+            }
+            Box::new(column)
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Unlocked Flags"))
+        .with_child(known)
+        .with_child(other)
+        .with_child(Flex::row()
+            .with_child(TextBox::new().with_placeholder("Flag number").lens(UiState::new_flag_lens))
+            .with_default_spacer()
+            .with_child(edit_button("Add Flag", Edit::AddFlag(idx))
+                .disabled_if(|t: &UiState, _env| Flag::from_str(&t.new_flag).is_err())))
+}
+
+/// Level cap and prestige fields, which the game keeps on the character but the editor otherwise
+/// only knows as unknown fields.
+#[cfg(feature = "full")]
+fn level_cap_panel(idx: usize) -> impl Widget<UiState> {
+    let character_lens = UiState::characters_lens.index(idx);
+    let fields = ViewSwitcher::new(
+        move |data: &UiState, _env| level_cap_fields(&data.characters[idx]).into_iter().map(|(key, _)| key).collect::<Vector<String>>(),
+        move |_keys, data: &UiState, _env| {
+            let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            let fields = level_cap_fields(&data.characters[idx]);
+            if fields.is_empty() {
+                column.add_child(Label::new("This character has no level cap or prestige fields"));
+            }
+            for (key, help) in fields {
+                column.add_child(Flex::row()
+                    .with_child(Label::new(key.clone()).fix_width(150.0))
+                    .with_default_spacer()
+                    .with_child(ValueTextBox::new(TextBox::new().with_placeholder(key.clone()), ParseFormatter::<f64>::new())
+                        .fix_width(80.0)
+                        .lens(character_lens.clone().then(ExtraNumberLens { key })))
+                    .with_default_spacer()
+                    .with_child(help_label(help)));
+            }
+            Box::new(column)
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Level Cap and Prestige"))
+        .with_child(fields)
+}
+
+/// Dead characters can only be revived after confirming, since it undoes the game's own rules.
+fn dead_row(idx: usize) -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(Checkbox::new("Dead").lens(UiState::characters_lens.index(idx).then(Character::is_dead)).disabled_if(|_, _| true))
+        .with_child(Button::new("Revive...")
+            .on_click(move |_ctx, t: &mut UiState, _env| t.prompt = Some(Prompt::Revive(idx)))
+            .disabled_if(move |state: &UiState, _env| !state.characters[idx].is_dead))
+}
+
+fn rescue_row(idx: usize) -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(Button::new("Rescue from Prospect")
+            .on_click(move |_ctx, t: &mut UiState, _env| t.request_bulk_action(BulkAction::RescueCharacter(idx)))
+            .disabled_if(|t: &UiState, _env| t.config.safe_mode))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Wipe inventory when restoring or rescuing").lens(UiState::wipe_inventory_lens))
+}
+
+/// Snapshots of the character, kept by the editor so different builds can be tried and rolled back.
+fn snapshots_panel(idx: usize) -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        move |data: &UiState, _env| {
+            let slot = data.characters[idx].character_slot;
+            data.snapshots.iter().filter(|x| x.slot == slot).cloned().collect::<Vector<Snapshot>>()
+        },
+        move |snapshots, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for snapshot in snapshots.iter() {
+                let (roll_back, delete) = (snapshot.clone(), snapshot.clone());
+                list.add_child(Flex::row()
+                    .with_child(Label::new(snapshot.name.clone()))
+                    .with_default_spacer()
+                    .with_child(Button::new("Roll Back to Snapshot").on_click(move |_ctx, t: &mut UiState, _env| {
+                        if let Some(snapshot) = t.snapshots.iter().position(|x| *x == roll_back) {
+                            t.request_bulk_action(BulkAction::RollBackToSnapshot(idx, snapshot));
+                        }
+                    }))
+                    .with_default_spacer()
+                    .with_child(Button::new("Delete").on_click(move |_ctx, t: &mut UiState, _env| {
+                        t.run_action("delete snapshot", |t| t.delete_snapshot(&delete));
+                    }))
+                );
+            }
+            Box::new(list)
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(TextBox::new().with_placeholder("Snapshot name").fix_width(200.0).lens(UiState::snapshot_name_lens))
+            .with_default_spacer()
+            .with_child(Button::new("Snapshot Character")
+                .on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("take snapshot", |t| t.take_snapshot(idx)); })))
+        .with_child(list)
+}
+
+/// The character's name, which is also the tab title, with a warning if the game wouldn't accept it.
+fn name_row(idx: usize) -> impl Widget<UiState> {
+    let name_lens = UiState::characters_lens.index(idx).then(Character::character_name);
+    Flex::row()
+        .with_child(Label::new("Name"))
+        .with_default_spacer()
+        .with_child(TextBox::new().with_placeholder("Character name").fix_width(200.0).lens(name_lens.clone()))
+        .with_default_spacer()
+        .with_child(Label::new(|name: &String, _env: &Env| validation::character_name_problem(name).map(|x| format!("Warning: {}", x)).unwrap_or_default())
+            .with_text_color(Color::rgb8(0xE0, 0x40, 0x40))
+            .lens(name_lens))
+}
+
+/// Moves the character to another slot, or swaps slots with the character there.
+fn slot_row(idx: usize) -> impl Widget<UiState> {
+    fn target_slot(t: &UiState) -> Option<Slot> {
+        Slot::from_str(&t.target_slot).ok()
+    }
+
+    Flex::row()
+        .with_child(Label::new(move |t: &UiState, _env: &Env| format!("Slot {}", t.characters[idx].character_slot)))
+        .with_default_spacer()
+        .with_child(TextBox::new().with_placeholder("New slot").fix_width(80.0).lens(UiState::target_slot_lens))
+        .with_default_spacer()
+        .with_child(Button::new("Move to Slot")
+            .on_click(move |ctx, t: &mut UiState, _env| {
+                if let Some(slot) = target_slot(t) {
+                    ctx.submit_command(EDIT.with(Edit::MoveCharacter(idx, slot)));
+                }
+            })
+            .disabled_if(|t: &UiState, _env| target_slot(t).is_none()))
+        .with_default_spacer()
+        .with_child(Button::new("Swap with Slot")
+            .on_click(move |ctx, t: &mut UiState, _env| {
+                if let Some(slot) = target_slot(t) {
+                    ctx.submit_command(EDIT.with(Edit::SwapCharacters(idx, slot)));
+                }
+            })
+            .disabled_if(|t: &UiState, _env| target_slot(t).is_none()))
+        .with_default_spacer()
+        .with_child(Button::new("◀ Move Tab")
+            .on_click(move |ctx, t: &mut UiState, _env| {
+                if let Some(target) = t.move_character_tab(idx, -1) {
+                    ctx.submit_command(SELECT_CHARACTER_TAB.with(target));
+                }
+            })
+            .disabled_if(move |_t: &UiState, _env| idx == 0))
+        .with_child(Button::new("Move Tab ▶")
+            .on_click(move |ctx, t: &mut UiState, _env| {
+                if let Some(target) = t.move_character_tab(idx, 1) {
+                    ctx.submit_command(SELECT_CHARACTER_TAB.with(target));
+                }
+            })
+            .disabled_if(move |t: &UiState, _env| idx + 1 >= t.characters.len()))
+}
+
+/// The character's location and last prospect, each with a list of the values they can sensibly
+/// take - mostly for moving a character stuck on a broken prospect back to the station.
+fn location_rows(idx: usize, data: &UiState) -> impl Widget<UiState> {
+    let character_lens = UiState::characters_lens.index(idx);
+    let mut locations = vec![(STATION_LOCATION.to_string(), STATION_LOCATION.to_string())];
+    let mut prospects = PROSPECTS.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+    prospects.sort();
+    locations.extend(prospects.into_iter().map(|x| (x.clone(), x)));
+
+    let mut last_prospects = vec![("(None)".to_string(), String::new())];
+    last_prospects.extend(data.prospects.iter().map(|p| {
+        let id = &p.data.info.prospect_id;
+        (format!("{} ({})", data.prospect_name(id), id), id.clone())
+    }));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(picker_row("Location", character_lens.clone().then(Character::location), Picker::Location, locations))
+        .with_default_spacer()
+        .with_child(picker_row("Last Prospect", character_lens.then(Character::last_prospect_id), Picker::LastProspect, last_prospects))
+        .with_child(Flex::row()
+            .with_spacer(100.0)
+            .with_default_spacer()
+            .with_child(Label::new(move |t: &UiState, _env: &Env| t.prospect_name(&t.characters[idx].last_prospect_id)).fix_width(300.0))
+            .with_default_spacer()
+            .with_child(edit_button("Detach from Prospect", Edit::DetachFromProspect(idx))
+                .disabled_if(move |t: &UiState, _env| t.characters[idx].last_prospect_id.is_empty())))
+}
+
+/// A text box with a button that opens a list of `(name, value)` choices underneath it.
+fn picker_row(label: &'static str, lens: impl Lens<UiState, String> + Clone + 'static, picker: Picker, choices: Vec<(String, String)>) -> impl Widget<UiState> {
+    let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for (name, value) in choices {
+        let lens = lens.clone();
+        list.add_child(Button::new(name).on_click(move |_ctx, t: &mut UiState, _env| {
+            lens.with_mut(t, |x| *x = value.clone());
+            t.open_picker = None;
+        }));
+    }
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(Label::new(label).fix_width(100.0))
+            .with_default_spacer()
+            .with_child(TextBox::new().with_placeholder(label).fix_width(300.0).lens(lens))
+            .with_default_spacer()
+            .with_child(Button::new("Choose...").on_click(move |_ctx, t: &mut UiState, _env| {
+                t.open_picker = if t.open_picker == Some(picker) { None } else { Some(picker) };
+            }))
+        )
+        .with_child(Either::new(
+            move |data: &UiState, _env| data.open_picker == Some(picker),
+            Scroll::new(list).vertical().fix_height(150.0),
+            Flex::column(),
+        ))
+}
+
+/// A dropdown of the choices `cosmetics.txt` has for a cosmetic option, showing the current one by
+/// name. A value the file doesn't list is shown by number and kept until another is picked.
+#[cfg(feature = "full")]
+fn cosmetic_row(label: &'static str, option: &'static str, lens: impl Lens<UiState, CosmeticIndex> + Clone + 'static) -> impl Widget<UiState> {
+    let picker = Picker::Cosmetic(option);
+    let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for (index, name) in COSMETICS.get(option).cloned().unwrap_or_default() {
+        let lens = lens.clone();
+        list.add_child(Button::new(name).on_click(move |_ctx, t: &mut UiState, _env| {
+            lens.with_mut(t, |x| *x = index);
+            t.open_picker = None;
+        }));
+    }
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(Label::new(label).fix_width(150.0))
+            .with_default_spacer()
+            .with_child(Button::new(move |t: &UiState, _env: &Env| cosmetic_name(option, lens.get(t))).on_click(move |_ctx, t: &mut UiState, _env| {
+                t.open_picker = if t.open_picker == Some(picker) { None } else { Some(picker) };
+            }))
+        )
+        .with_child(Either::new(
+            move |data: &UiState, _env| data.open_picker == Some(picker),
+            Scroll::new(list).vertical().fix_height(150.0),
+            Flex::column(),
+        ))
+}
+
+#[cfg(feature = "full")]
+fn appearance_tab(idx: usize) -> impl Widget<UiState> {
+    let cosmetics_lens = UiState::characters_lens.index(idx).then(Character::cosmetics);
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Checkbox::new("Male").lens(cosmetics_lens.clone().then(Cosmetics::is_male)))
+        .with_default_spacer()
+        .with_child(cosmetic_row("Head", "Customization_Head", cosmetics_lens.clone().then(Cosmetics::customization_head)))
+        .with_child(cosmetic_row("Hair", "Customization_Hair", cosmetics_lens.clone().then(Cosmetics::customization_hair)))
+        .with_child(cosmetic_row("Hair Color", "Customization_HairColor", cosmetics_lens.clone().then(Cosmetics::customization_hair_color)))
+        .with_child(cosmetic_row("Body", "Customization_Body", cosmetics_lens.clone().then(Cosmetics::customization_body)))
+        .with_child(cosmetic_row("Body Color", "Customization_BodyColor", cosmetics_lens.clone().then(Cosmetics::customization_body_color)))
+        .with_child(cosmetic_row("Skin Tone", "Customization_SkinTone", cosmetics_lens.clone().then(Cosmetics::customization_skin_tone)))
+        .with_child(cosmetic_row("Tattoo", "Customization_HeadTattoo", cosmetics_lens.clone().then(Cosmetics::customization_head_tattoo)))
+        .with_child(cosmetic_row("Scar", "Customization_HeadScar", cosmetics_lens.clone().then(Cosmetics::customization_head_scar)))
+        .with_child(cosmetic_row("Facial Hair", "Customization_HeadFacialHair", cosmetics_lens.clone().then(Cosmetics::customization_head_facial_hair)))
+        .with_child(cosmetic_row("Cap Logo", "Customization_CapLogo", cosmetics_lens.clone().then(Cosmetics::customization_cap_logo)))
+        .with_child(cosmetic_row("Voice", "Customization_Voice", cosmetics_lens.clone().then(Cosmetics::customization_voice)))
+        .with_child(cosmetic_row("Eye Color", "Customization_EyeColor", cosmetics_lens.then(Cosmetics::customization_eye_color)))
+}
+
+#[cfg(feature = "full")]
+fn blueprints_tab(idx: usize) -> impl Widget<UiState> {
+    let character_lens = UiState::characters_lens.index(idx);
+    let blueprints = ViewSwitcher::new(
+        |data: &UiState, _env| (data.search.clone(), data.hidden_dlc()),
+        move |(search, hidden), _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (category, blueprints) in BLUEPRINT_CATEGORIES.iter() {
+                let matching = blueprints.iter().copied().filter(|x| row_matches(search, x) && !dlc_hidden(hidden, x)).collect::<Vec<&'static str>>();
+                if matching.is_empty() {
+                    continue;
+                }
+                list.add_child(Label::new(*category).with_text_size(theme::HEADING_TEXT_SIZE));
+                for blueprint in matching {
+                    list.add_child(Flex::row()
+                        .with_child(Checkbox::new(display_name(blueprint))
+                            .lens(character_lens.clone().then(Character::talents).then(UnlockLens { row_name: blueprint }))
+                            .fix_width(250.0))
+                        .with_child(row_name_label(blueprint))
+                    );
+                }
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(move |data: &UiState, _env: &Env| {
+            format!("Blueprints unlocked: {} / {}", data.characters[idx].blueprints_unlocked(), BLUEPRINTS.len())
+        }))
+        .with_default_spacer()
+        .with_flex_child(blueprints, 1.0)
+}
+
+#[cfg(feature = "full")]
+fn inventory_tab(idx: usize) -> impl Widget<UiState> {
+    let inventory_lens = UiState::characters_lens.index(idx).then(Character::inventory);
+
+    let items_lens = inventory_lens.clone();
+    let items = ViewSwitcher::new(
+        move |data: &UiState, _env| data.characters[idx].inventory.delta.iter().map(|x| x.item_static_data.row_name.clone()).collect::<Vector<String>>(),
+        move |row_names, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            if row_names.is_empty() {
+                list.add_child(Label::new("Inventory is empty"));
+            }
+            for (item_idx, row_name) in row_names.iter().enumerate() {
+                let item_lens = items_lens.clone().then(Inventory::delta).index(item_idx).then(InventoryItem::item_dynamic_data);
+                list.add_child(Flex::row()
+                    .with_child(Label::new(row_name.clone()).fix_width(200.0))
+                    .with_default_spacer()
+                    .with_child(Label::new("Count"))
+                    .with_default_spacer()
+                    .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Count"), ParseFormatter::<f64>::new())
+                        .fix_width(60.0)
+                        .lens(item_lens.clone().then(ItemPropertyLens { property: PROPERTY_STACK, default: 1.0 })))
+                    .with_default_spacer()
+                    .with_child(Label::new("Durability"))
+                    .with_default_spacer()
+                    .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Durability"), ParseFormatter::<f64>::new())
+                        .fix_width(60.0)
+                        .lens(item_lens.then(ItemPropertyLens { property: PROPERTY_DURABILITY, default: 0.0 })))
+                    .with_default_spacer()
+                    .with_child(edit_button("Remove", Edit::RemoveItem(idx, item_idx)))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    let search_results = ViewSwitcher::new(
+        |data: &UiState, _env| data.inventory_search.clone(),
+        move |search, _data: &UiState, _env| {
+            let mut results = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            let mut categories: BTreeMap<&str, Vec<&'static str>> = BTreeMap::new();
+            for row_name in ITEMS.iter().copied().filter(|x| row_matches(search, x) || search::matches(search, item_category(x))) {
+                categories.entry(item_category(row_name)).or_default().push(row_name);
+            }
+            if categories.is_empty() {
+                results.add_child(Label::new("No items match"));
+            }
+            for (category, row_names) in categories {
+                results.add_child(Label::new(category).with_text_size(theme::HEADING_TEXT_SIZE));
+                for row_name in row_names {
+                    results.add_child(Flex::row()
+                        .with_child(Label::new(display_name(row_name)).fix_width(200.0))
+                        .with_default_spacer()
+                        .with_child(row_name_label(row_name))
+                        .with_default_spacer()
+                        .with_child(edit_button("Spawn", Edit::SpawnItem(idx, row_name)))
+                    );
+                }
+            }
+            Box::new(Scroll::new(results).vertical())
+        }
+    );
+
+    let editor = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(items, 1.0)
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Label::new("Spawn item"))
+            .with_default_spacer()
+            .with_child(TextBox::new().with_placeholder("Item name or category").fix_width(200.0).lens(UiState::inventory_search_lens))
+            .with_default_spacer()
+            .with_child(Label::new("Quantity"))
+            .with_default_spacer()
+            .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Quantity"), ParseFormatter::<u32>::new())
+                .fix_width(60.0)
+                .lens(UiState::spawn_quantity_lens))
+            .with_default_spacer()
+            .with_child(Label::new("Durability"))
+            .with_default_spacer()
+            .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Game default"), ParseFormatter::<f64>::new())
+                .fix_width(60.0)
+                .lens(UiState::spawn_durability_lens))
+            .with_default_spacer()
+            .with_child(Label::new("(0 for the game's default)"))
+        )
+        .with_default_spacer()
+        .with_child(search_results.fix_height(150.0));
+
+    Either::new(
+        move |data: &UiState, _env| data.characters[idx].inventory_loaded,
+        editor,
+        Label::new("The inventory file for this character could not be read"),
+    )
+}
+
+fn loadout_tab(idx: usize) -> impl Widget<UiState> {
+    let loadout_lens = UiState::characters_lens.index(idx).then(Character::loadout);
+    let slots = ViewSwitcher::new(
+        move |data: &UiState, _env| data.characters[idx].loadout.items.clone(),
+        move |items, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            if items.is_empty() {
+                list.add_child(Label::new("Nothing is equipped"));
+            }
+            for (slot, item) in items.iter().enumerate() {
+                let count = ItemPropertyLens { property: PROPERTY_STACK, default: 1.0 }.get(&item.item_dynamic_data);
+                list.add_child(Flex::row()
+                    .with_child(Label::new(format!("Slot {}: {} x{}", slot + 1, item.item_static_data.row_name, count)).fix_width(300.0))
+                    .with_default_spacer()
+                    .with_child(edit_button("Up", Edit::SwapLoadoutSlots(idx, slot, slot.saturating_sub(1)))
+                        .disabled_if(move |_: &Loadout, _env| slot == 0)
+                        .lens(loadout_lens.clone()))
+                    .with_child(edit_button("Down", Edit::SwapLoadoutSlots(idx, slot, slot + 1))
+                        .disabled_if(move |t: &Loadout, _env| slot + 1 >= t.items.len())
+                        .lens(loadout_lens.clone()))
+                    .with_default_spacer()
+                    .with_child(edit_button("Clear", Edit::ClearLoadoutSlot(idx, slot)))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    let editor = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(move |data: &UiState, _env: &Env| {
+            if data.characters[idx].loadout.valid { "Loadout is valid".to_string() } else { "Loadout is not valid".to_string() }
+        }))
+        .with_default_spacer()
+        .with_flex_child(slots, 1.0);
+
+    let tab = Either::new(
+        move |data: &UiState, _env| data.characters[idx].loadout_loaded,
+        editor,
+        Label::new("The loadout file for this character could not be read"),
+    );
+    #[cfg(feature = "full")]
+    let tab = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(tab, 1.0)
+        .with_default_spacer()
+        .with_child(loadout_templates_panel(idx));
+
+    tab
+}
+
+/// Loadouts saved by name, built in or the user's own, to give to any character.
+#[cfg(feature = "full")]
+fn loadout_templates_panel(idx: usize) -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        |data: &UiState, _env| data.loadout_templates.clone(),
+        move |templates, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for template in templates.iter() {
+                let (apply, delete, built_in) = (template.clone(), template.clone(), template.is_built_in());
+                let name = if built_in { format!("{} (built in)", template.name) } else { template.name.clone() };
+                list.add_child(Flex::row()
+                    .with_child(Label::new(name).fix_width(200.0))
+                    .with_default_spacer()
+                    .with_child(edit_button("Apply", Edit::ApplyLoadoutTemplate(idx, apply)))
+                    .with_default_spacer()
+                    .with_child(Button::new("Delete")
+                        .on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("delete loadout template", |t| t.delete_loadout_template(&delete)); })
+                        .disabled_if(move |_: &UiState, _env| built_in))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Loadout templates").with_text_size(theme::HEADING_TEXT_SIZE))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(TextBox::new().with_placeholder("Template name").fix_width(200.0).lens(UiState::template_name_lens))
+            .with_default_spacer()
+            .with_child(Button::new("Save Loadout as Template")
+                .on_click(move |_ctx, t: &mut UiState, _env| { t.run_action("save loadout template", |t| t.save_loadout_template(idx)); })
+                .disabled_if(move |t: &UiState, _env| !t.characters[idx].loadout_loaded)))
+        .with_default_spacer()
+        .with_child(list.fix_height(120.0))
+}
+
+fn backups_panel() -> impl Widget<UiState> {
+    let backup_list = ViewSwitcher::new(
+        |data: &UiState, _env| data.backups.clone(),
+        |backups, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            if backups.is_empty() {
+                list.add_child(Label::new("No backups yet - one is taken every time you save"));
+            }
+            for b in backups.iter() {
+                let (backup, view) = (b.clone(), b.clone());
+                list.add_child(Flex::row()
+                    .with_child(Label::new(b.name.clone()))
+                    .with_default_spacer()
+                    .with_child(Button::new("Restore from backup")
+                        .on_click(move |_ctx, t: &mut UiState, _env| t.request_restore_backup(&backup))
+                        .disabled_if(|t: &UiState, _env| t.config.safe_mode))
+                    .with_default_spacer()
+                    .with_child(Button::new("View...").on_click(move |ctx, t: &mut UiState, _env| {
+                        t.view_backup(&view);
+                        ctx.new_window(backup_window(&view));
+                    }))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Flex::row()
+            .with_child(Label::new("Backups to keep (0 = all): "))
+            .with_default_spacer()
+            .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Backups to keep"), ParseFormatter::<usize>::new())
+                .fix_width(50.0)
+                .lens(UiState::config_lens.then(Config::backup_retention)))
+        )
+        .with_default_spacer()
+        .with_child(backup_list.fix_height(100.0))
+}
+
+/// A window for looking through a backup without restoring it. The app's data only has room for one
+/// open backup, so once another is opened this window asks to be reopened instead.
+fn backup_window(backup: &Backup) -> WindowDesc<UiState> {
+    WindowDesc::new(backup_viewer(backup.clone()))
+        .title(format!("Backup {} (read only)", backup.name))
+        .window_size((800.0, 600.0))
+}
+
+/// The open backup's profile and characters, each character with how they differ from the same slot
+/// now and a button to bring them back.
+fn backup_viewer(opened: Backup) -> impl Widget<UiState> {
+    let body = ViewSwitcher::new(
+        |data: &UiState, _env| (data.backup_view.clone(), data.characters.clone()),
+        move |(view, characters), _data: &UiState, _env| {
+            let view = match view {
+                Some(view) if view.backup == opened => view,
+                Some(_) => return Box::new(Label::new("Another backup has been opened since - click View... again to see this one")),
+                None => return Box::new(Label::new("Reading backup...")),
+            };
+            let backup = view.backup.clone();
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start)
+                .with_child(Flex::row()
+                    .with_child(Label::new(format!("Backup {}", view.backup.name)).with_text_size(theme::HEADING_TEXT_SIZE))
+                    .with_default_spacer()
+                    .with_child(Button::new("Restore Whole Backup")
+                        .on_click(move |_ctx, t: &mut UiState, _env| t.request_restore_backup(&backup))
+                        .disabled_if(|t: &UiState, _env| t.config.safe_mode)))
+                .with_default_spacer()
+                .with_child(Label::new(format!(
+                    "Profile: {} credits, {} exotics",
+                    view.profile.meta_resource(META_RESOURCE_CREDITS),
+                    view.profile.meta_resource(META_RESOURCE_EXOTICS),
+                )));
+            for (backup_idx, c) in view.characters.iter().enumerate() {
+                let status = match (c.is_dead, c.is_abandoned) {
+                    (true, _) => " (dead)",
+                    (false, true) => " (abandoned)",
+                    _ => "",
+                };
+                list.add_default_spacer();
+                list.add_child(Flex::row()
+                    .with_child(Label::new(format!("{} (slot {}){}", character_tab_name(c), c.character_slot, status)).with_text_size(theme::HEADING_TEXT_SIZE))
+                    .with_default_spacer()
+                    .with_child(edit_button("Restore This Character", Edit::RestoreCharacterFromBackup { backup: view.backup.clone(), backup_idx })
+                        .disabled_if(|t: &UiState, _env| t.config.safe_mode)));
+                list.add_child(Label::new(character_summary(c)));
+                match characters.iter().find(|x| x.character_slot == c.character_slot) {
+                    Some(now) => {
+                        let differences = compare::compare(c, now);
+                        if differences.is_empty() {
+                            list.add_child(Label::new("Same XP, talents, blueprints and flags as now"));
+                        }
+                        for difference in differences {
+                            list.add_child(Label::new(format!("{} {}: {} in the backup, {} now", difference.section, difference.name, difference.left, difference.right)));
+                        }
+                    }
+                    None => list.add_child(Label::new("No character in this slot now")),
+                }
+            }
+            Box::new(Scroll::new(list).vertical())
+        },
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(body, 1.0)
+        .with_child(status_bar())
+}
+
+/// An unlock button for each DLC the save owns (or all of them, if unowned DLC are shown).
+fn dlc_row() -> impl Widget<UiState> {
+    ViewSwitcher::new(
+        |data: &UiState, _env| data.hidden_dlc(),
+        |hidden, _data: &UiState, _env| {
+            let mut row = Flex::row();
+            for (dlc, name) in DLC_NAMES.iter().enumerate() {
+                if !hidden.iter().any(|x| x == name) {
+                    row.add_child(bulk_action_button(BulkAction::UnlockDlc(dlc)));
+                    row.add_default_spacer();
+                }
+            }
+            Box::new(row)
+        }
+    )
+}
+
+fn bulk_action_button(action: BulkAction) -> impl Widget<UiState> {
+    Button::new(action.label()).on_click(move |_ctx, t: &mut UiState, _env| t.request_bulk_action(action))
+}
+
+fn save_button() -> impl Widget<UiState> {
+    Button::new("Save...")
+        .on_click(|_ctx, t: &mut UiState, _env| t.open_save_dialog())
+        .disabled_if(|t: &UiState, _env| t.config.safe_mode || t.is_busy())
+}
+
+/// What a field or flag controls, shown in small grey text next to it.
+fn help_label<T: Data>(help: &str) -> Label<T> {
+    Label::new(help.to_string()).with_text_size(theme::SMALL_TEXT_SIZE).with_text_color(Color::grey(0.6))
+}
+
+/// The raw row name, shown in small grey text next to its display name.
+fn row_name_label<T: Data>(row_name: &str) -> Label<T> {
+    Label::new(row_name.to_string()).with_text_size(theme::SMALL_TEXT_SIZE).with_text_color(Color::grey(0.6))
+}
+
+/// A button that makes `edit` when clicked. It can be used with any data, as the edit is made by
+/// the app delegate.
+fn edit_button<T: Data>(label: impl Into<LabelText<T>>, edit: Edit) -> impl Widget<T> {
+    Button::new(label).on_click(move |ctx, _: &mut T, _env| ctx.submit_command(EDIT.with(edit.clone())))
+}
+
+/// A prompt's button that makes `edit` and closes the prompt.
+fn prompt_edit_button(label: &str, edit: Edit) -> impl Widget<UiState> {
+    Button::new(label).on_click(move |ctx, t: &mut UiState, _env| {
+        t.prompt = None;
+        ctx.submit_command(EDIT.with(edit.clone()));
+    })
+}
+
+fn search_row() -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(Label::new("Search"))
+        .with_default_spacer()
+        .with_child(TextBox::new().with_placeholder("Talents, blueprints, workshop items and prospects").fix_width(350.0).lens(UiState::search_lens))
+}
+
+fn settings_panel() -> impl Widget<UiState> {
+    let theme = Flex::row()
+        .with_child(Label::new("Theme:"))
+        .with_default_spacer()
+        .with_child(RadioGroup::row(vec![
+            ("System", Theme::System),
+            ("Light", Theme::Light),
+            ("Dark", Theme::Dark),
+        ]).lens(UiState::config_lens.then(Config::theme)))
+        .with_default_spacer()
+        .with_child(Label::new("Text size:"))
+        .with_default_spacer()
+        .with_child(RadioGroup::row(theme::UI_SCALES.to_vec()).lens(UiState::config_lens.then(Config::ui_scale)));
+
+    let autosave = Flex::row()
+        .with_child(Checkbox::new("Autosave").lens(UiState::config_lens.then(Config::autosave)))
+        .with_default_spacer()
+        .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Seconds"), ParseFormatter::<u64>::new())
+            .fix_width(50.0)
+            .lens(UiState::config_lens.then(Config::autosave_delay)))
+        .with_default_spacer()
+        .with_child(Label::new("seconds after the last change, backing up first"));
+
+    let data_url = Flex::row()
+        .with_child(Label::new("Data files from:"))
+        .with_default_spacer()
+        .with_child(TextBox::new().with_placeholder(game_data::DEFAULT_DATA_URL).fix_width(450.0).lens(UiState::config_lens.then(Config::data_url)));
+
+    let options = Flex::row()
+        .with_child(Checkbox::new("Safe mode (read only)").lens(UiState::config_lens.then(Config::safe_mode)))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Ask before discarding unsaved changes").lens(UiState::config_lens.then(Config::confirm_unsaved)))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Ask before saving while Icarus is running").lens(UiState::config_lens.then(Config::confirm_game_running)))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Ask before bulk changes").lens(UiState::config_lens.then(Config::confirm_bulk_changes)))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Show DLC I don't own").lens(UiState::config_lens.then(Config::show_unowned_dlc)))
+        .with_default_spacer()
+        .with_child(Checkbox::new("Check for new versions at startup").lens(UiState::config_lens.then(Config::check_for_updates)))
+        .with_default_spacer()
+        .with_child(Button::new("Check Now").on_click(|_ctx, t: &mut UiState, _env| t.check_for_updates(false)))
+        .with_default_spacer()
+        .with_child(Button::new("Import Game Data...").on_click(|ctx, _t: &mut UiState, _env| {
+            let options = FileDialogOptions::new()
+                .select_directories()
+                .title("Choose a folder of data tables exported from the game's data.pak")
+                .accept_command(IMPORT_GAME_DATA);
+            ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+        }))
+        .with_default_spacer()
+        .with_child(Button::new("Download Data Files").on_click(|_ctx, t: &mut UiState, _env| t.download_data_files()))
+        .with_default_spacer()
+        .with_child(Button::new("Copy Data Files for Editing").on_click(|_ctx, t: &mut UiState, _env| {
+            t.run_action("copy data files", |t| {
+                let dir = game_data::write_defaults(&DATA_FILES)?;
+                t.prompt = Some(Prompt::Message(format!("The data files are in [{}]. Changes are used the next time the editor starts.", dir.to_string_lossy())));
+                Ok(())
+            });
+        }));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(options)
+        .with_default_spacer()
+        .with_child(autosave)
+        .with_default_spacer()
+        .with_child(theme)
+        .with_default_spacer()
+        .with_child(data_url)
+}
+
+/// Remembers which top-level tab is open, so it can be opened again next time.
+struct TabMemory;
+
+impl Controller<UiState, Tabs<StaticTabs<UiState>>> for TabMemory {
+    fn event(&mut self, child: &mut Tabs<StaticTabs<UiState>>, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        child.event(ctx, event, data, env);
+        if data.config.last_tab != child.tab_index() {
+            data.config.last_tab = child.tab_index();
+        }
+    }
+}
+
+#[cfg(feature = "full")]
+fn meta_resources_panel() -> impl Widget<UiState> {
+    let table_lens = UiState::profile_lens.then(Profile::meta_resources);
+    let table = ViewSwitcher::new(
+        |data: &UiState, _env| data.profile.meta_resources.iter().map(|x| x.meta_row.clone()).collect::<Vector<String>>(),
+        move |meta_rows, _data: &UiState, _env| {
+            let mut table = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (idx, meta_row) in meta_rows.iter().enumerate() {
+                let name = KNOWN_META_RESOURCES.iter().find(|(row, _)| row == meta_row).map(|(_, name)| name.to_string()).unwrap_or_else(|| meta_row.clone());
+                table.add_child(Flex::row()
+                    .with_child(Label::new(format!("{}: ", name)).fix_width(150.0))
+                    .with_default_spacer()
+                    .with_child(ValueTextBox::new(TextBox::new().with_placeholder(name), ParseFormatter::<f64>::new())
+                        .fix_width(100.0)
+                        .lens(table_lens.clone().index(idx).then(MetaResources::count)))
+                    .with_default_spacer()
+                    .with_child(edit_button("Remove", Edit::RemoveMetaResource(idx)))
+                );
+            }
+            Box::new(table)
+        }
+    );
+
+    let add_resource = ViewSwitcher::new(
+        |data: &UiState, _env| data.profile.meta_resources.iter().map(|x| x.meta_row.clone()).collect::<Vector<String>>(),
+        |meta_rows, _data: &UiState, _env| {
+            let mut row = Flex::row().with_child(Label::new("Add resource:"));
+            for (meta_row, name) in KNOWN_META_RESOURCES.iter().filter(|(row, _)| !meta_rows.iter().any(|x| x == row)) {
+                let meta_row = *meta_row;
+                row.add_default_spacer();
+                row.add_child(edit_button(*name, Edit::AddMetaResource(meta_row)));
+            }
+            Box::new(row)
+        }
+    );
+
+    let mut exotics = Flex::row();
+    for (meta_row, name) in EXOTIC_META_RESOURCES.iter() {
+        exotics.add_child(Label::new(format!("{}: ", name)));
+        exotics.add_child(ValueTextBox::new(TextBox::new().with_placeholder(*name), ParseFormatter::<f64>::new())
+            .fix_width(100.0)
+            .lens(UiState::profile_lens.then(MetaResourceLens { meta_row })));
+        exotics.add_default_spacer();
+    }
+
+    let vouchers = Flex::row()
+        .with_child(Label::new("Workshop refund vouchers: "))
+        .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Refund vouchers"), ParseFormatter::<f64>::new())
+            .fix_width(100.0)
+            .lens(UiState::profile_lens.then(MetaResourceLens { meta_row: META_RESOURCE_REFUND_VOUCHERS })));
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(exotics)
+        .with_default_spacer()
+        .with_child(vouchers)
+        .with_default_spacer()
+        .with_child(table)
+        .with_default_spacer()
+        .with_child(add_resource)
+}
+
+#[cfg(feature = "full")]
+fn profile_transfer_row() -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(Button::new("Export Profile...").on_click(|ctx, _t: &mut UiState, _env| {
+            let options = FileDialogOptions::new()
+                .allowed_types(vec![PROFILE_EXPORT_FILE])
+                .default_name("Profile export.json")
+                .accept_command(EXPORT_PROFILE);
+            ctx.submit_command(commands::SHOW_SAVE_PANEL.with(options));
+        }))
+        .with_default_spacer()
+        .with_child(Button::new("Import Profile...").on_click(|ctx, _t: &mut UiState, _env| {
+            let options = FileDialogOptions::new()
+                .allowed_types(vec![PROFILE_EXPORT_FILE])
+                .accept_command(IMPORT_PROFILE);
+            ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+        }))
+        .with_default_spacer()
+        .with_child(Button::new("Export Summary...").on_click(|ctx, _t: &mut UiState, _env| {
+            let options = FileDialogOptions::new()
+                .allowed_types(vec![MARKDOWN_FILE, CSV_FILE])
+                .default_type(MARKDOWN_FILE)
+                .default_name("Icarus summary.md")
+                .title("Save a summary of the profile and characters as Markdown or CSV")
+                .accept_command(EXPORT_SUMMARY);
+            ctx.submit_command(commands::SHOW_SAVE_PANEL.with(options));
+        }))
+        .with_default_spacer()
+        .with_child(Label::new("When importing:"))
+        .with_default_spacer()
+        .with_child(RadioGroup::row(vec![
+            ("Replace", MergeStrategy::Replace),
+            ("Add counts", MergeStrategy::AddCounts),
+            ("Combine", MergeStrategy::Union),
+        ]).lens(UiState::profile_merge_lens))
+}
+
+#[cfg(feature = "full")]
+fn workshop_tab() -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        |data: &UiState, _env| (data.search.clone(), data.hidden_dlc()),
+        |(search, hidden), _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (category, items) in WORKSHOP_CATEGORIES.iter() {
+                let matching = items.iter().copied().filter(|x| row_matches(search, x) && !dlc_hidden(hidden, x)).collect::<Vec<&'static str>>();
+                if matching.is_empty() {
+                    continue;
+                }
+                list.add_child(Label::new(*category).with_text_size(theme::HEADING_TEXT_SIZE));
+                for item in matching {
+                    let cost = match workshop_cost(item) {
+                        (credits, exotics) if exotics > 0.0 => format!("{} credits, {} exotics", credits, exotics),
+                        (credits, _) => format!("{} credits", credits),
+                    };
+                    list.add_child(Flex::row()
+                        .with_child(Checkbox::new(display_name(item)).lens(WorkshopItemLens { row_name: item }).fix_width(250.0))
+                        .with_child(row_name_label(item).fix_width(250.0))
+                        .with_default_spacer()
+                        .with_child(Label::new(cost))
+                    );
+                }
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new(|data: &UiState, _env: &Env| format!(
+            "Credits: {}, Exotics: {}",
+            data.profile.meta_resource(META_RESOURCE_CREDITS),
+            data.profile.meta_resource(META_RESOURCE_EXOTICS),
+        )))
+        .with_child(Label::new(|data: &UiState, _env: &Env| {
+            let (credits, exotics) = data.profile.workshop_spent();
+            format!("Total spent on unlocked items: {} credits, {} exotics", credits, exotics)
+        }))
+        .with_child(Checkbox::new("Purchase with credits and exotics (unlocking deducts the item's cost, locking doesn't refund it)").lens(UiState::purchase_with_credits_lens))
+        .with_default_spacer()
+        .with_flex_child(list, 1.0)
+}
+
+/// Every talent the profile can have, with its rank - the prospect and workshop unlocks, plus any
+/// other rows the save has. Unlike the workshop tab, changing a rank here never costs credits.
+#[cfg(feature = "full")]
+fn profile_talents_tab() -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        |data: &UiState, _env| (data.search.clone(), data.profile_talent_filter, data.profile.other_talents(), data.hidden_dlc()),
+        |(search, filter, other_talents, hidden), _data: &UiState, _env| {
+            let mut groups = Vec::new();
+            if *filter != ProfileTalentFilter::Workshop {
+                groups.push(("Prospect Unlocks", PROSPECT_TIERS.iter().flat_map(|(_, x)| x.iter()).map(|x| x.to_string()).collect::<Vec<String>>()));
+            }
+            if *filter != ProfileTalentFilter::Prospects {
+                groups.push(("Workshop Unlocks", WORKSHOP_CATEGORIES.iter().flat_map(|(_, x)| x.iter()).map(|x| x.to_string()).collect()));
+            }
+            if *filter == ProfileTalentFilter::All {
+                groups.push(("Other", other_talents.iter().cloned().collect()));
+            }
+
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for (group, row_names) in groups {
+                let matching = row_names.into_iter().filter(|x| row_matches(search, x) && !dlc_hidden(hidden, x)).collect::<Vec<String>>();
+                if matching.is_empty() {
+                    continue;
+                }
+                list.add_child(Label::new(group).with_text_size(theme::HEADING_TEXT_SIZE));
+                for row_name in matching {
+                    let (label_row, minus_row, plus_row) = (row_name.clone(), row_name.clone(), row_name.clone());
+                    list.add_child(Flex::row()
+                        .with_child(Label::new(display_name(&row_name).to_string()).fix_width(250.0))
+                        .with_child(row_name_label(&row_name).fix_width(250.0))
+                        .with_default_spacer()
+                        .with_child(Label::new(move |p: &Profile, _env: &Env| format!("{}", p.talent_rank(&label_row))).fix_width(40.0))
+                        .with_default_spacer()
+                        .with_child(edit_button("-", Edit::ChangeProfileTalentRank(minus_row, -1))
+                            .disabled_if(move |p: &Profile, _env| p.talent_rank(&row_name) <= 0))
+                        .with_child(edit_button("+", Edit::ChangeProfileTalentRank(plus_row, 1)))
+                        .lens(UiState::profile_lens)
+                    );
+                }
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(RadioGroup::row(vec![
+            ("All", ProfileTalentFilter::All),
+            ("Prospect unlocks", ProfileTalentFilter::Prospects),
+            ("Workshop unlocks", ProfileTalentFilter::Workshop),
+        ]).lens(UiState::profile_talent_filter_lens))
+        .with_default_spacer()
+        .with_flex_child(list, 1.0)
+}
+
+#[cfg(feature = "full")]
+fn prospects_tab() -> impl Widget<UiState> {
+    let prospects = ViewSwitcher::new(
+        |data: &UiState, _env| data.prospects.iter().map(|x| x.file_name.clone()).collect::<Vector<String>>(),
+        |file_names, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            if file_names.is_empty() {
+                list.add_child(Label::new("No prospect saves found"));
+            }
+            for (idx, file_name) in file_names.iter().enumerate() {
+                let info_lens = UiState::prospects_lens.index(idx).then(Prospect::data).then(ProspectSave::info);
+                list.add_child(Label::new(file_name.clone()).with_text_size(theme::HEADING_TEXT_SIZE));
+                list.add_child(Label::new(|info: &ProspectInfo, _env: &Env| {
+                    let kind = if info.is_open_world() { "Open world" } else { "Mission" };
+                    format!("{} - state: {}", kind, info.prospect_state)
+                }).lens(info_lens.clone()));
+                list.add_child(Flex::row()
+                    .with_child(Label::new("Name"))
+                    .with_default_spacer()
+                    .with_child(TextBox::new().with_placeholder("Prospect name").fix_width(200.0).lens(info_lens.clone().then(ProspectInfo::lobby_name)))
+                    .with_default_spacer()
+                    .with_child(Label::new("Difficulty"))
+                    .with_default_spacer()
+                    .with_child(TextBox::new().with_placeholder("Difficulty").fix_width(100.0).lens(info_lens.clone().then(ProspectInfo::difficulty)))
+                );
+                list.add_child(Flex::row()
+                    .with_child(Label::new("Elapsed Time (s)"))
+                    .with_default_spacer()
+                    .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Elapsed time"), ParseFormatter::<f64>::new()).fix_width(100.0).lens(info_lens.clone().then(ProspectInfo::elapsed_time)))
+                    .with_default_spacer()
+                    .with_child(Checkbox::new("No Respawns").lens(info_lens.clone().then(ProspectInfo::no_respawns)))
+                    .with_default_spacer()
+                    .with_child(Checkbox::new("Insurance").lens(info_lens.clone().then(ProspectInfo::insurance)))
+                );
+                list.add_child(Label::new(|info: &ProspectInfo, _env: &Env| {
+                    info.members.iter()
+                        .map(|x| format!("{} ({}, slot {}): {}", x.character_name, x.account_name, x.character_slot, x.status))
+                        .collect::<Vec<String>>()
+                        .join("\n")
+                }).lens(info_lens.clone()));
+                list.add_child(edit_button("Rejoin Abandoned Players", Edit::RejoinAbandoned(idx))
+                    .disabled_if(|info: &ProspectInfo, _env| info.abandoned_members() == 0)
+                    .lens(info_lens));
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_flex_child(prospects, 1.0)
+}
+
+fn prospect_unlocks_tab() -> impl Widget<UiState> {
+    let list = ViewSwitcher::new(
+        |data: &UiState, _env| (data.search.clone(), data.hidden_dlc()),
+        |(search, hidden), _data: &UiState, _env| {
+            let talents_lens = UiState::profile_lens.then(Profile::talents);
+            let completed_lens = UiState::profile_lens.then(Profile::completed_missions);
+            let times_lens = UiState::profile_lens.then(Profile::mission_best_times);
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            list.add_child(Flex::row()
+                .with_child(Label::new("Unlocked").fix_width(250.0))
+                .with_child(Label::new("").fix_width(100.0))
+                .with_child(Label::new("Best time (s)").fix_width(110.0))
+            );
+            for (tier, row_names) in PROSPECT_TIERS.iter() {
+                let matching = row_names.iter().copied().filter(|x| row_matches(search, x) && !dlc_hidden(hidden, x)).collect::<Vec<&'static str>>();
+                if matching.is_empty() {
+                    continue;
+                }
+                list.add_child(Label::new(format!("Tier {}", tier)).with_text_size(theme::HEADING_TEXT_SIZE));
+                for row_name in matching {
+                    list.add_child(Flex::row()
+                        .with_child(Checkbox::new(display_name(row_name)).lens(talents_lens.clone().then(UnlockLens { row_name })).fix_width(250.0))
+                        .with_child(Checkbox::new("Completed").lens(completed_lens.clone().then(ListedLens { row_name })).fix_width(100.0))
+                        .with_child(ValueTextBox::new(TextBox::new().with_placeholder("Best time"), ParseFormatter::<f64>::new())
+                            .lens(times_lens.clone().then(MissionTimeLens { row_name }))
+                            .fix_width(100.0))
+                        .with_default_spacer()
+                        .with_child(row_name_label(row_name))
+                    );
+                }
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+    let mut unlock_buttons = Flex::row().with_child(Label::new("Unlock up to:"));
+    for (tier, _) in PROSPECT_TIERS.iter() {
+        let tier = *tier;
+        unlock_buttons.add_default_spacer();
+        unlock_buttons.add_child(edit_button(format!("Tier {}", tier), Edit::UnlockProspectsUpToTier(tier)));
+    }
+
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(unlock_buttons)
+        .with_default_spacer()
+        .with_flex_child(list, 1.0)
+}
+
+fn choose_save_dir_options() -> FileDialogOptions {
+    FileDialogOptions::new()
+        .select_directories()
+        .title("Choose the folder containing Profile.json and Characters.json")
+        .accept_command(CHOOSE_SAVE_DIR)
+}
+
+fn choose_save_dir_button() -> impl Widget<UiState> {
+    Button::new("Choose Save Folder...").on_click(|ctx, _t: &mut UiState, _env| {
+        ctx.submit_command(commands::SHOW_OPEN_PANEL.with(choose_save_dir_options()));
+    })
+}
+
+fn open_server_button() -> impl Widget<UiState> {
+    Button::new("Open Server Saves...").on_click(|ctx, _t: &mut UiState, _env| {
+        let options = FileDialogOptions::new()
+            .select_directories()
+            .title("Choose the dedicated server's Saved or PlayerData folder")
+            .accept_command(CHOOSE_SERVER_DIR);
+        ctx.submit_command(commands::SHOW_OPEN_PANEL.with(options));
+    })
+}
+
+fn save_dir_row() -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(Label::new(|data: &UiState, _env: &Env| format!("Save folder: {}", data.save_dir.to_string_lossy())))
+        .with_default_spacer()
+        .with_child(choose_save_dir_button())
+        .with_default_spacer()
+        .with_child(open_server_button())
+        .with_default_spacer()
+        .with_child(open_button("Open Save Folder", |t| t.save_dir.clone()))
+        .with_default_spacer()
+        .with_child(open_button("Open Profile.json", |t| t.profile_file.clone()))
+        .with_default_spacer()
+        .with_child(open_button("Open Characters.json", |t| t.characters_file.clone()))
+}
+
+/// Opens a file or folder from the save outside the editor, to look at the raw files.
+fn open_button(label: &'static str, path: impl Fn(&UiState) -> PathBuf + 'static) -> impl Widget<UiState> {
+    Button::new(label).on_click(move |_ctx, t: &mut UiState, _env| {
+        let path = path(t);
+        t.run_action("open file", |_| shell::open(&path));
+    })
+}
+
+/// What's wrong with a character's inventory and loadout files, if anything. A slot the game hasn't
+/// written files for yet has none, and the game may not cope with that.
+fn file_warning(c: &Character) -> Option<String> {
+    let problem = |loaded: bool, path: &Path, file: &str| match (loaded, path.exists()) {
+        (true, _) => None,
+        (false, true) => Some(format!("the {} file can't be read", file)),
+        (false, false) => Some(format!("there's no {} file", file)),
+    };
+    let problems = [problem(c.inventory_loaded, &c.inventory_path, "inventory"), problem(c.loadout_loaded, &c.loadout_path, "loadout")];
+    let problems = problems.into_iter().flatten().collect::<Vec<String>>();
+    if problems.is_empty() {
+        None
+    } else {
+        Some(format!("Warning: {}", problems.join(" and ")))
+    }
+}
+
+/// A line summing up a character: level, talent points, blueprints and the credits they hold.
+fn character_summary(c: &Character) -> String {
+    format!(
+        "Level {}  |  {} / {} talent points spent  |  {} blueprints unlocked  |  {} credits",
+        c.level(), c.talent_points_spent(), c.talent_points_earned(), c.blueprints_unlocked(), c.meta_resource(META_RESOURCE_CREDITS),
+    )
+}
+
+/// A box for XP or XP debt, which won't take anything the game wouldn't, with a red border while
+/// its text can't be used.
+#[cfg(feature = "full")]
+fn xp_box(placeholder: &str, idx: usize, field: XpField, lens: impl Lens<UiState, Xp> + 'static) -> impl Widget<UiState> {
+    ValueTextBox::new(TextBox::new().with_placeholder(placeholder), XpFormatter)
+        .validate_while_editing(true)
+        .delegate(XpErrorDelegate(idx, field))
+        .lens(lens)
+        .env_scope(move |env, t: &UiState| {
+            if t.xp_input_errors.get(idx, field).is_some() {
+                theme::apply_invalid(env);
+            }
+        })
+}
+
+/// Says why the text in a character's XP box can't be used, while it can't.
+#[cfg(feature = "full")]
+fn xp_error_label(idx: usize, field: XpField) -> impl Widget<UiState> {
+    Label::new(move |t: &UiState, _env: &Env| t.xp_input_errors.get(idx, field).cloned().unwrap_or_default())
+        .with_text_color(Color::rgb8(0xE0, 0x40, 0x40))
+}
+
+/// The character's summary, with badges for a dead or abandoned character, at the top of their tab.
+fn summary_header(idx: usize) -> impl Widget<UiState> {
+    let character_lens = UiState::characters_lens.index(idx);
+    Flex::row()
+        .with_child(Label::new(|c: &Character, _env: &Env| character_summary(c))
+            .with_text_size(theme::HEADING_TEXT_SIZE)
+            .lens(character_lens.clone()))
+        .with_default_spacer()
+        .with_child(Label::new(|c: &Character, _env: &Env| {
+            match (c.is_dead, c.is_abandoned) {
+                (true, true) => "DEAD  ABANDONED",
+                (true, false) => "DEAD",
+                (false, true) => "ABANDONED",
+                (false, false) => "",
+            }.to_string()
+        }).with_text_color(Color::rgb8(0xE0, 0x40, 0x40)).lens(character_lens))
+}
+
+/// Shown at the top of a character's tab when `file_warning` finds a problem, with a button to
+/// start the files afresh.
+fn file_warning_row(idx: usize) -> impl Widget<UiState> {
+    Either::new(
+        move |t: &UiState, _env| t.characters.get(idx).and_then(file_warning).is_some(),
+        Flex::row()
+            .with_child(Label::new(move |t: &UiState, _env: &Env| t.characters.get(idx).and_then(file_warning).unwrap_or_default())
+                .with_text_color(Color::rgb8(0xE0, 0x40, 0x40)))
+            .with_default_spacer()
+            .with_child(edit_button("Start Empty Files", Edit::FixCharacterFiles(idx))),
+        Flex::row(),
+    )
+}
+
+fn character_files_row(idx: usize) -> impl Widget<UiState> {
+    Flex::row()
+        .with_child(open_button("Open Inventory File", move |t| t.characters[idx].inventory_path.clone()))
+        .with_default_spacer()
+        .with_child(open_button("Open Loadout File", move |t| t.characters[idx].loadout_path.clone()))
+}
+
+/// Lets the user move between the saves of different Steam accounts. Hidden when there's only one.
+fn account_row() -> impl Widget<UiState> {
+    ViewSwitcher::new(
+        |data: &UiState, _env| (data.save_dirs.len(), data.save_dirs.iter().position(|x| *x == data.save_dir)),
+        |(count, current), data: &UiState, _env| {
+            let mut row = Flex::row();
+            if *count > 1 {
+                row.add_child(Label::new("Account:"));
+                for (idx, dir) in data.save_dirs.iter().enumerate() {
+                    let name = dir.file_name().unwrap_or(dir.as_os_str()).to_string_lossy().to_string();
+                    let is_current = *current == Some(idx);
+                    row.add_default_spacer();
+                    row.add_child(Button::new(name)
+                        .on_click(move |_ctx, t: &mut UiState, _env| {
+                            if t.is_dirty() && t.config.confirm_unsaved {
+                                t.prompt = Some(Prompt::SwitchSaveDir(idx));
+                            } else {
+                                t.change_save_dir(t.save_dirs[idx].clone());
+                            }
+                        })
+                        .disabled_if(move |_t: &UiState, _env| is_current));
+                }
+            }
+            Box::new(row)
+        }
+    )
+}
+
+fn error_view(data: &UiState) -> impl Widget<UiState> {
+    Flex::column()
+        .with_child(account_row())
+        .with_default_spacer()
+        .with_child(Label::new(format!("Error occurred during startup: {}", data.error.as_ref().unwrap_or(&"Unknown Error".to_string()))).with_line_break_mode(LineBreaking::WordWrap))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(choose_save_dir_button())
+            .with_default_spacer()
+            .with_child(open_server_button())
+            .with_default_spacer()
+            .with_child(Button::new("Open Anyway, Skipping Unreadable Characters")
+                .on_click(|_ctx, t: &mut UiState, _env| t.reload_skipping_broken())
+                .disabled_if(|t: &UiState, _env| t.save_dir.as_os_str().is_empty()))
+            .with_default_spacer()
+            .with_child(Button::new("Try to Repair Save Files...")
+                .on_click(|_ctx, t: &mut UiState, _env| { t.run_action("repair save files", UiState::plan_json_repair); })
+                .disabled_if(|t: &UiState, _env| t.save_dir.as_os_str().is_empty()))
+        )
+        .with_default_spacer()
+        .with_child(access_panel())
+        .with_default_spacer()
+        .with_child(salvage_panel())
+}
+
+/// Walks a new user through finding their save, checking it's theirs, backing it up and choosing
+/// whether to look for updates, before they see the rest of the editor.
+fn wizard_view() -> impl Widget<UiState> {
+    let step = ViewSwitcher::new(
+        |data: &UiState, _env| (data.wizard, data.error.is_some(), data.save_dir.to_string_lossy().to_string(), data.characters.len()),
+        |(step, _, _, _), data: &UiState, _env| {
+            let mut column = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            let step = match step {
+                Some(step) => *step,
+                None => return Box::new(column),
+            };
+            column.add_child(Label::new(step.title()).with_text_size(theme::HEADING_TEXT_SIZE));
+            column.add_default_spacer();
+            match step {
+                WizardStep::SaveFolder => {
+                    column.add_child(Label::new(format!("Save folder: {}", data.save_dir.to_string_lossy())).with_line_break_mode(LineBreaking::WordWrap));
+                    column.add_default_spacer();
+                    match &data.error {
+                        Some(error) => column.add_child(Label::new(format!("This folder can't be opened. {}", error))
+                            .with_text_color(Color::rgb8(0xE0, 0x40, 0x40))
+                            .with_line_break_mode(LineBreaking::WordWrap)),
+                        None => column.add_child(Label::new("This folder has a save in it. If you have more than one Steam account, pick the one to edit.")
+                            .with_line_break_mode(LineBreaking::WordWrap)),
+                    }
+                    column.add_default_spacer();
+                    let mut accounts = Flex::row();
+                    for (idx, dir) in data.save_dirs.iter().enumerate() {
+                        let name = dir.file_name().unwrap_or(dir.as_os_str()).to_string_lossy().to_string();
+                        accounts.add_child(Button::new(name)
+                            .on_click(move |_ctx, t: &mut UiState, _env| t.change_save_dir(t.save_dirs[idx].clone()))
+                            .disabled_if(move |t: &UiState, _env| t.save_dirs[idx] == t.save_dir));
+                        accounts.add_default_spacer();
+                    }
+                    accounts.add_child(choose_save_dir_button());
+                    column.add_child(accounts);
+                }
+                WizardStep::Characters => {
+                    column.add_child(Label::new("These are the characters in the save. If they aren't yours, go back and pick another folder."));
+                    column.add_default_spacer();
+                    if data.characters.is_empty() {
+                        column.add_child(Label::new("There are no characters in this save yet."));
+                    }
+                    for c in data.characters.iter() {
+                        column.add_child(Label::new(format!("{} - slot {}, level {}", c.character_name, c.character_slot, c.level())));
+                    }
+                }
+                WizardStep::Backup => {
+                    column.add_child(Label::new("Before changing anything, make a backup of the whole save so you can always go back to how it is now. \
+                        Backups go in the Backups folder next to Profile.json, and one is also made every time you save.")
+                        .with_line_break_mode(LineBreaking::WordWrap));
+                    column.add_default_spacer();
+                    column.add_child(Button::new("Back Up Now")
+                        .on_click(|_ctx, t: &mut UiState, _env| t.back_up_now())
+                        .disabled_if(|t: &UiState, _env| t.config.safe_mode || t.is_busy()));
+                }
+                WizardStep::Updates => {
+                    column.add_child(Label::new("The editor can look for a new version on GitHub each time it starts. It's off unless you turn it on, as it goes online.")
+                        .with_line_break_mode(LineBreaking::WordWrap));
+                    column.add_default_spacer();
+                    column.add_child(Checkbox::new("Check for new versions at startup").lens(UiState::config_lens.then(Config::check_for_updates)));
+                }
+            }
+            Box::new(column)
+        }
+    );
+
+    let buttons = Flex::row()
+        .with_child(Button::new("Back")
+            .on_click(|_ctx, t: &mut UiState, _env| t.wizard_back())
+            .disabled_if(|t: &UiState, _env| t.wizard.map(|x| x.is_first()).unwrap_or(true)))
+        .with_default_spacer()
+        .with_child(Button::new(|t: &UiState, _env: &Env| if t.wizard.map(|x| x.is_last()).unwrap_or(false) { "Finish".to_string() } else { "Next".to_string() })
+            .on_click(|_ctx, t: &mut UiState, _env| t.wizard_next())
+            .disabled_if(|t: &UiState, _env| t.is_busy() || (t.wizard == Some(WizardStep::SaveFolder) && t.error.is_some())))
+        .with_default_spacer()
+        .with_child(Button::new("Skip Setup").on_click(|_ctx, t: &mut UiState, _env| t.finish_wizard()));
+
+    let wizard = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(step.fix_width(600.0))
+        .with_default_spacer()
+        .with_child(buttons)
+        .with_default_spacer()
+        .with_child(status_bar());
+
+    Align::centered(wizard)
+}
+
+/// What seems to be stopping the save folder from opening, with what to do about it.
+fn access_panel() -> impl Widget<UiState> {
+    let problems = ViewSwitcher::new(
+        |data: &UiState, _env| data.access_problems.clone(),
+        |problems, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for problem in problems.iter() {
+                list.add_child(Label::new(problem.title()).with_text_color(Color::rgb8(0xE0, 0x40, 0x40)).with_line_break_mode(LineBreaking::WordWrap));
+                list.add_child(Label::new(problem.advice()).with_line_break_mode(LineBreaking::WordWrap));
+                list.add_default_spacer();
+            }
+            Box::new(list)
+        }
+    );
+
+    let mut buttons = Flex::row()
+        .with_child(Button::new("Retry").on_click(|_ctx, t: &mut UiState, _env| t.reload_from_disk()))
+        .with_default_spacer()
+        .with_child(Button::new("Make Files Writable")
+            .on_click(|_ctx, t: &mut UiState, _env| {
+                if t.run_action("make the save files writable", |t| t.check_writable().and_then(|_| access::make_writable(&t.save_dir))) {
+                    t.reload_from_disk();
+                }
+            })
+            .disabled_if(|t: &UiState, _env| !t.access_problems.iter().any(|x| matches!(x, AccessProblem::ReadOnly(_)))));
+    if cfg!(windows) {
+        buttons.add_default_spacer();
+        buttons.add_child(Button::new("Restart as Administrator").on_click(|ctx, t: &mut UiState, _env| {
+            if t.run_action("restart as administrator", |_| shell::restart_as_admin()) {
+                ctx.submit_command(commands::QUIT_APP);
+            }
+        }));
+    }
+
+    Either::new(
+        |data: &UiState, _env| data.access_problems.is_empty(),
+        Flex::column(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(problems)
+            .with_child(buttons),
+    )
+}
+
+/// What repairing the unreadable save files would do, so it can be checked before anything is
+/// written.
+fn salvage_panel() -> impl Widget<UiState> {
+    let salvages = ViewSwitcher::new(
+        |data: &UiState, _env| data.salvages.clone(),
+        |salvages, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for salvage in salvages.iter() {
+                list.add_child(Label::new(format!(
+                    "[{}]: {} ({} of {} bytes)",
+                    salvage.path.to_string_lossy(),
+                    salvage.method,
+                    salvage.text.len(),
+                    salvage.original_length,
+                )).with_line_break_mode(LineBreaking::WordWrap));
+                list.add_child(Label::new(salvage.preview()).with_text_size(theme::SMALL_TEXT_SIZE).with_line_break_mode(LineBreaking::WordWrap));
+                list.add_default_spacer();
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
+
+    Either::new(
+        |data: &UiState, _env| data.salvages.is_empty(),
+        Flex::column(),
+        Flex::column()
+            .with_child(salvages.fix_height(250.0))
+            .with_default_spacer()
+            .with_child(Flex::row()
+                .with_child(Button::new("Write Repaired Files")
+                    .on_click(|_ctx, t: &mut UiState, _env| { t.run_action("write repaired files", UiState::apply_json_repair); })
+                    .disabled_if(|t: &UiState, _env| t.config.safe_mode))
+                .with_default_spacer()
+                .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.salvages.clear()))
+            ),
+    )
+}
+
+/// Shown when the game or Steam has changed the save since it was loaded.
+fn external_change_banner() -> impl Widget<UiState> {
+    let banner = Flex::row()
+        .with_child(Label::new("Save files changed on disk").with_text_color(Color::rgb8(0xE0, 0xA0, 0x20)))
+        .with_default_spacer()
+        .with_child(Button::new("Reload").on_click(|_ctx, t: &mut UiState, _env| t.reload_from_disk()))
+        .with_default_spacer()
+        .with_child(Button::new("Keep My Edits").on_click(|_ctx, t: &mut UiState, _env| t.keep_edits()));
+
+    Either::new(|data: &UiState, _env| data.external_change, banner, Flex::row())
+}
+
+/// Shown when the update check finds a newer release.
+fn update_banner() -> impl Widget<UiState> {
+    let banner = Flex::row()
+        .with_child(Label::new(|data: &UiState, _env: &Env| {
+            let version = data.available_update.as_ref().map(|x| x.version.as_str()).unwrap_or_default();
+            format!("Version {} is available - newer game patches may need it", version)
+        }).with_text_color(Color::rgb8(0xE0, 0xA0, 0x20)))
+        .with_default_spacer()
+        .with_child(Button::new("Download").on_click(|_ctx, t: &mut UiState, _env| {
+            if let Some(release) = t.available_update.clone() {
+                t.run_action("open the download page", |_| shell::open_url(&release.url));
+            }
+        }))
+        .with_default_spacer()
+        .with_child(Button::new("Not Now").on_click(|_ctx, t: &mut UiState, _env| t.available_update = None));
+
+    Either::new(|data: &UiState, _env| data.available_update.is_some(), banner, Flex::row())
+}
+
+/// The save folder, when it was last saved and how the last action went.
+fn status_bar() -> impl Widget<UiState> {
+    let progress = Either::new(
+        |data: &UiState, _env| data.is_busy(),
+        Flex::row()
+            .with_child(Spinner::new().fix_size(12.0, 12.0))
+            .with_default_spacer()
+            .with_child(Label::new(|data: &UiState, _env: &Env| format!("{}...", data.busy.iter().cloned().collect::<Vec<&str>>().join(", "))).with_text_size(theme::SMALL_TEXT_SIZE))
+            .with_default_spacer(),
+        Flex::row(),
+    );
+
+    Flex::row()
+        .with_child(progress)
+        .with_child(Label::new(|data: &UiState, _env: &Env| {
+            let last_saved = data.last_saved.clone().unwrap_or_else(|| "not yet".to_string());
+            let unsaved = match data.changed_files().len() {
+                0 => String::new(),
+                1 => " (1 file unsaved)".to_string(),
+                n => format!(" ({} files unsaved)", n),
+            };
+            format!("{}  |  Last saved: {}{}  |  {}", data.save_dir.to_string_lossy(), last_saved, unsaved, data.status)
+        }).with_text_size(theme::SMALL_TEXT_SIZE))
+}
+
+/// Anything that went wrong reading the save that didn't stop it from loading.
+fn warnings_panel() -> impl Widget<UiState> {
+    let warnings = ViewSwitcher::new(
+        |data: &UiState, _env| data.load_warnings.clone(),
+        |warnings, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for warning in warnings.iter() {
+                list.add_child(Label::new(warning.clone()).with_text_color(Color::rgb8(0xE0, 0x40, 0x40)).with_line_break_mode(LineBreaking::WordWrap));
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
 
-        Ok(data)
-    }
+    let steam_cloud = Either::new(
+        |data: &UiState, _env| data.steam_cloud.is_some(),
+        Button::new("Keep My Edits in Steam Cloud").on_click(|_ctx, t: &mut UiState, _env| {
+            t.run_action("update Steam Cloud cache", UiState::prefer_local_in_steam_cloud);
+        }),
+        Flex::column(),
+    );
+
+    Either::new(
+        |data: &UiState, _env| data.load_warnings.is_empty(),
+        Flex::column(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(warnings.fix_height(80.0))
+            .with_child(steam_cloud),
+    )
+}
+
+/// Every change the editor has made to this save, newest first.
+fn audit_log_panel() -> impl Widget<UiState> {
+    let entries = ViewSwitcher::new(
+        |data: &UiState, _env| data.audit_log.clone().unwrap_or_default(),
+        |entries, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            if entries.is_empty() {
+                list.add_child(Label::new("Nothing changed yet"));
+            }
+            for entry in entries.iter().rev() {
+                list.add_child(Label::new(format!("{}  {}: {} -> {}", entry.time, entry.field, entry.old, entry.new))
+                    .with_line_break_mode(LineBreaking::WordWrap));
+            }
+            Box::new(Scroll::new(list).vertical())
+        }
+    );
 
-    pub fn save(&self) -> Result<(), Box<dyn Error>> {
-        let mut profile_file_io = OpenOptions::new().write(true).open(self.profile_file.clone())?;
-        let profile_string = serde_json::to_string(&self.profile)?;
-        let profile_bytes = profile_string.as_bytes();
-        profile_file_io.set_len(profile_bytes.len() as u64)?;
-        profile_file_io.write_all(profile_bytes)?;
-        profile_file_io.flush()?;
+    let header = Flex::row()
+        .with_child(Label::new(|data: &UiState, _env: &Env| {
+            format!("Change log: {} changes, {} not saved yet", data.audit_log.as_ref().map(|x| x.len()).unwrap_or(0), data.audit_pending.len())
+        }))
+        .with_default_spacer()
+        .with_child(open_button("Open Log File", |t| audit::log_path(&t.save_dir)))
+        .with_default_spacer()
+        .with_child(Button::new("Close").on_click(|_ctx, t: &mut UiState, _env| t.audit_log = None));
 
-        let mut character_file_io = OpenOptions::new().write(true).open(self.characters_file.clone())?;
-        let mut characters = Characters {
-            characters_json: Vector::new(),
-        };
+    Either::new(
+        |data: &UiState, _env| data.audit_log.is_some(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(header)
+            .with_child(entries.fix_height(160.0)),
+        Flex::column(),
+    )
+}
 
-        for c in &self.characters {
-            let character_string = serde_json::to_string(c)?;
-            characters.characters_json.push_front(character_string);
+/// The problems "Validate Save" found, each with a button to fix it.
+fn validation_panel() -> impl Widget<UiState> {
+    let issues = ViewSwitcher::new(
+        |data: &UiState, _env| data.validation_issues.clone().unwrap_or_default(),
+        |issues, _data: &UiState, _env| {
+            let mut list = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+            for issue in issues.iter() {
+                list.add_child(Flex::row()
+                    .with_child(edit_button("Fix", Edit::RepairIssue(issue.clone())))
+                    .with_default_spacer()
+                    .with_child(Label::new(issue.description.clone()))
+                );
+            }
+            Box::new(Scroll::new(list).vertical())
         }
+    );
+
+    let header = Flex::row()
+        .with_child(Label::new(|data: &UiState, _env: &Env| match data.validation_issues.as_ref().map(|x| x.len()) {
+            Some(0) => "Validation: no problems found".to_string(),
+            Some(n) => format!("Validation: {} problems found", n),
+            None => String::new(),
+        }))
+        .with_default_spacer()
+        .with_child(edit_button("Fix All", Edit::RepairAllIssues)
+            .disabled_if(|t: &UiState, _env| t.validation_issues.as_ref().map(|x| x.is_empty()).unwrap_or(true)))
+        .with_default_spacer()
+        .with_child(Button::new("Close").on_click(|_ctx, t: &mut UiState, _env| t.validation_issues = None));
 
-        let characters_string = serde_json::to_string(&characters)?;
-        let characters_bytes = characters_string.as_bytes();
+    Either::new(
+        |data: &UiState, _env| data.validation_issues.is_some(),
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(header)
+            .with_child(issues.fix_height(120.0)),
+        Flex::column(),
+    )
+}
 
-        character_file_io.set_len(characters_bytes.len() as u64)?;
-        character_file_io.write_all(characters_bytes)?;
-        character_file_io.flush()?;
+fn switch_save_dir_prompt(idx: usize) -> Flex<UiState> {
+    Flex::column()
+        .with_child(Label::new("You have unsaved changes. Save changes before switching accounts?"))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Save").on_click(move |_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                t.request_save_of(t.changed_files(), move |t| t.change_save_dir(t.save_dirs[idx].clone()));
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Don't Save").on_click(move |_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                t.change_save_dir(t.save_dirs[idx].clone());
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+}
 
-        Ok(())
-    }
+fn confirm_prompt(action: BulkAction, data: &UiState) -> Flex<UiState> {
+    Flex::column()
+        .with_child(Label::new(action.question(data)))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new(action.label()).on_click(move |_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                t.bulk_action(action);
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+        .with_default_spacer()
+        .with_child(Checkbox::new("Don't ask again").lens(UiState::config_lens.then(Config::confirm_bulk_changes).then(lens::Not)))
 }
 
-#[derive(Clone, Data)]
-struct CharTabs {
+fn reload_prompt() -> Flex<UiState> {
+    Flex::column()
+        .with_child(Label::new("You have unsaved changes. Throw them away and load the save again?"))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Reload").on_click(|_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                t.reload_from_disk();
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+}
 
+fn close_prompt() -> Flex<UiState> {
+    Flex::column()
+        .with_child(Label::new("You have unsaved changes. Save changes before closing?"))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Save").on_click(|_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                t.request_save_of(t.changed_files(), |_| Application::global().quit());
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Don't Save").on_click(|ctx, _t: &mut UiState, _env| ctx.submit_command(commands::QUIT_APP)))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
 }
 
-impl TabsPolicy for CharTabs {
-    type Key = usize;
-    type Input = UiState;
-    type BodyWidget = Flex<UiState>;
-    type LabelWidget = Label<UiState>;
-    type Build = ();
+fn delete_character_prompt(idx: usize, data: &UiState) -> Flex<UiState> {
+    let name = data.characters.get(idx).map(|c| c.character_name.clone()).unwrap_or_default();
+    Flex::column()
+        .with_child(Label::new(format!("Delete {}? The character is removed from Characters.json when you save.", name)))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(prompt_edit_button("Delete", Edit::DeleteCharacter { idx, remove_files: false }))
+            .with_default_spacer()
+            .with_child(prompt_edit_button("Delete and Remove Files", Edit::DeleteCharacter { idx, remove_files: true }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+        .with_default_spacer()
+        .with_child(Label::new("Removed inventory and loadout files are kept in the backup taken on save."))
+}
 
-    fn tabs_changed(&self, old_data: &Self::Input, data: &Self::Input) -> bool {
-        !old_data.characters.eq(&data.characters)
-    }
+fn revive_prompt(idx: usize, data: &UiState) -> Flex<UiState> {
+    let (name, xp_debt) = data.characters.get(idx).map(|c| (c.character_name.clone(), c.xp_debt)).unwrap_or_default();
+    Flex::column()
+        .with_child(Label::new(format!("Revive {}? They have {} XP debt from dying.", name, xp_debt)))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(prompt_edit_button("Revive", Edit::Revive { idx, debt: ReviveDebt::Keep }))
+            .with_default_spacer()
+            .with_child(prompt_edit_button("Revive and Forgive Debt", Edit::Revive { idx, debt: ReviveDebt::Forgive }))
+            .with_default_spacer()
+            .with_child(prompt_edit_button("Revive and Take Debt from XP", Edit::Revive { idx, debt: ReviveDebt::TakeFromXp }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+}
 
-    fn tabs(&self, data: &Self::Input) -> Vec<Self::Key> {
-        data.characters.iter().map(|x| x.character_slot as usize).collect()
+fn save_prompt(data: &UiState) -> Flex<UiState> {
+    let mut files = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for (idx, choice) in data.save_choices.iter().enumerate() {
+        files.add_child(Checkbox::new(choice.label.clone()).lens(UiState::save_choices_lens.index(idx).then(SaveChoice::chosen)));
     }
 
-    fn tab_info(&self, key: Self::Key, _: &Self::Input) -> TabInfo<Self::Input> {
-        println!("Loading tab info for key {}", key);
-        TabInfo::new(
-            LabelText::from(move |state: &UiState, _: &Env|{
-                state.characters.iter().find(|x| x.character_slot as usize == key).map(|x| x.character_name.clone()).expect("unreachable")
-            }),
-            false,
+    Flex::column()
+        .with_child(Label::new("Choose the files to save. Files with unsaved changes are ticked, and every file is backed up first."))
+        .with_default_spacer()
+        .with_child(Scroll::new(files).vertical().fix_height(250.0))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Save")
+                .on_click(|_ctx, t: &mut UiState, _env| {
+                    t.prompt = None;
+                    t.request_save_of(t.chosen_files(), |_| {});
+                })
+                .disabled_if(|t: &UiState, _env| t.chosen_files().is_empty()))
+            .with_default_spacer()
+            .with_child(Button::new("All").on_click(|_ctx, t: &mut UiState, _env| t.choose_all_files(true)))
+            .with_child(Button::new("None").on_click(|_ctx, t: &mut UiState, _env| t.choose_all_files(false)))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
         )
-    }
+}
 
-    #[cfg(feature = "full")]
-    fn tab_body(&self, key: Self::Key, data: &Self::Input) -> Self::BodyWidget {
-        println!("Loading tab body for key {}", key);
-        let idx = data.characters.index_of(data.characters.iter().find(|x| x.character_slot as usize == key).expect("not possible")).expect("not possible");
-        println!("Found idx {}", idx);
-        let character_lens = UiState::characters_lens.index(idx);
-        Flex::column()
-            .cross_axis_alignment(CrossAxisAlignment::Start)
-            .with_child(Flex::row()
-                .with_child(Label::new(format!("Current Prospect: {}", data.characters[idx].location)))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Label::new("XP"))
-                .with_default_spacer()
-                .with_child(ValueTextBox::new(TextBox::new(), ParseFormatter::<f64>::new()).lens(character_lens.clone().then(Character::xp)))
-                .with_default_spacer()
-                .with_child(Button::new("Max Level").on_click(|_, state: &mut Character, _| state.level_to_max() ).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Label::new("XP Debt"))
-                .with_default_spacer()
-                .with_child(ValueTextBox::new(TextBox::new(), ParseFormatter::<f64>::new()).lens(character_lens.clone().then(Character::xp_debt)))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Label::new("Dead"))
-                .with_default_spacer()
-                .with_child(Checkbox::new("").lens(character_lens.clone().then(Character::is_dead)).disabled_if(|_, _| true))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Label::new("Abandoned"))
-                .with_default_spacer()
-                .with_child(Checkbox::new("")
-                    .disabled_if(|state: &bool, _ctx| !*state)
-                    .lens(character_lens.clone().then(Character::is_abandoned)))
-                .with_child(Button::new("Restore Character")
-                    .on_click(|_ctx, t: &mut Character, _env|{ t.restore().expect("Restoring character failed unexpectedly") })
-                    .disabled_if(|state: &Character, _ctx| !state.is_abandoned)
-                    .lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Reset Talents").on_click(|_ctx, t: &mut Character, _env| t.reset_talents()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Reset Blueprints").on_click(|_ctx, t: &mut Character, _env| t.reset_blueprints()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Unlock All Talents").on_click(|_ctx, t: &mut Character, _env| t.unlock_all_talents()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Unlock All Blueprints").on_click(|_ctx, t: &mut Character, _env| t.unlock_all_blueprints()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Checkbox::new("Exotic Mining Unlocked").lens(character_lens.clone().then(Character::unlocked_flags).then(FlagLens{ flag: EXOTIC_MINING_FLAG })))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Checkbox::new("Exotic Extraction Unlocked").lens(character_lens.clone().then(Character::unlocked_flags).then(FlagLens{ flag: EXOTIC_EXTRACTION_FLAG })))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Save").on_click(|_ctx, t: &mut UiState, _env| t.save().expect("Error saving profile and/or character data") ))
-            )
-    }
+/// Asks whether to go ahead with a save, or with `action` if it writes straight away, while the game
+/// is running.
+fn game_running_prompt(action: Option<BulkAction>) -> Flex<UiState> {
+    let label = match action {
+        Some(action) => format!("{} Anyway", action.label()),
+        None => "Save Anyway".to_string(),
+    };
 
-    #[cfg(feature = "minimal")]
-    fn tab_body(&self, key: Self::Key, data: &Self::Input) -> Self::BodyWidget {
-        println!("Loading tab body for key {}", key);
-        let idx = data.characters.index_of(data.characters.iter().find(|x| x.character_slot as usize == key).expect("not possible")).expect("not possible");
-        println!("Found idx {}", idx);
-        let character_lens = UiState::characters_lens.index(idx);
-        Flex::column()
-            .cross_axis_alignment(CrossAxisAlignment::Start)
-            .with_child(Flex::row()
-                .with_child(Label::new(format!("Current Prospect: {}", data.characters[idx].location)))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Label::new("Abandoned"))
-                .with_default_spacer()
-                .with_child(Checkbox::new("")
-                    .disabled_if(|state: &bool, _ctx| !*state)
-                    .lens(character_lens.clone().then(Character::is_abandoned)))
-                .with_child(Button::new("Restore Character")
-                    .on_click(|_ctx, t: &mut Character, _env|{ t.restore().expect("Restoring character failed unexpectedly") })
-                    .disabled_if(|state: &Character, _ctx| !state.is_abandoned)
-                    .lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Reset Talents").on_click(|_ctx, t: &mut Character, _env| t.reset_talents()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Reset Blueprints").on_click(|_ctx, t: &mut Character, _env| t.reset_blueprints()).lens(character_lens.clone()))
-            ).with_default_spacer()
-            .with_child(Flex::row()
-                .with_child(Button::new("Save").on_click(|_ctx, t: &mut UiState, _env| t.save().expect("Error saving profile and/or character data") ))
-            )
+    Flex::column()
+        .with_child(Label::new(format!("Icarus ({}) is running. It overwrites the save files when it exits, so changes saved now will be lost.", guard::GAME_PROCESS)))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new(label).on_click(move |_ctx, t: &mut UiState, _env| {
+                t.prompt = None;
+                match action {
+                    Some(action) => t.write_now(action),
+                    None => {
+                        let files = std::mem::take(&mut t.pending_save);
+                        t.save_in_background(files, |_| {});
+                    }
+                }
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("Cancel").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+}
+
+fn message_prompt(message: &str) -> Flex<UiState> {
+    Flex::column()
+        .with_child(Label::new(message.to_string()))
+        .with_default_spacer()
+        .with_child(Button::new("OK").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+}
+
+fn restart_prompt(message: &str) -> Flex<UiState> {
+    let unsaved = Either::new(
+        |t: &UiState, _env| t.is_dirty(),
+        Label::new("Save or undo your changes to restart now."),
+        Flex::row(),
+    );
+
+    Flex::column()
+        .with_child(Label::new(message.to_string()))
+        .with_default_spacer()
+        .with_child(unsaved)
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Restart Now").on_click(|ctx, t: &mut UiState, _env| {
+                if t.run_action("restart the editor", |_| shell::restart()) {
+                    ctx.submit_command(commands::QUIT_APP);
+                }
+            }).disabled_if(|t: &UiState, _env| t.is_dirty()))
+            .with_default_spacer()
+            .with_child(Button::new("Later").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None))
+        )
+}
+
+/// The editor's version, where its game data came from and where the save is, with a link for
+/// reporting problems.
+fn about_prompt(data: &UiState) -> Flex<UiState> {
+    let mut data_files = Flex::column().cross_axis_alignment(CrossAxisAlignment::Start);
+    for line in about::data_file_lines() {
+        data_files.add_child(Label::new(line));
     }
 
-    fn tab_label(&self, _: Self::Key, info: TabInfo<Self::Input>, _: &Self::Input) -> Self::LabelWidget {
-        Self::default_make_label(info)
+    Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .with_child(Label::new("Icarus Offline Character Editor").with_text_size(theme::HEADING_TEXT_SIZE))
+        .with_default_spacer()
+        .with_child(Label::new(cli::version_text()))
+        .with_default_spacer()
+        .with_child(data_files)
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Label::new(format!("Save folder: {}", data.save_dir.to_string_lossy())))
+            .with_default_spacer()
+            .with_child(open_button("Open", |t| t.save_dir.clone())))
+        .with_child(Label::new(format!("Last backup: {}", data.last_backup())))
+        .with_default_spacer()
+        .with_child(Flex::row()
+            .with_child(Button::new("Report a Problem").on_click(|_ctx, t: &mut UiState, _env| {
+                t.run_action("open the issues page", |_| shell::open_url(about::ISSUES_URL));
+            }))
+            .with_default_spacer()
+            .with_child(Button::new("OK").on_click(|_ctx, t: &mut UiState, _env| t.prompt = None)))
+}
+
+/// Shows whichever question is currently waiting on the user in place of the main view.
+fn prompt_view() -> impl Widget<UiState> {
+    ViewSwitcher::new(
+        |data: &UiState, _env| data.prompt.clone(),
+        |prompt, data: &UiState, _env| {
+            let layout = match prompt {
+                Some(Prompt::Close) => close_prompt(),
+                Some(Prompt::DeleteCharacter(idx)) => delete_character_prompt(*idx, data),
+                Some(Prompt::Revive(idx)) => revive_prompt(*idx, data),
+                Some(Prompt::GameRunning(action)) => game_running_prompt(*action),
+                Some(Prompt::Save) => save_prompt(data),
+                Some(Prompt::SwitchSaveDir(idx)) => switch_save_dir_prompt(*idx),
+                Some(Prompt::Reload) => reload_prompt(),
+                Some(Prompt::Confirm(action)) => confirm_prompt(*action, data),
+                Some(Prompt::Message(message)) => message_prompt(message),
+                Some(Prompt::Restart(message)) => restart_prompt(message),
+                Some(Prompt::About) => about_prompt(data),
+                None => Flex::column(),
+            };
+            Box::new(Align::centered(layout))
+        }
+    )
+}
+
+/// Whether the menu's actions can be used - not while a prompt or the error screen has its own
+/// buttons, as with the keyboard shortcuts.
+fn menu_enabled(t: &UiState, _env: &Env) -> bool {
+    t.prompt.is_none() && t.error.is_none()
+}
+
+/// The window's menu bar. Menu actions happen outside the widgets, so undo steps and the change log
+/// are kept here rather than by `UndoController`.
+fn app_menu(_window: Option<WindowId>, _data: &UiState, _env: &Env) -> Menu<UiState> {
+    let file = Menu::new("File")
+        .entry(MenuItem::new("Open Save Folder...")
+            .on_activate(|ctx, _t: &mut UiState, _env| ctx.submit_command(commands::SHOW_OPEN_PANEL.with(choose_save_dir_options())))
+            .enabled_if(|t: &UiState, _env| t.prompt.is_none()))
+        .entry(MenuItem::new("Reload").on_activate(|_ctx, t: &mut UiState, _env| t.request_reload()).enabled_if(menu_enabled))
+        .entry(MenuItem::new("Save...")
+            .on_activate(|_ctx, t: &mut UiState, _env| t.open_save_dialog())
+            .enabled_if(|t: &UiState, env| menu_enabled(t, env) && !t.config.safe_mode))
+        .separator()
+        .entry(MenuItem::new("Exit").on_activate(|ctx, t: &mut UiState, _env| {
+            if t.asks_before_closing() {
+                t.prompt = Some(Prompt::Close);
+            } else {
+                ctx.submit_command(commands::QUIT_APP);
+            }
+        }));
+    let edit = Menu::new("Edit")
+        .entry(MenuItem::new("Undo")
+            .on_activate(|_ctx, t: &mut UiState, _env| t.undo_logged())
+            .enabled_if(|t: &UiState, env| menu_enabled(t, env) && t.history.can_undo()))
+        .entry(MenuItem::new("Redo")
+            .on_activate(|_ctx, t: &mut UiState, _env| t.redo_logged())
+            .enabled_if(|t: &UiState, env| menu_enabled(t, env) && t.history.can_redo()));
+    let tools = Menu::new("Tools")
+        .entry(MenuItem::new("Validate Save").on_activate(|_ctx, t: &mut UiState, _env| t.validate_save()).enabled_if(menu_enabled))
+        .entry(MenuItem::new("Back Up Now")
+            .on_activate(|_ctx, t: &mut UiState, _env| t.back_up_now())
+            .enabled_if(|t: &UiState, env| menu_enabled(t, env) && !t.config.safe_mode))
+        .entry(MenuItem::new("Open Backups Folder").on_activate(|_ctx, t: &mut UiState, _env| {
+            let folder = backup::backup_root(&t.save_dir);
+            t.run_action("open the backups folder", |_| shell::open(&folder));
+        }));
+    let help = Menu::new("Help")
+        .entry(MenuItem::new("About").on_activate(|_ctx, t: &mut UiState, _env| t.prompt = Some(Prompt::About)));
+
+    Menu::empty().entry(file).entry(edit).entry(tools).entry(help)
+}
+
+fn window_title(data: &UiState, _env: &Env) -> String {
+    let mut title = "Icarus Offline Character Editor".to_string();
+    if data.config.safe_mode {
+        title.push_str(" (safe mode)");
     }
+    if data.is_dirty() {
+        title.push_str(" (modified)");
+    }
+    title
 }
 
 #[cfg(feature = "full")]
@@ -570,53 +4805,78 @@ fn ui_builder() -> impl Widget<UiState> {
         |selector, data: &UiState, _env| {
             Box::new(match selector {
                 MainView::Data => {
-                    let label_credits = Label::<UiState>::new("Credits: ");
-                    let textbox_credits = ValueTextBox::new(TextBox::new(), ParseFormatter::<f64>::new())
-                        .fix_width(100.0)
-                        .lens(UiState::profile_lens.then(Profile::meta_resources).then(MetaResourceLens { resource_name: META_RESOURCE_CREDITS.to_string() }).then(MetaResources::count));
-                    let label_exotics = Label::<UiState>::new("Exotics: ");
-                    let textbox_exotics = ValueTextBox::new(TextBox::new(), ParseFormatter::<f64>::new())
-                        .fix_width(100.0)
-                        .lens(UiState::profile_lens.then(Profile::meta_resources).then(MetaResourceLens { resource_name: META_RESOURCE_EXOTICS.to_string() }).then(MetaResources::count));
-                    let label_retraining_points = Label::<UiState>::new("Retraining Points: ");
-                    let textbox_retraining_points = ValueTextBox::new(TextBox::new(), ParseFormatter::<f64>::new())
-                        .fix_width(100.0)
-                        .lens(UiState::profile_lens.then(Profile::meta_resources).then(MetaResourceLens { resource_name: META_RESOURCE_RETRAINING_POINTS.to_string() }).then(MetaResources::count));
                     let checkbox_exotic_miner_radar = Checkbox::new("Workshop Exotic Miner & Radar Unlocked")
-                        .lens(UiState::profile_lens.then(Profile::talents).then(ProspectLens { prospect: "Prospect_OLY_Arctic_Extraction" }));
+                        .lens(UiState::profile_lens.then(Profile::talents).then(UnlockLens { row_name: "Prospect_OLY_Arctic_Extraction" }));
                     let checkbox_deep_ore_scanner = Checkbox::new("Workshop Deep Ore Vein Scanner Unlocked")
-                        .lens(UiState::profile_lens.then(Profile::talents).then(ProspectLens { prospect: "Prospect_OLY_Riverlands_Extraction" }));
-                    let tabs = Tabs::for_policy(CharTabs{})/*.lens(UiState)*/;
+                        .lens(UiState::profile_lens.then(Profile::talents).then(UnlockLens { row_name: "Prospect_OLY_Riverlands_Extraction" }));
+                    let tabs = Tabs::new()
+                        .with_tab("Characters", character_tabs())
+                        .with_tab("Workshop", workshop_tab())
+                        .with_tab("Prospect Unlocks", prospect_unlocks_tab())
+                        .with_tab("Profile Talents", profile_talents_tab())
+                        .with_tab("Prospect Saves", prospects_tab())
+                        .with_tab("Compare", compare_tab())
+                        .with_tab("Advanced", advanced_tab(RawTarget::Profile))
+                        .with_tab_index(data.config.last_tab);
                     let layout = Flex::column()
-                        .with_child(Flex::row().with_child(label_credits).with_default_spacer().with_child(textbox_credits))
+                        .with_child(account_row())
+                        .with_child(external_change_banner())
+                        .with_child(update_banner())
                         .with_default_spacer()
-                        .with_child(Flex::row().with_child(label_exotics).with_default_spacer().with_child(textbox_exotics))
+                        .with_child(meta_resources_panel())
                         .with_default_spacer()
-                        .with_child(Flex::row().with_child(label_retraining_points).with_default_spacer().with_child(textbox_retraining_points))
+                        .with_child(profile_transfer_row())
                         .with_default_spacer()
                         .with_child(Flex::row().with_child(checkbox_exotic_miner_radar).with_default_spacer().with_child(checkbox_deep_ore_scanner))
                         .with_default_spacer()
                         .with_child(Flex::row()
-                            .with_child(Button::new("Unlock All Prospects").on_click(|_ctx, t: &mut Profile, _env| t.unlock_all_prospects()).lens(UiState::profile_lens))
+                            .with_child(bulk_action_button(BulkAction::UnlockAllProspects))
                         )
                         .with_default_spacer()
+                        .with_child(dlc_row())
+                        .with_default_spacer()
                         .with_child(Flex::row()
-                            .with_child(Button::new("Unlock All Workshop Items").on_click(|_ctx, t: &mut Profile, _env| t.unlock_all_workshop_items()).lens(UiState::profile_lens))
+                            .with_child(bulk_action_button(BulkAction::UnlockAllWorkshopItems))
                         )
                         .with_default_spacer()
                         .with_child(Flex::row()
-                            .with_child(Button::new("Save").on_click(|_ctx, t: &mut UiState, _env| t.save().expect("Error saving data")))
+                            .with_child(save_button())
+                            .with_default_spacer()
+                            .with_child(Button::new("Undo")
+                                .on_click(|_ctx, t: &mut UiState, _env| t.undo())
+                                .disabled_if(|t: &UiState, _env| !t.history.can_undo()))
+                            .with_child(Button::new("Redo")
+                                .on_click(|_ctx, t: &mut UiState, _env| t.redo())
+                                .disabled_if(|t: &UiState, _env| !t.history.can_redo()))
+                            .with_default_spacer()
+                            .with_child(Button::new("Validate Save").on_click(|_ctx, t: &mut UiState, _env| t.validate_save()))
+                            .with_default_spacer()
+                            .with_child(Button::new("Change Log").on_click(|_ctx, t: &mut UiState, _env| { t.run_action("read change log", UiState::show_audit_log); }))
                         )
                         .with_default_spacer()
-                        .with_flex_child(tabs, 1.0);
+                        .with_child(warnings_panel())
+                        .with_child(validation_panel())
+                        .with_child(audit_log_panel())
+                        .with_child(save_dir_row())
+                        .with_default_spacer()
+                        .with_child(backups_panel())
+                        .with_default_spacer()
+                        .with_child(settings_panel())
+                        .with_default_spacer()
+                        .with_child(search_row())
+                        .with_default_spacer()
+                        .with_flex_child(tabs.controller(TabMemory), 1.0)
+                        .with_default_spacer()
+                        .with_child(status_bar());
                     Align::centered(layout)
                 },
-                MainView::Error => Align::centered(Label::new(format!("Error occurred during startup: {}", data.error.as_ref().unwrap_or(&"Unknown Error".to_string())))),
+                MainView::Error => Align::centered(error_view(data)),
             })
         }
     );
 
-    view_switcher
+    let main_view = Either::new(|data: &UiState, _env| data.wizard.is_some(), wizard_view(), view_switcher);
+    Either::new(|data: &UiState, _env| data.prompt.is_some(), prompt_view(), main_view)
 }
 
 #[cfg(feature = "minimal")]
@@ -626,49 +4886,575 @@ fn ui_builder() -> impl Widget<UiState> {
         |selector, data: &UiState, _env| {
             Box::new(match selector {
                 MainView::Data => {
-                    let tabs = Tabs::for_policy(CharTabs{})/*.lens(UiState)*/;
+                    let tabs = Tabs::new()
+                        .with_tab("Characters", character_tabs())
+                        .with_tab("Prospect Unlocks", prospect_unlocks_tab())
+                        .with_tab_index(data.config.last_tab);
                     let layout = Flex::column()
+                        .with_child(account_row())
+                        .with_child(external_change_banner())
+                        .with_child(update_banner())
+                        .with_default_spacer()
                         .with_child(Flex::row()
-                            .with_child(Button::new("Unlock All Prospects").on_click(|_ctx, t: &mut Profile, _env| t.unlock_all_prospects()).lens(UiState::profile_lens))
+                            .with_child(bulk_action_button(BulkAction::UnlockAllProspects))
                         )
                         .with_default_spacer()
+                        .with_child(dlc_row())
+                        .with_default_spacer()
                         .with_child(Flex::row()
-                            .with_child(Button::new("Save").on_click(|_ctx, t: &mut UiState, _env| t.save().expect("Error saving data")))
+                            .with_child(save_button())
+                            .with_default_spacer()
+                            .with_child(Button::new("Undo")
+                                .on_click(|_ctx, t: &mut UiState, _env| t.undo())
+                                .disabled_if(|t: &UiState, _env| !t.history.can_undo()))
+                            .with_child(Button::new("Redo")
+                                .on_click(|_ctx, t: &mut UiState, _env| t.redo())
+                                .disabled_if(|t: &UiState, _env| !t.history.can_redo()))
+                            .with_default_spacer()
+                            .with_child(Button::new("Validate Save").on_click(|_ctx, t: &mut UiState, _env| t.validate_save()))
+                            .with_default_spacer()
+                            .with_child(Button::new("Change Log").on_click(|_ctx, t: &mut UiState, _env| { t.run_action("read change log", UiState::show_audit_log); }))
                         )
                         .with_default_spacer()
-                        .with_flex_child(tabs, 1.0);
+                        .with_child(warnings_panel())
+                        .with_child(validation_panel())
+                        .with_child(audit_log_panel())
+                        .with_child(save_dir_row())
+                        .with_default_spacer()
+                        .with_child(backups_panel())
+                        .with_default_spacer()
+                        .with_child(settings_panel())
+                        .with_default_spacer()
+                        .with_child(search_row())
+                        .with_default_spacer()
+                        .with_flex_child(tabs.controller(TabMemory), 1.0)
+                        .with_default_spacer()
+                        .with_child(status_bar());
                     Align::centered(layout)
                 },
-                MainView::Error => Align::centered(Label::new(format!("Error occurred during startup: {}", data.error.as_ref().unwrap_or(&"Unknown Error".to_string())))),
+                MainView::Error => Align::centered(error_view(data)),
             })
         }
     );
 
-    view_switcher
+    let main_view = Either::new(|data: &UiState, _env| data.wizard.is_some(), wizard_view(), view_switcher);
+    Either::new(|data: &UiState, _env| data.prompt.is_some(), prompt_view(), main_view)
+}
+
+const CHOOSE_SAVE_DIR: Selector<FileInfo> = Selector::new("icarus-editor.choose-save-dir");
+const CHOOSE_SERVER_DIR: Selector<FileInfo> = Selector::new("icarus-editor.choose-server-dir");
+const EXPORT_CHARACTER: Selector<FileInfo> = Selector::new("icarus-editor.export-character");
+const IMPORT_CHARACTER: Selector<FileInfo> = Selector::new("icarus-editor.import-character");
+const CHARACTER_EXPORT_FILE: FileSpec = FileSpec::new("Character export", &["json"]);
+const EXPORT_PROFILE: Selector<FileInfo> = Selector::new("icarus-editor.export-profile");
+const IMPORT_PROFILE: Selector<FileInfo> = Selector::new("icarus-editor.import-profile");
+const PROFILE_EXPORT_FILE: FileSpec = FileSpec::new("Profile export", &["json"]);
+const EXPORT_SUMMARY: Selector<FileInfo> = Selector::new("icarus-editor.export-summary");
+const MARKDOWN_FILE: FileSpec = FileSpec::new("Markdown", &["md"]);
+const CSV_FILE: FileSpec = FileSpec::new("CSV", &["csv"]);
+const IMPORT_RANKS: Selector<FileInfo> = Selector::new("icarus-editor.import-ranks");
+const IMPORT_GAME_DATA: Selector<FileInfo> = Selector::new("icarus-editor.import-game-data");
+
+struct Delegate;
+
+impl AppDelegate<UiState> for Delegate {
+    fn command(&mut self, _ctx: &mut DelegateCtx, _target: Target, cmd: &Command, data: &mut UiState, _env: &Env) -> Handled {
+        if let Some(edit) = cmd.get(EDIT) {
+            data.apply_edit(edit.clone());
+            return Handled::Yes;
+        }
+        if let Some((idx, field, error)) = cmd.get(XP_INPUT_ERROR) {
+            data.set_xp_input_error(*idx, *field, error.clone());
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(CHOOSE_SERVER_DIR) {
+            data.run_action("open server saves", |t| t.open_server_dir(file_info.path()));
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(CHOOSE_SAVE_DIR) {
+            data.change_save_dir(file_info.path().to_path_buf());
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(EXPORT_CHARACTER) {
+            if let Some(idx) = data.pending_export.take() {
+                data.run_action("export character", |t| t.export_character(idx, file_info.path()));
+            }
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(EXPORT_PROFILE) {
+            data.run_action("export profile", |t| {
+                export::export_profile(&t.profile, file_info.path())?;
+                t.status = format!("Exported profile to [{}]", file_info.path().to_string_lossy());
+                Ok(())
+            });
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(EXPORT_SUMMARY) {
+            data.run_action("export summary", |t| {
+                let characters = t.characters.iter().cloned().collect::<Vec<Character>>();
+                summary::export_summary(&t.profile, &characters, file_info.path())?;
+                t.status = format!("Exported a summary to [{}]", file_info.path().to_string_lossy());
+                Ok(())
+            });
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(IMPORT_RANKS) {
+            if let Some(idx) = data.pending_rank_import.take() {
+                data.run_action("import ranks", |t| t.import_ranks(idx, file_info.path()));
+            }
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(IMPORT_PROFILE) {
+            data.run_action("import profile", |t| t.import_profile(file_info.path()));
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(IMPORT_GAME_DATA) {
+            data.run_action("import game data", |t| {
+                let written = game_data::import(file_info.path())?;
+                t.prompt = Some(Prompt::Restart(format!("Imported game data - restart the editor to use it.\n{}", written.join("\n"))));
+                Ok(())
+            });
+            return Handled::Yes;
+        }
+        if let Some(file_info) = cmd.get(IMPORT_CHARACTER) {
+            data.run_action("import character", |t| t.import_character(file_info.path()));
+            return Handled::Yes;
+        }
+
+        Handled::No
+    }
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let main_window = WindowDesc::new(ui_builder()).title("Icarus Offline Character Editor").window_size((750.0, 900.0));
-    let data = UiState::new();
-    match data {
-        Ok(d) => AppLauncher::with_window(main_window)
-            .log_to_console()
-            .launch(d)?,
-        Err(e) => AppLauncher::with_window(main_window)
-            .log_to_console()
-            .launch(UiState {
-                profile_file: Default::default(),
-                profile: Profile {
-                    user_id: "".to_string(),
-                    meta_resources: Default::default(),
-                    unlocked_flags: Default::default(),
-                    talents: Default::default()
-                },
-                characters_file: Default::default(),
-                characters: Default::default(),
-                error: Some(format!("Error: {}", e)),
-            })?,
+    let options = match cli::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    match options.command {
+        Some(cli::CliCommand::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Some(cli::CliCommand::Version) => {
+            println!("{}", cli::version_text());
+            return Ok(());
+        }
+        _ => {}
+    }
+    let config = Config::load();
+    let save_dir = match options.save_dir.clone().or_else(|| config.save_dir.clone()) {
+        Some(save_dir) => Ok(save_dir),
+        None => UiState::find_save_dir(),
+    };
+    // Hang on to the folder when loading fails, so the error screen can offer to retry it
+    let mut data = match save_dir {
+        Ok(save_dir) => UiState::load(save_dir.clone()).map_err(|e| (e, save_dir)),
+        Err(e) => Err((e, PathBuf::new())),
+    };
+    if let Ok(d) = &mut data {
+        d.config.safe_mode = options.safe_mode;
+    }
+    if let Some(command) = options.command {
+        return cli::run(command, &options, data.map_err(|(e, _)| e)?);
+    }
+
+    // The window paints its background from the launch theme, so the themed background goes here
+    let root = ui_builder()
+        .background(druid::theme::WINDOW_BACKGROUND_COLOR)
+        .env_scope(|env, data: &UiState| {
+            theme::apply(data.config.theme, env);
+            theme::apply_scale(data.config.ui_scale, env);
+        })
+        .controller(RawJsonSync).controller(UndoController).controller(ShortcutController).controller(FileWatcher::default()).controller(AutosaveController::default()).controller(ConfigController);
+    let mut main_window = WindowDesc::new(root).title(window_title).menu(app_menu).window_size(config.window_size);
+    if let Some(position) = config.window_position {
+        main_window = main_window.set_position(position);
+    }
+    let launcher = AppLauncher::with_window(main_window)
+        .delegate(Delegate)
+        .log_to_console();
+    tasks::init(launcher.get_external_handle());
+    let mut data = match data {
+        Ok(d) => d,
+        Err((e, save_dir)) => {
+            let mut data = UiState::from_error(format!("Error: {}", e));
+            data.access_problems = access::diagnose(&save_dir);
+            data.save_dir = save_dir;
+            data.config.safe_mode = options.safe_mode;
+            data
+        }
+    };
+    if !data.config.setup_done {
+        data.start_wizard();
+    } else if data.config.check_for_updates {
+        data.check_for_updates(true);
     }
+    launcher.launch(data)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_save::TestSave;
+
+    fn fixture_profile() -> Profile {
+        serde_json::from_str(include_str!("../tests/fixtures/save/Profile.json")).unwrap()
+    }
+
+    /// The first character in the fixture save.
+    fn fixture_character() -> Character {
+        let characters: Value = serde_json::from_str(include_str!("../tests/fixtures/save/Characters.json")).unwrap();
+        serde_json::from_str(characters["Characters.json"][0].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn flag_lens_adds_and_removes_flag() {
+        let mut flags: Vector<Flag> = vec![Flag(0), Flag(2)].into();
+        let lens = FlagLens { flag: Flag(3) };
+        assert!(!lens.get(&flags));
+
+        lens.put(&mut flags, true);
+        lens.put(&mut flags, true);
+        assert_eq!(flags, Vector::from(vec![Flag(0), Flag(2), Flag(3)]));
+        assert!(lens.get(&flags));
+
+        lens.put(&mut flags, false);
+        assert_eq!(flags, Vector::from(vec![Flag(0), Flag(2)]));
+    }
+
+    #[test]
+    fn built_in_presets_use_known_rows() {
+        for preset in presets::list_presets().unwrap().iter().filter(|x| x.is_built_in()) {
+            for (talent, rank) in preset.talents().unwrap() {
+                assert!(TALENTS.contains(talent.as_str()), "{} isn't a talent", talent);
+                assert!(rank <= TALENT_LEVELS[talent.as_str()], "{} only goes up to {}", talent, TALENT_LEVELS[talent.as_str()]);
+            }
+        }
+        for template in loadout_templates::list_templates().unwrap().iter().filter(|x| x.is_built_in()) {
+            for item in template.loadout().unwrap().items.iter() {
+                assert!(ITEMS.contains(&item.item_static_data.row_name.as_str()), "{} isn't an item", item.item_static_data.row_name);
+            }
+        }
+    }
+
+    #[test]
+    fn level_cap_fields_are_found_and_edited() {
+        let mut c = fixture_character();
+        c.extra.insert("MaxLevel".to_string(), Value::from(50));
+        c.extra.insert("BonusLevelCap".to_string(), Value::from(5));
+        let keys = level_cap_fields(&c).into_iter().map(|(key, _)| key).collect::<Vec<String>>();
+        assert_eq!(keys, vec!["BonusLevelCap", "MaxLevel"]);
+
+        ExtraNumberLens { key: "MaxLevel".to_string() }.put(&mut c, 60.0);
+        assert_eq!(serde_json::to_value(&c).unwrap()["MaxLevel"], Value::from(60));
+    }
+
+    #[test]
+    fn edits_are_undoable_and_logged() {
+        let mut state = UiState::from_error(String::new());
+        state.profile = fixture_profile();
+        state.apply_edit(Edit::RemoveMetaResource(0));
+        assert_eq!(state.profile.meta_resources.len(), 2);
+        assert!(!state.audit_pending.is_empty());
+
+        state.undo();
+        assert_eq!(state.profile, fixture_profile());
+        state.apply_edit(Edit::RemoveMetaResource(10));
+        assert!(!state.history.can_undo());
+    }
+
+    #[test]
+    fn saving_some_files_leaves_the_rest_changed() {
+        let mut state = UiState::from_error(String::new());
+        state.profile_file = PathBuf::from("Profile.json");
+        state.characters_file = PathBuf::from("Characters.json");
+        state.profile = fixture_profile();
+        state.mark_saved();
+        assert!(state.changed_files().is_empty());
+
+        state.profile.set_meta_resource(META_RESOURCE_CREDITS, 0.0);
+        assert_eq!(state.changed_files(), vec![state.profile_file.clone()]);
+        let saved = state.saved_after_writing(&state.snapshot(), &[state.characters_file.clone()]);
+        state.history.mark_saved_as(saved);
+        assert!(state.is_dirty());
+        let saved = state.saved_after_writing(&state.snapshot(), &[state.profile_file.clone()]);
+        state.history.mark_saved_as(saved);
+        assert!(!state.is_dirty());
+    }
+
+    #[test]
+    fn read_only_save_files_are_found_and_fixed() {
+        let root = std::env::temp_dir().join(format!("icarus-editor-read-only-test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let dir = root.join("OneDrive");
+        fs::create_dir_all(&dir).unwrap();
+        let profile = dir.join(icarus_save::PROFILE_FILE);
+        fs::write(&profile, "{}").unwrap();
+        let mut permissions = fs::metadata(&profile).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&profile, permissions).unwrap();
+
+        let problems = access::diagnose(&dir);
+        assert_eq!(problems, Vector::from(vec![AccessProblem::OneDrive, AccessProblem::ReadOnly(Vector::from(vec!["Profile.json".to_string()]))]));
+        access::make_writable(&dir).unwrap();
+        assert_eq!(access::diagnose(&dir), Vector::from(vec![AccessProblem::OneDrive]));
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn game_pass_saves_are_found_in_the_package() {
+        let local = std::env::temp_dir().join(format!("icarus-editor-game-pass-test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&local);
+        let package = local.join("Packages").join("Surviving.Icarus_abc123");
+        let save = save_locations::player_data_dir(&package.join("LocalCache").join("Local")).join("1234");
+        fs::create_dir_all(&save).unwrap();
+        fs::write(save.join(CHARACTERS_FILE), "{}").unwrap();
+        fs::create_dir_all(local.join("Packages").join("Other.App_xyz").join("LocalState")).unwrap();
+
+        let roots = save_locations::packaged_save_roots(&local);
+        assert_eq!(roots.len(), 1);
+        assert_eq!(UiState::find_player_dirs(&roots[0], 2), vec![save.clone()]);
+        assert_eq!(access::diagnose(&save).front(), Some(&AccessProblem::PackagedStorage));
+        let _ = fs::remove_dir_all(&local);
+    }
+
+    #[test]
+    fn characters_are_restored_from_a_backup_into_their_slot() {
+        let c = fixture_character();
+        let backup = Backup { name: "2024-01-01_00-00-00".to_string(), path: PathBuf::from("Backups") };
+        let other = Backup { name: "2024-01-02_00-00-00".to_string(), path: PathBuf::from("Backups2") };
+        let mut state = UiState::from_error(String::new());
+        state.save_dir = PathBuf::from("save");
+
+        let mut old = c.clone();
+        old.xp = Xp(0);
+        state.backup_view = Some(BackupView { backup: backup.clone(), profile: fixture_profile(), characters: vec![old].into() });
+        state.characters.push_back(c);
+        state.apply_edit(Edit::RestoreCharacterFromBackup { backup: other, backup_idx: 0 });
+        assert!(matches!(state.prompt, Some(Prompt::Message(_))));
+        assert_ne!(state.characters[0].xp, Xp(0));
+        state.prompt = None;
+        state.apply_edit(Edit::RestoreCharacterFromBackup { backup: backup.clone(), backup_idx: 0 });
+        assert!(state.prompt.is_none());
+        assert_eq!(state.characters.len(), 1);
+        assert_eq!(state.characters[0].xp, Xp(0));
+        assert!(state.characters[0].inventory_path.starts_with("save"));
+        state.undo();
+        assert_ne!(state.characters[0].xp, Xp(0));
+        assert!(state.restore_character_from_backup(&backup, 1).is_err());
+    }
+
+    #[test]
+    fn cli_refuses_amounts_that_are_not_numbers() {
+        let parse = |amount: &str| cli::parse(["set-credits".to_string(), amount.to_string()].into_iter()).map(|x| x.command);
+        assert!(matches!(parse("500"), Ok(Some(cli::CliCommand::SetCredits(_)))));
+        for amount in ["NaN", "inf", "-1", "lots"] {
+            assert!(parse(amount).is_err(), "{}", amount);
+        }
+    }
+
+    #[test]
+    fn unlock_lens_counts_any_rank_and_never_duplicates_rows() {
+        let lens = UnlockLens { row_name: "Workshop_Envirosuit" };
+        let mut rows = Vector::from(vec![Talent::new("Workshop_Envirosuit", 2)]);
+        assert!(lens.get(&rows));
+        lens.put(&mut rows, true);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].rank, Rank(2));
+
+        rows[0].rank = Rank(0);
+        assert!(!lens.get(&rows));
+        lens.put(&mut rows, true);
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].rank, Rank(1));
+
+        rows[0].rank = Rank(3);
+        lens.put(&mut rows, false);
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn cosmetics_are_named_from_the_data_file() {
+        let cosmetics = build_cosmetics("cosmetics.txt", "Customization_Voice,1,High\nCustomization_Voice,0,Low\nCustomization_Voice,x,Broken");
+        assert_eq!(cosmetics["Customization_Voice"], vec![(CosmeticIndex(0), "Low"), (CosmeticIndex(1), "High")]);
+        assert_eq!(cosmetic_name("Customization_EyeColor", CosmeticIndex(0)), "Brown");
+        assert_eq!(cosmetic_name("Customization_EyeColor", CosmeticIndex(40)), "Unknown (40)");
+    }
+
+    #[test]
+    fn excess_talent_points_come_off_the_newest_talents() {
+        let mut c = fixture_character();
+        c.xp = Xp(LEVEL_XP[2]);
+        c.talents = Vector::from(vec![Talent::new("Bow_Accuracy", 2), Talent::new("Bow_Arrow_Speed", 3), Talent::new("Workshop_Envirosuit", 1)]);
+        assert_eq!(c.talent_points_earned(), 2);
+        assert_eq!(c.trim_talent_points(), 3);
+        assert_eq!(c.talent_points_spent(), 2);
+        let names = c.talents.iter().map(|t| t.row_name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, ["Bow_Accuracy", "Workshop_Envirosuit"]);
+    }
+
+    #[test]
+    fn wizard_only_shows_for_new_configs() {
+        assert!(!Config::default().setup_done);
+        assert!(serde_json::from_str::<Config>("{\"backup_retention\": 5}").unwrap().setup_done);
+    }
+
+    #[test]
+    fn credit_lens_reads_and_writes_credits() {
+        let mut profile = fixture_profile();
+        let lens = MetaResourceLens { meta_row: META_RESOURCE_CREDITS };
+        assert_eq!(lens.get(&profile), 1250.0);
+
+        lens.put(&mut profile, 500000.0);
+        assert_eq!(profile.meta_resource(META_RESOURCE_CREDITS), 500000.0);
+        assert_eq!(profile.meta_resources.len(), 3);
+    }
+
+    #[test]
+    fn exotic_lens_only_adds_row_when_changed() {
+        let mut profile = fixture_profile();
+        let exotics = MetaResourceLens { meta_row: META_RESOURCE_EXOTICS };
+        assert_eq!(exotics.get(&profile), 40.0);
+        exotics.put(&mut profile, 45.0);
+        assert_eq!(profile.meta_resource(META_RESOURCE_EXOTICS), 45.0);
+
+        let red_exotics = MetaResourceLens { meta_row: META_RESOURCE_RED_EXOTICS };
+        assert_eq!(red_exotics.get(&profile), 0.0);
+        red_exotics.put(&mut profile, 0.0);
+        assert!(!profile.meta_resources.iter().any(|x| x.meta_row == META_RESOURCE_RED_EXOTICS));
+        red_exotics.put(&mut profile, 3.0);
+        assert_eq!(profile.meta_resource(META_RESOURCE_RED_EXOTICS), 3.0);
+    }
+
+    #[test]
+    fn ranks_csv_skips_header_and_blank_lines() {
+        let ranks = build_code::parse_ranks_csv("row_name,rank\r\n\"Bow_Accuracy\",3\r\n\r\nBlueprint_Bow\r\n").unwrap();
+        assert_eq!(ranks, vec![("Bow_Accuracy".to_string(), 3), ("Blueprint_Bow".to_string(), 1)]);
+        assert!(build_code::parse_ranks_csv("Bow_Accuracy,3\nBow_Damage,lots").is_err());
+    }
+
+    #[test]
+    fn item_property_lens_defaults_missing_property() {
+        let mut properties = Vector::new();
+        let lens = ItemPropertyLens { property: PROPERTY_DURABILITY, default: 0.0 };
+        assert_eq!(lens.get(&properties), 0.0);
+        lens.put(&mut properties, 0.0);
+        assert!(properties.is_empty());
+        lens.put(&mut properties, 900.0);
+        assert_eq!(properties[0].property_type, PROPERTY_DURABILITY);
+        assert_eq!(lens.get(&properties), 900.0);
+    }
+
+    #[test]
+    fn xp_formatter_only_accepts_what_the_game_does() {
+        use druid::text::Formatter;
+        assert_eq!(XpFormatter.value(" 1200 ").unwrap(), Xp(1200));
+        assert_eq!(XpFormatter.value("1e3").unwrap(), Xp(1000));
+        assert_eq!(XpFormatter.value(&xp_input::MAX_XP.to_string()).unwrap(), Xp(xp_input::MAX_XP));
+        for input in ["-1", "2147483648", "1e30", "NaN", "inf", "1.5", "", "lots"] {
+            assert!(XpFormatter.value(input).is_err(), "{} should be rejected", input);
+        }
+    }
+
+    #[test]
+    fn moved_characters_keep_their_old_files_until_the_new_ones_are_saved() {
+        let save = TestSave::new("moved_characters_keep_their_old_files");
+        let mut state = UiState::load_with(save.dir.clone(), false).unwrap();
+        state.config = Config::default();
+        state.move_character(0, Slot(3)).unwrap();
+        let (old_inventory, new_inventory) = (save.path("Inventory/InventoryID_0.json"), state.characters[0].inventory_path.clone());
+
+        let characters_only = vec![state.characters_file.clone()];
+        let mut job = state.clone();
+        job.write_save(&characters_only).unwrap();
+        state.finish_save(&job, &characters_only);
+        assert!(old_inventory.exists() && !new_inventory.exists());
+        assert_eq!(state.pending_deletions.len(), 2);
+
+        let rest = state.changed_files();
+        let mut job = state.clone();
+        job.write_save(&rest).unwrap();
+        state.finish_save(&job, &rest);
+        assert!(!old_inventory.exists() && new_inventory.exists());
+        assert!(state.pending_deletions.is_empty());
+    }
+
+    #[test]
+    fn the_oldest_kept_backup_can_be_restored() {
+        let save = TestSave::new("the_oldest_kept_backup_can_be_restored");
+        let mut state = UiState::load_with(save.dir.clone(), false).unwrap();
+        state.config = Config::default();
+        state.config.backup_retention = 3;
+        for _ in 0..3 {
+            state.back_up_now();
+        }
+        let oldest = state.backups.last().cloned().unwrap();
+        assert_eq!(state.backups.len(), 3);
+
+        // Written without a backup, which would push the oldest one out
+        state.characters[0].xp = Xp(0);
+        state.store().save().unwrap();
+        state.backup_view = Some(BackupView { backup: oldest.clone(), profile: fixture_profile(), characters: Vector::new() });
+        state.restore_backup(&oldest);
+        assert!(state.prompt.is_none());
+        // The backup window it was restored from keeps showing it
+        assert!(state.backup_view.is_some());
+        assert!(oldest.path.exists());
+        assert_ne!(state.characters[0].xp, Xp(0));
+    }
+
+    #[test]
+    fn characters_with_unreadable_files_are_not_duplicated() {
+        let save = TestSave::new("characters_with_unreadable_files_are_not_duplicated");
+        let mut state = UiState::load_with(save.dir.clone(), false).unwrap();
+        // The second fixture character's inventory has a damaged item in it
+        assert!(state.duplicate_character(1).is_err());
+        state.duplicate_character(0).unwrap();
+        assert_eq!(state.characters.len(), 3);
+        assert!(state.characters[2].inventory_loaded && state.characters[2].loadout_loaded);
+    }
+
+    #[test]
+    fn gaps_in_the_level_table_are_reported() {
+        assert_eq!(build_level_table("gappy_levels.txt", "1,0\n2,100\n5,400\n7,600\n"), vec![0, 100, 400, 600]);
+        let problems = data_file::problems();
+        assert!(problems.contains(&"gappy_levels.txt: levels 3 to 4 are missing".to_string()));
+        assert!(problems.contains(&"gappy_levels.txt: level 6 is missing".to_string()));
+    }
+
+    #[test]
+    fn prompt_edits_ignore_characters_that_are_gone() {
+        let mut state = UiState::from_error(String::new());
+        let mut c = fixture_character();
+        c.is_dead = true;
+        c.xp = Xp(1000);
+        c.xp_debt = Xp(300);
+        state.characters.push_back(c);
+
+        state.apply_edit(Edit::Revive { idx: 1, debt: ReviveDebt::TakeFromXp });
+        state.apply_edit(Edit::DeleteCharacter { idx: 1, remove_files: true });
+        assert!(state.characters[0].is_dead && state.pending_deletions.is_empty());
+        state.apply_edit(Edit::Revive { idx: 0, debt: ReviveDebt::TakeFromXp });
+        assert!(!state.characters[0].is_dead);
+        assert_eq!((state.characters[0].xp, state.characters[0].xp_debt), (Xp(700), Xp(0)));
+    }
+
+    #[test]
+    fn restoring_a_backup_asks_first() {
+        let mut state = UiState::from_error(String::new());
+        state.config.confirm_bulk_changes = true;
+        let backup = Backup { name: "2026-01-01_00-00-00".to_string(), path: PathBuf::from("backup") };
+        state.backups.push_back(backup.clone());
+
+        state.request_restore_backup(&backup);
+        assert!(state.prompt == Some(Prompt::Confirm(BulkAction::RestoreBackup(0))));
+        state.prompt = None;
+        state.request_restore_backup(&Backup { name: "gone".to_string(), path: PathBuf::from("gone") });
+        assert!(state.prompt.is_none());
+    }
+}
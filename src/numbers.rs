@@ -0,0 +1,72 @@
+//! The whole numbers in the save files. The game writes them as plain JSON numbers, and some older
+//! saves (and older versions of this editor) wrote them as floats such as `3.0`, so each type here
+//! reads any JSON number, rounding away a fractional part, and always writes an integer back.
+
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Number;
+
+macro_rules! whole_number {
+    ($(#[$meta:meta])* $name:ident($inner:ty)) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Serialize)]
+        #[cfg_attr(feature = "gui", derive(druid::Data))]
+        #[serde(transparent)]
+        pub struct $name(pub $inner);
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let number = Number::deserialize(deserializer)?;
+                let value = match (number.as_i64(), number.as_f64()) {
+                    (Some(value), _) => <$inner>::try_from(value).ok(),
+                    (None, Some(value)) if value.round() >= <$inner>::MIN as f64 && value.round() <= <$inner>::MAX as f64 => Some(value.round() as $inner),
+                    _ => None,
+                };
+                value.map($name).ok_or_else(|| D::Error::custom(format!("{} is out of range for {}", number, stringify!($name))))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = ParseIntError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.trim().parse().map($name)
+            }
+        }
+    };
+}
+
+whole_number!(
+    /// A character's `ChrSlot`, which also names their inventory and loadout files.
+    Slot(u32)
+);
+
+whole_number!(
+    /// An entry in `UnlockedFlags`.
+    Flag(u32)
+);
+
+whole_number!(
+    /// An amount of XP or XP debt.
+    Xp(i64)
+);
+
+whole_number!(
+    /// How many points are in a talent. Blueprints, workshop items and prospects are unlocked at 1.
+    Rank(i64)
+);
+
+whole_number!(
+    /// Which of the choices in the character creator a cosmetic option is set to.
+    CosmeticIndex(u32)
+);
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use druid::Data;
+use druid::im::vector::Vector;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::files;
+use crate::snapshots::RESERVED_CHARACTERS;
+
+const PRESET_DIR: &str = "presets";
+
+/// Talent presets that come with the editor, as `(name, [(talent row name, rank)])`.
+const BUILT_IN_PRESETS: [(&str, &[(&str, i64)]); 2] = [
+    ("Solo Hunter", &[
+        ("Solo_Bow_Damage", 2), ("Solo_Hunting_Resources", 1), ("Solo_Health", 2), ("Solo_Stamina", 1),
+        ("Bow_Accuracy", 2), ("Bow_Projectile_Damage1", 3), ("Bow_Arrow_Speed", 3), ("Knife_Instant_Skin", 3),
+        ("Gathering_Leather_Yield", 2), ("Gathering_Meat_Yield", 1), ("Stalking_Sneak_Speed", 3), ("Stalking_Detection_Range", 3),
+    ]),
+    ("Support Farmer", &[
+        ("Produce_Crops_Yield", 2), ("Produce_CropPlot_Crop_Yield", 2), ("Produce_CropPlot_Growth_Speed", 2),
+        ("Produce_Food_Buff_Duration", 3), ("Produce_Food_Decay", 3), ("Resources_Food_Benefit", 2), ("Resources_Crop_Grow_Speed", 2),
+        ("Exploration_Healing_Revive", 1), ("Exploration_Revive_Regeneration", 1), ("Exploration_Party_Xp", 1), ("Repair_Hammer_Speed", 3),
+    ]),
+];
+
+/// A preset's file: its talents by row name, with their ranks. It's named after the preset.
+#[derive(Serialize, Deserialize, Default)]
+struct PresetFile {
+    #[serde(rename = "Talents")]
+    talents: BTreeMap<String, i64>,
+}
+
+/// A named set of talent ranks that can be given to any character. Built in presets have no file.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct TalentPreset {
+    pub name: String,
+    #[data(eq)]
+    pub path: Option<PathBuf>,
+}
+
+impl TalentPreset {
+    pub fn is_built_in(&self) -> bool {
+        self.path.is_none()
+    }
+
+    pub fn talents(&self) -> Result<Vec<(String, i64)>, Box<dyn Error>> {
+        match &self.path {
+            Some(path) => Ok(files::read_json::<PresetFile>(path)?.talents.into_iter().collect()),
+            None => BUILT_IN_PRESETS.iter()
+                .find(|(name, _)| *name == self.name)
+                .map(|(_, talents)| talents.iter().map(|(row_name, rank)| (row_name.to_string(), *rank)).collect())
+                .ok_or_else(|| format!("there's no built in preset called [{}]", self.name).into()),
+        }
+    }
+}
+
+fn preset_dir() -> Option<PathBuf> {
+    config::config_dir().map(|x| x.join(PRESET_DIR))
+}
+
+/// Saves `talents` as a preset called `name`, replacing any of the user's own presets with that
+/// name. Built in presets can't be replaced.
+pub fn save_preset(name: &str, talents: &[(String, i64)]) -> Result<TalentPreset, Box<dyn Error>> {
+    let name = name.trim();
+    if name.is_empty() {
+        Err("the preset needs a name")?
+    }
+    if let Some(c) = name.chars().find(|c| RESERVED_CHARACTERS.contains(c)) {
+        Err(format!("preset names can't contain [{}]", c))?
+    }
+    if BUILT_IN_PRESETS.iter().any(|(x, _)| x.eq_ignore_ascii_case(name)) {
+        Err(format!("[{}] is the name of a built in preset", name))?
+    }
+
+    let dir = preset_dir().ok_or("Unable to find config directory")?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.json", name));
+    files::write_json(&path, &PresetFile { talents: talents.iter().cloned().collect() })?;
+
+    Ok(TalentPreset { name: name.to_string(), path: Some(path) })
+}
+
+/// The built in presets, followed by the user's own by name.
+pub fn list_presets() -> Result<Vector<TalentPreset>, Box<dyn Error>> {
+    let mut presets = BUILT_IN_PRESETS.iter()
+        .map(|(name, _)| TalentPreset { name: name.to_string(), path: None })
+        .collect::<Vector<TalentPreset>>();
+    let dir = match preset_dir() {
+        Some(dir) if dir.exists() => dir,
+        _ => return Ok(presets),
+    };
+
+    let mut saved = Vec::new();
+    for entry in dir.read_dir()? {
+        let path = entry?.path();
+        if path.extension().map(|x| x == "json").unwrap_or(false) {
+            let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            saved.push(TalentPreset { name, path: Some(path) });
+        }
+    }
+    saved.sort_by(|a, b| a.name.cmp(&b.name));
+    presets.extend(saved);
+
+    Ok(presets)
+}
+
+pub fn delete_preset(preset: &TalentPreset) -> Result<(), Box<dyn Error>> {
+    let path = preset.path.as_ref().ok_or("built in presets can't be deleted")?;
+    fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use im::Vector;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::files;
+use crate::numbers::Slot;
+
+pub const PROSPECTS_DIR: &str = "Prospects";
+
+/// A member's `Status` once they've left a prospect without returning to the station. This and
+/// `ACTIVE_STATUS` are the values as the game writes them, as far as is known.
+pub const ABANDONED_STATUS: &str = "Abandoned";
+pub const ACTIVE_STATUS: &str = "Active";
+
+/// A prospect (world) save from the `Prospects/` folder. Only the `ProspectInfo` header is
+/// editable - the world itself is kept as-is.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct ProspectSave {
+    #[serde(rename = "ProspectInfo")]
+    pub info: ProspectInfo,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct ProspectInfo {
+    #[serde(rename = "ProspectID", default)]
+    pub prospect_id: String,
+    #[serde(rename = "LobbyName", default)]
+    pub lobby_name: String,
+    #[serde(rename = "ProspectState", default)]
+    pub prospect_state: String,
+    #[serde(rename = "Difficulty", default)]
+    pub difficulty: String,
+    #[serde(rename = "ElapsedTime", default)]
+    pub elapsed_time: f64,
+    #[serde(rename = "NoRespawns", default)]
+    pub no_respawns: bool,
+    #[serde(rename = "Insurance", default)]
+    pub insurance: bool,
+    /// Which prospect this is in the game's data, e.g. `Tier1_Forest_Recon_0`
+    #[serde(rename = "ProspectDTKey", default)]
+    pub prospect_dt_key: String,
+    /// Everyone who has played in the prospect
+    #[serde(rename = "AssociatedMembers", default, skip_serializing_if = "Vector::is_empty")]
+    pub members: Vector<ProspectMember>,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ProspectInfo {
+    /// Whether this is an open world session rather than a mission prospect. Open world sessions
+    /// go on indefinitely, so they're the ones worth coming back to.
+    pub fn is_open_world(&self) -> bool {
+        self.prospect_dt_key.to_ascii_lowercase().contains("openworld")
+    }
+
+    pub fn abandoned_members(&self) -> usize {
+        self.members.iter().filter(|x| x.status.eq_ignore_ascii_case(ABANDONED_STATUS)).count()
+    }
+
+    /// Marks every member who abandoned the prospect as active in it again, so they can rejoin.
+    /// Returns how many there were.
+    pub fn reset_abandoned(&mut self) -> usize {
+        let mut reset = 0;
+        for member in self.members.iter_mut().filter(|x| x.status.eq_ignore_ascii_case(ABANDONED_STATUS)) {
+            member.status = ACTIVE_STATUS.to_string();
+            reset += 1;
+        }
+
+        reset
+    }
+}
+
+/// A player's entry in a prospect's `AssociatedMembers`.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct ProspectMember {
+    #[serde(rename = "AccountName", default)]
+    pub account_name: String,
+    #[serde(rename = "CharacterName", default)]
+    pub character_name: String,
+    #[serde(rename = "UserID", default)]
+    pub user_id: String,
+    #[serde(rename = "ChrSlot", default)]
+    pub character_slot: Slot,
+    #[serde(rename = "Status", default)]
+    pub status: String,
+    #[cfg_attr(feature = "gui", data(eq))]
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "gui", derive(druid::Data, druid::Lens))]
+pub struct Prospect {
+    pub file_name: String,
+    #[cfg_attr(feature = "gui", data(eq))]
+    pub path: PathBuf,
+    pub data: ProspectSave,
+}
+
+impl Prospect {
+    pub fn load(path: &Path) -> Result<Prospect, Box<dyn Error>> {
+        Ok(Prospect {
+            file_name: path.file_stem().map(|x| x.to_string_lossy().to_string()).unwrap_or_default(),
+            path: path.to_path_buf(),
+            data: files::read_json(path)?,
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        files::write_json(&self.path, &self.data)
+    }
+}
+
+/// Loads every prospect save in `save_dir/Prospects`. A missing folder just means no prospects, and
+/// files that can't be read are skipped, with a warning added to `warnings`.
+pub fn load_prospects(save_dir: &Path, warnings: &mut Vec<String>) -> Result<Vector<Prospect>, Box<dyn Error>> {
+    let dir = save_dir.join(PROSPECTS_DIR);
+    let mut prospects = Vec::new();
+    if dir.exists() {
+        for entry in dir.read_dir()? {
+            let path = entry?.path();
+            if path.extension().map(|x| x != "json").unwrap_or(true) {
+                continue;
+            }
+            match Prospect::load(&path) {
+                Ok(prospect) => prospects.push(prospect),
+                Err(e) => warnings.push(format!("Unable to read prospect [{}]: {}", path.to_string_lossy(), e)),
+            }
+        }
+    }
+    prospects.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+
+    Ok(Vector::from(prospects))
+}
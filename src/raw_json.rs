@@ -0,0 +1,154 @@
+use std::error::Error;
+
+use druid::widget::Controller;
+use druid::{Data, Env, Event, EventCtx, Widget};
+use serde_json::Value;
+
+use crate::{Character, Profile, UiState};
+
+/// What the raw JSON editor is showing.
+#[derive(Clone, Copy, PartialEq, Debug, Data)]
+pub enum RawTarget {
+    Profile,
+    Character(usize),
+}
+
+impl UiState {
+    /// The JSON the game would get for `target` if the save was written now, laid out for editing.
+    fn raw_json_of(&self, target: RawTarget) -> Result<String, Box<dyn Error>> {
+        let text = match target {
+            RawTarget::Profile => serde_json::to_string_pretty(&self.profile)?,
+            RawTarget::Character(idx) => serde_json::to_string_pretty(&self.characters[idx])?,
+        };
+        Ok(text)
+    }
+
+    /// Opens the raw JSON editor on `target`, replacing anything typed there.
+    pub fn edit_raw_json(&mut self, target: RawTarget) -> Result<(), Box<dyn Error>> {
+        self.raw_json = self.raw_json_of(target)?;
+        self.raw_json_target = Some(target);
+        self.raw_json_error = None;
+        self.raw_json_conflict = false;
+        Ok(())
+    }
+
+    pub fn close_raw_json(&mut self) {
+        self.raw_json_target = None;
+        self.raw_json_error = None;
+        self.raw_json_conflict = false;
+        self.raw_json.clear();
+    }
+
+    /// The raw JSON editor's target, or `None` if it's closed or its character has been deleted.
+    fn raw_json_target(&mut self) -> Option<RawTarget> {
+        match self.raw_json_target {
+            Some(RawTarget::Character(idx)) if idx >= self.characters.len() => {
+                self.close_raw_json();
+                None
+            }
+            target => target,
+        }
+    }
+
+    /// Whether the text says the same as the target does now, ignoring layout and how numbers are
+    /// written. Fails if the text isn't a valid profile or character.
+    fn raw_json_matches(&self, target: RawTarget) -> Result<bool, serde_json::Error> {
+        let (text, current) = match target {
+            RawTarget::Profile => (serde_json::to_value(serde_json::from_str::<Profile>(&self.raw_json)?)?, serde_json::to_value(&self.profile)?),
+            RawTarget::Character(idx) => (serde_json::to_value(serde_json::from_str::<Character>(&self.raw_json)?)?, serde_json::to_value(&self.characters[idx])?),
+        };
+        Ok(Value::eq(&text, &current))
+    }
+
+    /// Replaces the profile or character with what's in the raw JSON editor. A character keeps its
+    /// inventory and loadout; changing its slot moves them to the new slot's files.
+    fn apply_raw_json(&mut self, target: RawTarget) -> Result<(), serde_json::Error> {
+        match target {
+            RawTarget::Profile => self.profile = serde_json::from_str(&self.raw_json)?,
+            RawTarget::Character(idx) => {
+                let mut character: Character = serde_json::from_str(&self.raw_json)?;
+                let old = &self.characters[idx];
+                if character.character_slot == old.character_slot {
+                    character.inventory_path = old.inventory_path.clone();
+                    character.loadout_path = old.loadout_path.clone();
+                } else {
+                    character.set_file_paths(&self.save_dir);
+                }
+                character.inventory = old.inventory.clone();
+                character.inventory_loaded = old.inventory_loaded;
+                character.loadout = old.loadout.clone();
+                character.loadout_loaded = old.loadout_loaded;
+                self.characters[idx] = character;
+            }
+        }
+        Ok(())
+    }
+
+    /// After the text has been edited: puts it in place straight away if it's valid, unless the
+    /// other tabs have changed the same thing since the text last was.
+    fn sync_from_raw_json(&mut self) {
+        let target = match self.raw_json_target() {
+            Some(target) => target,
+            None => return,
+        };
+        match self.raw_json_matches(target) {
+            Err(e) => self.raw_json_error = Some(e.to_string()),
+            Ok(_) if self.raw_json_conflict => self.raw_json_error = None,
+            Ok(true) => self.raw_json_error = None,
+            Ok(false) => {
+                self.raw_json_error = self.apply_raw_json(target).err().map(|e| e.to_string());
+            }
+        }
+    }
+
+    /// After an edit anywhere else: shows it in the text, unless the text can't be read, in which
+    /// case the two have conflicting changes for the user to choose between.
+    pub fn sync_to_raw_json(&mut self) {
+        let target = match self.raw_json_target() {
+            Some(target) => target,
+            None => return,
+        };
+        if self.raw_json_conflict {
+            return;
+        }
+        match self.raw_json_matches(target) {
+            Ok(true) => {}
+            Ok(false) => {
+                if let Ok(text) = self.raw_json_of(target) {
+                    self.raw_json = text;
+                }
+            }
+            Err(_) => self.raw_json_conflict = true,
+        }
+    }
+
+    /// Settles a conflict in favour of the text, replacing the other tabs' changes.
+    pub fn keep_raw_json(&mut self) {
+        if let Some(target) = self.raw_json_target() {
+            match self.apply_raw_json(target) {
+                Ok(()) => self.raw_json_conflict = false,
+                Err(e) => self.raw_json_error = Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Keeps the raw JSON editor and the other tabs in step: whichever one an event changes, the other
+/// is updated to match. Meant to wrap the root widget, inside the `UndoController` so the changes it
+/// makes can be undone.
+pub struct RawJsonSync;
+
+impl<W: Widget<UiState>> Controller<UiState, W> for RawJsonSync {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        let (text, profile, characters) = (data.raw_json.clone(), data.profile.clone(), data.characters.clone());
+        child.event(ctx, event, data, env);
+        if data.raw_json_target.is_none() {
+            return;
+        }
+        if !data.raw_json.same(&text) {
+            data.sync_from_raw_json();
+        } else if !data.profile.same(&profile) || !data.characters.same(&characters) {
+            data.sync_to_raw_json();
+        }
+    }
+}
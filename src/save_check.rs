@@ -0,0 +1,262 @@
+use std::error::Error;
+
+use druid::Data;
+use druid::im::vector::Vector;
+
+use crate::inventory::{Inventory, PROPERTY_DURABILITY, PROPERTY_STACK};
+use crate::loadout::Loadout;
+use crate::{validation, Character, Profile, UiState, BLUEPRINTS, PROSPECTS, TALENTS, WORKSHOP_ITEMS};
+
+/// Something "Validate Save" found wrong with the save, and the fix for it.
+#[derive(Clone, Data, PartialEq, Debug)]
+pub struct Issue {
+    pub description: String,
+    pub repair: Repair,
+}
+
+/// A one-click fix for an issue. Repairs only change the editor's copy of the save, so like any other
+/// edit they can be undone, and are written out on the next save.
+#[derive(Clone, Data, PartialEq, Debug)]
+pub enum Repair {
+    /// Replaces a character's inventory with whatever can still be read from its file
+    ReplaceInventory(usize),
+    /// Gives a character an empty loadout
+    ReplaceLoadout(usize),
+    /// Moves a character whose slot is taken to the next free slot
+    MoveToFreeSlot(usize),
+    RemoveCharacterTalent(usize, String),
+    RemoveProfileTalent(String),
+    /// Sets a character's negative and NaN numbers to 0
+    FixCharacterNumbers(usize),
+    /// Sets the profile's negative and NaN numbers to 0
+    FixProfileNumbers,
+}
+
+/// What's wrong with a number that should be a count, if anything.
+fn number_problem(value: f64) -> Option<String> {
+    if !value.is_finite() {
+        Some("is not a number".to_string())
+    } else if value < 0.0 {
+        Some(format!("is negative ({})", value))
+    } else {
+        None
+    }
+}
+
+/// What's wrong with a whole number that should be a count, if anything. Slots, flags and cosmetic
+/// options can't be negative at all, so only XP and ranks need checking.
+fn whole_number_problem(value: i64) -> Option<String> {
+    if value < 0 {
+        Some(format!("is negative ({})", value))
+    } else {
+        None
+    }
+}
+
+/// A character's XP and talent ranks, by name.
+fn character_whole_numbers(c: &mut Character) -> Vec<(String, &mut i64)> {
+    let mut numbers = vec![
+        ("XP".to_string(), &mut c.xp.0),
+        ("XP debt".to_string(), &mut c.xp_debt.0),
+    ];
+    for talent in c.talents.iter_mut() {
+        numbers.push((format!("{} rank", talent.row_name), &mut talent.rank.0));
+    }
+
+    numbers
+}
+
+/// Every other number in a character that's a count, by name.
+fn character_numbers(c: &mut Character) -> Vec<(String, &mut f64)> {
+    let mut numbers = Vec::new();
+    for resource in c.meta_resources.iter_mut() {
+        numbers.push((format!("{} count", resource.meta_row), &mut resource.count));
+    }
+    for item in c.inventory.delta.iter_mut() {
+        let row_name = item.item_static_data.row_name.clone();
+        for property in item.item_dynamic_data.iter_mut().filter(|x| x.property_type == PROPERTY_STACK || x.property_type == PROPERTY_DURABILITY) {
+            numbers.push((format!("inventory {} {}", row_name, property.property_type), &mut property.value));
+        }
+    }
+
+    numbers
+}
+
+fn profile_numbers(profile: &mut Profile) -> Vec<(String, &mut f64)> {
+    let mut numbers = Vec::new();
+    for resource in profile.meta_resources.iter_mut() {
+        numbers.push((format!("{} count", resource.meta_row), &mut resource.count));
+    }
+    for time in profile.mission_best_times.iter_mut() {
+        numbers.push((format!("{} best time", time.mission_row), &mut time.best_time));
+    }
+
+    numbers
+}
+
+impl UiState {
+    /// Cross-checks the characters, their files and the profile against each other and the editor's
+    /// data files.
+    fn find_issues(&self) -> Vector<Issue> {
+        let mut issues = Vector::new();
+        for (idx, c) in self.characters.iter().enumerate() {
+            let who = format!("\"{}\" (slot {})", c.character_name, c.character_slot);
+            if !c.inventory_loaded {
+                let problem = if c.inventory_path.exists() { "can't be read" } else { "doesn't exist" };
+                issues.push_back(Issue {
+                    description: format!("{}: inventory [{}] {}", who, c.inventory_path.to_string_lossy(), problem),
+                    repair: Repair::ReplaceInventory(idx),
+                });
+            }
+            if !c.loadout_loaded {
+                let problem = if c.loadout_path.exists() { "can't be read" } else { "doesn't exist" };
+                issues.push_back(Issue {
+                    description: format!("{}: loadout [{}] {}", who, c.loadout_path.to_string_lossy(), problem),
+                    repair: Repair::ReplaceLoadout(idx),
+                });
+            }
+
+            let shared = self.characters.iter().take(idx).any(|x| x.character_slot == c.character_slot)
+                || self.skipped_characters.iter().any(|(_, x)| validation::character_slot(x) == Some(c.character_slot));
+            if shared {
+                issues.push_back(Issue { description: format!("{}: another character has the same slot", who), repair: Repair::MoveToFreeSlot(idx) });
+            }
+
+            for talent in c.talents.iter().filter(|t| !TALENTS.contains(t.row_name.as_str()) && !BLUEPRINTS.contains(t.row_name.as_str())) {
+                issues.push_back(Issue {
+                    description: format!("{}: {} is not a known talent or blueprint", who, talent.row_name),
+                    repair: Repair::RemoveCharacterTalent(idx, talent.row_name.clone()),
+                });
+            }
+
+            let mut numbers = c.clone();
+            let whole_numbers = character_whole_numbers(&mut numbers).into_iter().map(|(name, value)| (name, whole_number_problem(*value)));
+            let mut numbers = c.clone();
+            let other_numbers = character_numbers(&mut numbers).into_iter().map(|(name, value)| (name, number_problem(*value)));
+            for (name, problem) in whole_numbers.chain(other_numbers) {
+                if let Some(problem) = problem {
+                    issues.push_back(Issue { description: format!("{}: {} {}", who, name, problem), repair: Repair::FixCharacterNumbers(idx) });
+                }
+            }
+        }
+
+        for talent in self.profile.talents.iter().filter(|t| !PROSPECTS.contains(t.row_name.as_str()) && !WORKSHOP_ITEMS.contains(t.row_name.as_str())) {
+            issues.push_back(Issue {
+                description: format!("Profile: {} is not a known prospect or workshop item", talent.row_name),
+                repair: Repair::RemoveProfileTalent(talent.row_name.clone()),
+            });
+        }
+        for talent in self.profile.talents.iter() {
+            if let Some(problem) = whole_number_problem(talent.rank.0) {
+                issues.push_back(Issue { description: format!("Profile: {} rank {}", talent.row_name, problem), repair: Repair::FixProfileNumbers });
+            }
+        }
+        let mut profile = self.profile.clone();
+        for (name, value) in profile_numbers(&mut profile) {
+            if let Some(problem) = number_problem(*value) {
+                issues.push_back(Issue { description: format!("Profile: {} {}", name, problem), repair: Repair::FixProfileNumbers });
+            }
+        }
+
+        issues
+    }
+
+    pub fn validate_save(&mut self) {
+        let issues = self.find_issues();
+        self.status = match issues.len() {
+            0 => "No problems found".to_string(),
+            1 => "Found 1 problem".to_string(),
+            n => format!("Found {} problems", n),
+        };
+        self.validation_issues = Some(issues);
+    }
+
+    fn repair(&mut self, repair: &Repair) -> Result<(), Box<dyn Error>> {
+        match repair {
+            Repair::ReplaceInventory(idx) => {
+                let c = &mut self.characters[*idx];
+                c.inventory = Inventory::repair(&c.inventory_path);
+                c.inventory_loaded = true;
+            }
+            Repair::ReplaceLoadout(idx) => {
+                let c = &mut self.characters[*idx];
+                c.loadout = Loadout { valid: true, ..Default::default() };
+                c.loadout_loaded = true;
+            }
+            Repair::MoveToFreeSlot(idx) => {
+                let slot = self.next_free_slot();
+                self.move_character(*idx, slot)?;
+            }
+            Repair::RemoveCharacterTalent(idx, row_name) => self.characters[*idx].talents.retain(|t| &t.row_name != row_name),
+            Repair::RemoveProfileTalent(row_name) => self.profile.talents.retain(|t| &t.row_name != row_name),
+            Repair::FixCharacterNumbers(idx) => {
+                let c = &mut self.characters[*idx];
+                for (_, value) in character_whole_numbers(c) {
+                    if whole_number_problem(*value).is_some() {
+                        *value = 0;
+                    }
+                }
+                for (_, value) in character_numbers(c) {
+                    if number_problem(*value).is_some() {
+                        *value = 0.0;
+                    }
+                }
+            }
+            Repair::FixProfileNumbers => {
+                for talent in self.profile.talents.iter_mut().filter(|t| whole_number_problem(t.rank.0).is_some()) {
+                    talent.rank.0 = 0;
+                }
+                for (_, value) in profile_numbers(&mut self.profile) {
+                    if number_problem(*value).is_some() {
+                        *value = 0.0;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fixes one issue from the last validation. The save is checked again first, as edits since
+    /// then may have fixed it already or moved the characters it refers to.
+    pub fn repair_issue(&mut self, issue: &Issue) -> Result<(), Box<dyn Error>> {
+        if !self.find_issues().contains(issue) {
+            self.validate_save();
+            Err("the save has changed since it was validated - check the new list")?
+        }
+        self.repair(&issue.repair)?;
+        self.validate_save();
+        self.status = format!("Fixed {}", issue.description);
+
+        Ok(())
+    }
+
+    /// Starts a character's missing or unreadable inventory and loadout afresh, the same as fixing
+    /// those issues in "Validate Save" does.
+    pub fn fix_character_files(&mut self, idx: usize) -> Result<(), Box<dyn Error>> {
+        let c = &self.characters[idx];
+        let repairs = [(!c.inventory_loaded, Repair::ReplaceInventory(idx)), (!c.loadout_loaded, Repair::ReplaceLoadout(idx))];
+        for (_, repair) in repairs.iter().filter(|(needed, _)| *needed) {
+            self.repair(repair)?;
+        }
+        self.status = format!("Fixed the files of \"{}\" - they'll be written on the next save", self.characters[idx].character_name);
+
+        Ok(())
+    }
+
+    pub fn repair_all_issues(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut repairs = Vec::new();
+        for issue in self.find_issues() {
+            if !repairs.contains(&issue.repair) {
+                repairs.push(issue.repair);
+            }
+        }
+        for repair in &repairs {
+            self.repair(repair)?;
+        }
+        self.validate_save();
+        self.status = format!("Made {} repairs", repairs.len());
+
+        Ok(())
+    }
+}
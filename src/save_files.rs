@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use druid::{Data, Lens};
+use druid::im::vector::Vector;
+use icarus_save::Character;
+
+use crate::history::EditSnapshot;
+use crate::{Prompt, UiState};
+
+/// A file the save dialog can write, and whether it's been picked.
+#[derive(Clone, PartialEq, Debug, Data, Lens)]
+pub struct SaveChoice {
+    #[data(eq)]
+    pub path: PathBuf,
+    pub label: String,
+    pub chosen: bool,
+}
+
+/// A character as `Characters.json` has it, without the inventory and loadout kept in files of
+/// their own.
+fn without_items(c: &Character) -> Character {
+    let mut c = c.clone();
+    c.inventory = Default::default();
+    c.inventory_loaded = false;
+    c.loadout = Default::default();
+    c.loadout_loaded = false;
+    c
+}
+
+impl UiState {
+    /// Every file a save can write, with a name to show for it. Inventories and loadouts that
+    /// couldn't be read are never written, so aren't included.
+    fn writable_files(&self) -> Vec<(PathBuf, String)> {
+        let mut files = vec![
+            (self.profile_file.clone(), "Profile.json".to_string()),
+            (self.characters_file.clone(), "Characters.json".to_string()),
+        ];
+        for c in self.characters.iter() {
+            if c.inventory_loaded {
+                files.push((c.inventory_path.clone(), format!("{}'s inventory (slot {})", c.character_name, c.character_slot)));
+            }
+            if c.loadout_loaded {
+                files.push((c.loadout_path.clone(), format!("{}'s loadout (slot {})", c.character_name, c.character_slot)));
+            }
+        }
+        files.extend(self.prospects.iter().map(|p| (p.path.clone(), format!("Prospect {}", p.file_name))));
+
+        files
+    }
+
+    /// The files whose contents in the editor differ from what's on disk.
+    pub fn changed_files(&self) -> Vec<PathBuf> {
+        let saved = match self.history.saved() {
+            Some(saved) => saved,
+            None => return Vec::new(),
+        };
+
+        let mut files = Vec::new();
+        if !saved.profile.same(&self.profile) {
+            files.push(self.profile_file.clone());
+        }
+        if saved.characters.len() != self.characters.len()
+            || saved.characters.iter().zip(self.characters.iter()).any(|(a, b)| !without_items(a).same(&without_items(b))) {
+            files.push(self.characters_file.clone());
+        }
+        for c in self.characters.iter() {
+            let old = saved.characters.iter().find(|x| x.inventory_path == c.inventory_path);
+            if c.inventory_loaded && !old.map(|x| x.inventory_loaded && x.inventory.same(&c.inventory)).unwrap_or(false) {
+                files.push(c.inventory_path.clone());
+            }
+            let old = saved.characters.iter().find(|x| x.loadout_path == c.loadout_path);
+            if c.loadout_loaded && !old.map(|x| x.loadout_loaded && x.loadout.same(&c.loadout)).unwrap_or(false) {
+                files.push(c.loadout_path.clone());
+            }
+        }
+        for p in self.prospects.iter() {
+            if !saved.prospects.iter().any(|x| x.path == p.path && x.same(p)) {
+                files.push(p.path.clone());
+            }
+        }
+
+        files
+    }
+
+    /// Opens the save dialog, with the files that have changed picked.
+    pub fn open_save_dialog(&mut self) {
+        let changed = self.changed_files();
+        self.save_choices = self.writable_files().into_iter()
+            .map(|(path, label)| SaveChoice { chosen: changed.contains(&path), path, label })
+            .collect();
+        self.prompt = Some(Prompt::Save);
+    }
+
+    pub fn chosen_files(&self) -> Vec<PathBuf> {
+        self.save_choices.iter().filter(|x| x.chosen).map(|x| x.path.clone()).collect()
+    }
+
+    pub fn choose_all_files(&mut self, chosen: bool) {
+        self.save_choices = self.save_choices.iter().map(|x| SaveChoice { chosen, ..x.clone() }).collect::<Vector<SaveChoice>>();
+    }
+
+    /// What's on disk once `files` have been written from `written`: those parts of `written`, and
+    /// the rest as it was.
+    pub fn saved_after_writing(&self, written: &EditSnapshot, files: &[PathBuf]) -> EditSnapshot {
+        let saved = match self.history.saved() {
+            Some(saved) => saved,
+            None => return written.clone(),
+        };
+
+        let profile = if files.contains(&self.profile_file) { &written.profile } else { &saved.profile };
+        let characters = if files.contains(&self.characters_file) { &written.characters } else { &saved.characters };
+        let characters = characters.iter().map(|c| {
+            let mut c = c.clone();
+            let from = if files.contains(&c.inventory_path) { written } else { saved };
+            match from.characters.iter().find(|x| x.inventory_path == c.inventory_path) {
+                Some(x) => {
+                    c.inventory = x.inventory.clone();
+                    c.inventory_loaded = x.inventory_loaded;
+                }
+                // A new character whose inventory hasn't been written yet
+                None => c.inventory_loaded = false,
+            }
+            let from = if files.contains(&c.loadout_path) { written } else { saved };
+            match from.characters.iter().find(|x| x.loadout_path == c.loadout_path) {
+                Some(x) => {
+                    c.loadout = x.loadout.clone();
+                    c.loadout_loaded = x.loadout_loaded;
+                }
+                None => c.loadout_loaded = false,
+            }
+            c
+        }).collect();
+        let prospects = written.prospects.iter().map(|p| {
+            match saved.prospects.iter().find(|x| x.path == p.path) {
+                Some(x) if !files.contains(&p.path) => x.clone(),
+                _ => p.clone(),
+            }
+        }).collect();
+
+        EditSnapshot { profile: profile.clone(), characters, prospects }
+    }
+}
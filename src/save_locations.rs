@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+
+/// Where Windows keeps the data of packaged (Microsoft Store and Game Pass) apps, under
+/// `%LOCALAPPDATA%`.
+const PACKAGES_DIR: &str = "Packages";
+/// Where Windows installs packaged apps. Nothing in it can be written, even by an administrator.
+const WINDOWS_APPS_DIR: &str = "WindowsApps";
+
+/// The `PlayerData` folder Steam and GOG copies of Icarus keep a folder per account in.
+pub fn player_data_dir(local_dir: &Path) -> PathBuf {
+    local_dir.join("Icarus").join("Saved").join("PlayerData")
+}
+
+/// Folders to look for Game Pass saves in. A packaged game's writes to `%LOCALAPPDATA%` are
+/// redirected into its package's `LocalCache\Local`, so its `PlayerData` ends up there; the
+/// package's `LocalState` is searched too, as some packaged games keep their saves there instead.
+pub fn packaged_save_roots(local_dir: &Path) -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    let packages = match local_dir.join(PACKAGES_DIR).read_dir() {
+        Ok(packages) => packages,
+        Err(_) => return roots,
+    };
+    for package in packages.flatten() {
+        if !package.file_name().to_string_lossy().to_lowercase().contains("icarus") {
+            continue;
+        }
+        let package = package.path();
+        roots.push(player_data_dir(&package.join("LocalCache").join("Local")));
+        roots.push(package.join("LocalState"));
+    }
+    roots.retain(|x| x.is_dir());
+    roots.sort();
+
+    roots
+}
+
+fn has_component(path: &Path, name: &str) -> bool {
+    path.components().any(|x| x.as_os_str().to_string_lossy().eq_ignore_ascii_case(name))
+}
+
+/// Whether `path` is in a packaged app's data, where a Game Pass save lives.
+pub fn is_packaged_storage(path: &Path) -> bool {
+    has_component(path, PACKAGES_DIR)
+}
+
+/// Whether `path` is in the folder packaged apps are installed to.
+pub fn is_packaged_install(path: &Path) -> bool {
+    has_component(path, WINDOWS_APPS_DIR)
+}
@@ -0,0 +1,20 @@
+/// Queries shorter than this only match as a substring - any shorter and nearly every row name
+/// would match fuzzily.
+const MIN_FUZZY_LENGTH: usize = 3;
+
+/// Whether `text` matches the search `query`, ignoring case. An empty query matches everything. As
+/// well as plain substrings, a query matches if its characters appear in `text` in order, so
+/// "hntarch" finds "Talent_Hunting_Archery".
+pub fn matches(query: &str, text: &str) -> bool {
+    let query = query.trim().to_lowercase();
+    let text = text.to_lowercase();
+    if text.contains(&query) {
+        return true;
+    }
+    if query.chars().count() < MIN_FUZZY_LENGTH {
+        return false;
+    }
+
+    let mut remaining = text.chars();
+    query.chars().filter(|c| !c.is_whitespace()).all(|q| remaining.any(|c| c == q))
+}
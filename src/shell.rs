@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::path::Path;
+use std::process::Command;
+
+/// Opens a folder in the file manager, or a file in whatever the OS opens that kind of file with.
+pub fn open(path: &Path) -> Result<(), Box<dyn Error>> {
+    if !path.exists() {
+        Err(format!("[{}] doesn't exist", path.to_string_lossy()))?
+    }
+    opener().arg(path).spawn()?;
+
+    Ok(())
+}
+
+/// Opens a web page in the default browser.
+pub fn open_url(url: &str) -> Result<(), Box<dyn Error>> {
+    opener().arg(url).spawn()?;
+
+    Ok(())
+}
+
+/// Starts another copy of the editor with the same arguments, for the caller to then quit.
+pub fn restart() -> Result<(), Box<dyn Error>> {
+    Command::new(std::env::current_exe()?).args(std::env::args_os().skip(1)).spawn()?;
+
+    Ok(())
+}
+
+/// Starts another copy of the editor with the same arguments as administrator, after Windows asks
+/// the user, for the caller to then quit.
+#[cfg(windows)]
+pub fn restart_as_admin() -> Result<(), Box<dyn Error>> {
+    // PowerShell takes single quoted strings literally, apart from doubled single quotes
+    let quote = |x: &std::ffi::OsStr| format!("'{}'", x.to_string_lossy().replace('\'', "''"));
+    let mut command = format!("Start-Process -FilePath {} -Verb RunAs", quote(std::env::current_exe()?.as_os_str()));
+    let args = std::env::args_os().skip(1).map(|x| quote(&x)).collect::<Vec<String>>();
+    if !args.is_empty() {
+        command.push_str(&format!(" -ArgumentList {}", args.join(",")));
+    }
+    let status = Command::new("powershell").args(["-NoProfile", "-Command", &command]).status()?;
+    if !status.success() {
+        Err("the editor wasn't restarted as administrator")?
+    }
+
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn restart_as_admin() -> Result<(), Box<dyn Error>> {
+    Err("running as administrator is only supported on Windows".into())
+}
+
+#[cfg(windows)]
+fn opener() -> Command {
+    Command::new("explorer")
+}
+
+#[cfg(target_os = "macos")]
+fn opener() -> Command {
+    Command::new("open")
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn opener() -> Command {
+    Command::new("xdg-open")
+}
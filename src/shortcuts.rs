@@ -0,0 +1,96 @@
+use druid::widget::{Controller, Tabs};
+use druid::{Env, Event, EventCtx, HotKey, KbKey, KeyEvent, Selector, SysMods, Widget};
+
+use crate::{CharTabs, UiState};
+
+/// Moves the character tabs on by this many tabs, wrapping round at either end.
+const CYCLE_CHARACTER_TAB: Selector<isize> = Selector::new("icarus-editor.cycle-character-tab");
+/// Opens the character tab with this index, if there's a character there.
+pub const SELECT_CHARACTER_TAB: Selector<usize> = Selector::new("icarus-editor.select-character-tab");
+
+/// Handles Ctrl+S to save, Ctrl+R to reload from disk, and Ctrl+Tab, Ctrl+Shift+Tab and Ctrl+1 to
+/// Ctrl+9 to switch characters. Undo and redo are handled by `UndoController`. Meant to wrap the
+/// root widget, which sees key presses wherever the focus is.
+pub struct ShortcutController;
+
+impl ShortcutController {
+    fn handle(ctx: &mut EventCtx, key: &KeyEvent, data: &mut UiState) -> bool {
+        if HotKey::new(SysMods::Cmd, "s").matches(key) {
+            data.open_save_dialog();
+        } else if HotKey::new(SysMods::Cmd, "r").matches(key) {
+            data.request_reload();
+        } else if HotKey::new(SysMods::Cmd, KbKey::Tab).matches(key) {
+            ctx.submit_command(CYCLE_CHARACTER_TAB.with(1));
+        } else if HotKey::new(SysMods::CmdShift, KbKey::Tab).matches(key) {
+            ctx.submit_command(CYCLE_CHARACTER_TAB.with(-1));
+        } else if let Some(idx) = (1..=9).find(|n| HotKey::new(SysMods::Cmd, n.to_string().as_str()).matches(key)) {
+            ctx.submit_command(SELECT_CHARACTER_TAB.with(idx - 1));
+        } else {
+            return false;
+        }
+
+        true
+    }
+}
+
+impl<W: Widget<UiState>> Controller<UiState, W> for ShortcutController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            // Prompts and the error screen have their own buttons, which shortcuts shouldn't skip
+            if data.prompt.is_none() && data.error.is_none() && ShortcutController::handle(ctx, key, data) {
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
+
+/// Switches the character tabs when `ShortcutController` asks.
+pub struct CharacterTabShortcuts;
+
+impl Controller<UiState, Tabs<CharTabs>> for CharacterTabShortcuts {
+    fn event(&mut self, child: &mut Tabs<CharTabs>, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        if let Event::Command(cmd) = event {
+            let count = data.characters.len();
+            let idx = if let Some(offset) = cmd.get(CYCLE_CHARACTER_TAB) {
+                Some((child.tab_index() as isize + offset).rem_euclid(count.max(1) as isize) as usize)
+            } else {
+                cmd.get(SELECT_CHARACTER_TAB).copied()
+            };
+            if let Some(idx) = idx.filter(|idx| *idx < count) {
+                child.set_tab_index(idx);
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
+
+/// Switches the open character when `ShortcutController` asks and they're listed down the side
+/// instead of in tabs.
+pub struct CharacterListShortcuts;
+
+impl<W: Widget<UiState>> Controller<UiState, W> for CharacterListShortcuts {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        if let Event::Command(cmd) = event {
+            let count = data.characters.len();
+            let idx = if let Some(offset) = cmd.get(CYCLE_CHARACTER_TAB) {
+                Some((data.selected_character as isize + offset).rem_euclid(count.max(1) as isize) as usize)
+            } else {
+                cmd.get(SELECT_CHARACTER_TAB).copied()
+            };
+            if let Some(idx) = idx.filter(|idx| *idx < count) {
+                data.selected_character = idx;
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
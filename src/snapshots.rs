@@ -0,0 +1,90 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use druid::Data;
+use druid::im::vector::Vector;
+
+use crate::config;
+use crate::export;
+use crate::numbers::Slot;
+use crate::Character;
+
+const SNAPSHOT_DIR: &str = "snapshots";
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+/// Characters that can't go in a file name on Windows.
+pub const RESERVED_CHARACTERS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// A saved copy of a character, its inventory and its loadout, kept by the editor rather than next
+/// to the save.
+#[derive(Clone, PartialEq, Debug, Data)]
+pub struct Snapshot {
+    pub slot: Slot,
+    pub name: String,
+    #[data(eq)]
+    pub path: PathBuf,
+}
+
+/// Snapshots are kept per save folder, so each account has its own.
+fn snapshot_root(save_dir: &Path) -> Option<PathBuf> {
+    let account = save_dir.file_name()?;
+    config::config_dir().map(|x| x.join(SNAPSHOT_DIR).join(account))
+}
+
+fn slot_dir(save_dir: &Path, slot: Slot) -> Result<PathBuf, Box<dyn Error>> {
+    let root = snapshot_root(save_dir).ok_or("Unable to find config directory")?;
+    Ok(root.join(format!("Slot_{}", slot)))
+}
+
+/// Saves a snapshot of `character` called `name`, or named after the time if `name` is blank.
+pub fn take_snapshot(save_dir: &Path, character: &Character, name: &str) -> Result<Snapshot, Box<dyn Error>> {
+    let name = match name.trim() {
+        "" => Local::now().format(TIMESTAMP_FORMAT).to_string(),
+        name => name.to_string(),
+    };
+    if let Some(c) = name.chars().find(|c| RESERVED_CHARACTERS.contains(c)) {
+        Err(format!("snapshot names can't contain [{}]", c))?
+    }
+
+    let dir = slot_dir(save_dir, character.character_slot)?;
+    let path = dir.join(format!("{}.json", name));
+    if path.exists() {
+        Err(format!("there's already a snapshot called [{}]", name))?
+    }
+    fs::create_dir_all(&dir)?;
+    export::export_character(character, &path)?;
+
+    Ok(Snapshot { slot: character.character_slot, name, path })
+}
+
+/// Lists the snapshots for every slot in `save_dir`, by slot and then by name.
+pub fn list_snapshots(save_dir: &Path) -> Result<Vector<Snapshot>, Box<dyn Error>> {
+    let root = match snapshot_root(save_dir) {
+        Some(root) if root.exists() => root,
+        _ => return Ok(Vector::new()),
+    };
+    let mut snapshots = Vec::new();
+    for slot_entry in root.read_dir()? {
+        let slot_entry = slot_entry?;
+        let slot = match slot_entry.file_name().to_string_lossy().strip_prefix("Slot_").and_then(|x| x.parse::<Slot>().ok()) {
+            Some(slot) if slot_entry.file_type()?.is_dir() => slot,
+            _ => continue,
+        };
+        for entry in slot_entry.path().read_dir()? {
+            let path = entry?.path();
+            if path.extension().map(|x| x == "json").unwrap_or(false) {
+                let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+                snapshots.push(Snapshot { slot, name, path });
+            }
+        }
+    }
+    snapshots.sort_by(|a, b| a.slot.cmp(&b.slot).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(Vector::from(snapshots))
+}
+
+pub fn delete_snapshot(snapshot: &Snapshot) -> Result<(), Box<dyn Error>> {
+    fs::remove_file(&snapshot.path)?;
+    Ok(())
+}
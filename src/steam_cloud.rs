@@ -0,0 +1,251 @@
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use directories::BaseDirs;
+
+use crate::files;
+
+const ICARUS_APP_ID: &str = "1149460";
+const REMOTE_CACHE_FILE: &str = "remotecache.vcf";
+/// The difference between a 64-bit Steam ID (the `PlayerData` folder name) and the 32-bit account
+/// ID Steam uses for its `userdata` folders.
+const STEAM_ID_BASE: u64 = 76561197960265728;
+
+/// A `remotecache.vcf` file - Valve's KeyValues text format, which is nested `"key" "value"` and
+/// `"key" { ... }` pairs.
+pub struct RemoteCache {
+    pub path: PathBuf,
+    root: KeyValues,
+}
+
+enum Value {
+    Text(String),
+    Section(KeyValues),
+}
+
+type KeyValues = Vec<(String, Value)>;
+
+/// What Steam remembers about one file in the cloud.
+pub struct CloudFile {
+    pub name: String,
+    /// When Steam last saw the local file
+    pub local_time: u64,
+    /// When the file was last synced
+    pub time: u64,
+    /// When the cloud copy was last changed
+    pub remote_time: u64,
+}
+
+/// Finds Icarus's Steam Cloud cache for the Steam account that owns `save_dir`.
+pub fn find_remote_cache(save_dir: &Path) -> Option<PathBuf> {
+    let steam_id = save_dir.file_name()?.to_str()?.parse::<u64>().ok()?;
+    let account_id = steam_id.checked_sub(STEAM_ID_BASE)?;
+    steam_dirs()
+        .into_iter()
+        .map(|x| x.join("userdata").join(account_id.to_string()).join(ICARUS_APP_ID).join(REMOTE_CACHE_FILE))
+        .find(|x| x.exists())
+}
+
+fn steam_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for var in ["ProgramFiles(x86)", "ProgramFiles"] {
+        if let Some(program_files) = env::var_os(var) {
+            dirs.push(PathBuf::from(program_files).join("Steam"));
+        }
+    }
+    if let Some(base) = BaseDirs::new() {
+        dirs.push(base.home_dir().join(".steam").join("steam"));
+        dirs.push(base.data_local_dir().join("Steam"));
+    }
+
+    dirs
+}
+
+/// Seconds since the epoch that `path` was last modified.
+fn modified_time(path: &Path) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+impl RemoteCache {
+    pub fn load(path: &Path) -> Result<RemoteCache, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let root = parse(&mut tokenize(&text)?.into_iter())?;
+
+        Ok(RemoteCache { path: path.to_path_buf(), root })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let mut text = String::new();
+        write(&self.root, 0, &mut text);
+        files::write_atomic(&self.path, text.as_bytes())
+    }
+
+    fn entries_mut(&mut self) -> Option<&mut KeyValues> {
+        match self.root.iter_mut().find(|(k, _)| k == ICARUS_APP_ID) {
+            Some((_, Value::Section(entries))) => Some(entries),
+            _ => None,
+        }
+    }
+
+    fn entries(&self) -> Option<&KeyValues> {
+        match self.root.iter().find(|(k, _)| k == ICARUS_APP_ID) {
+            Some((_, Value::Section(entries))) => Some(entries),
+            _ => None,
+        }
+    }
+
+    /// Looks up `file`, which the cache names relative to the cloud root - so we match on the end
+    /// of the path.
+    pub fn file(&self, save_dir: &Path, file: &Path) -> Option<CloudFile> {
+        let entries = self.entries()?;
+        let (name, fields) = entries.iter().find(|(k, _)| matches(save_dir, file, k))?;
+        let fields = match fields {
+            Value::Section(fields) => fields,
+            Value::Text(_) => return None,
+        };
+        let number = |key: &str| {
+            fields.iter().find_map(|(k, v)| match v {
+                Value::Text(v) if k == key => v.parse::<u64>().ok(),
+                _ => None,
+            }).unwrap_or(0)
+        };
+
+        Some(CloudFile {
+            name: name.clone(),
+            local_time: number("localtime"),
+            time: number("time"),
+            remote_time: number("remotetime"),
+        })
+    }
+
+    /// Describes every file in `files` that's out of step with the cloud.
+    pub fn warnings(&self, save_dir: &Path, files: &[PathBuf]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        for file in files {
+            let cloud = match self.file(save_dir, file) {
+                Some(cloud) => cloud,
+                None => continue,
+            };
+            let modified = modified_time(file).unwrap_or(cloud.local_time);
+            if cloud.remote_time > modified {
+                warnings.push(format!("Steam Cloud has a newer copy of [{}] than this computer - Steam may replace your edits with it", cloud.name));
+            } else if cloud.remote_time > cloud.time {
+                warnings.push(format!("[{}] has changed in Steam Cloud since it was last synced - saving will cause a cloud conflict in Steam", cloud.name));
+            }
+        }
+
+        warnings
+    }
+
+    /// Marks `files` as in sync with the cloud as of their last sync, so the next time Steam looks
+    /// the only change it sees is the local one, and it uploads the edited files. Steam keeps this
+    /// file open while it runs, so this only sticks if Steam is closed.
+    pub fn prefer_local(&mut self, save_dir: &Path, files: &[PathBuf]) {
+        let entries = match self.entries_mut() {
+            Some(entries) => entries,
+            None => return,
+        };
+        for (name, fields) in entries.iter_mut() {
+            if !files.iter().any(|f| matches(save_dir, f, name)) {
+                continue;
+            }
+            if let Value::Section(fields) = fields {
+                let time = fields.iter().find_map(|(k, v)| match v {
+                    Value::Text(v) if k == "time" => Some(v.clone()),
+                    _ => None,
+                });
+                if let Some(time) = time {
+                    for (k, v) in fields.iter_mut() {
+                        if k == "remotetime" {
+                            *v = Value::Text(time.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn matches(save_dir: &Path, file: &Path, name: &str) -> bool {
+    let relative = match file.strip_prefix(save_dir) {
+        Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+        Err(_) => return false,
+    };
+    let name = name.replace('\\', "/");
+
+    name == relative || name.ends_with(&format!("/{}", relative))
+}
+
+enum Token {
+    Text(String),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, Box<dyn Error>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => s.extend(chars.next()),
+                        Some(c) => s.push(c),
+                        None => Err("Unterminated string in Steam Cloud cache")?,
+                    }
+                }
+                tokens.push(Token::Text(s));
+            }
+            c if c.is_whitespace() => {}
+            c => Err(format!("Unexpected '{}' in Steam Cloud cache", c))?,
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse(tokens: &mut impl Iterator<Item = Token>) -> Result<KeyValues, Box<dyn Error>> {
+    let mut values = Vec::new();
+    while let Some(token) = tokens.next() {
+        let key = match token {
+            Token::Text(key) => key,
+            Token::Close => return Ok(values),
+            Token::Open => Err("Unexpected '{' in Steam Cloud cache")?,
+        };
+        let value = match tokens.next() {
+            Some(Token::Text(value)) => Value::Text(value),
+            Some(Token::Open) => Value::Section(parse(tokens)?),
+            _ => Err(format!("Missing value for \"{}\" in Steam Cloud cache", key))?,
+        };
+        values.push((key, value));
+    }
+
+    Ok(values)
+}
+
+fn write(values: &KeyValues, depth: usize, out: &mut String) {
+    let indent = "\t".repeat(depth);
+    for (key, value) in values {
+        match value {
+            Value::Text(text) => out.push_str(&format!("{}\"{}\"\t\t\"{}\"\n", indent, escape(key), escape(text))),
+            Value::Section(section) => {
+                out.push_str(&format!("{}\"{}\"\n{}{{\n", indent, escape(key), indent));
+                write(section, depth + 1, out);
+                out.push_str(&format!("{}}}\n", indent));
+            }
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
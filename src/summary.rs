@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::path::Path;
+
+use chrono::Local;
+
+use crate::files;
+use crate::{display_name, Character, CharacterEdits, Profile, BLUEPRINTS, BLUEPRINT_CATEGORIES, KNOWN_META_RESOURCES, TALENT_CATEGORIES, TALENT_LEVELS, WORKSHOP_ITEMS};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SummaryFormat {
+    Markdown,
+    Csv,
+}
+
+impl SummaryFormat {
+    /// CSV for a `.csv` file, otherwise Markdown.
+    pub fn for_path(path: &Path) -> SummaryFormat {
+        match path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => SummaryFormat::Csv,
+            _ => SummaryFormat::Markdown,
+        }
+    }
+}
+
+/// A character's unlocked rows from `categories`, by category. Rows that aren't in any category go
+/// under "Other", so nothing the character has is left out of the summary.
+fn unlocked_by_category(c: &Character, categories: &[(&'static str, Vec<&'static str>)], include: impl Fn(&str) -> bool) -> Vec<(&'static str, Vec<(String, i64)>)> {
+    let mut grouped = Vec::new();
+    for (category, row_names) in categories {
+        let rows = row_names.iter()
+            .filter(|x| c.talent_rank(x) > 0)
+            .map(|x| (x.to_string(), c.talent_rank(x)))
+            .collect::<Vec<(String, i64)>>();
+        if !rows.is_empty() {
+            grouped.push((*category, rows));
+        }
+    }
+    let other = c.talents.iter()
+        .filter(|t| include(&t.row_name) && !categories.iter().any(|(_, rows)| rows.contains(&t.row_name.as_str())))
+        .map(|t| (t.row_name.clone(), t.rank.0))
+        .collect::<Vec<(String, i64)>>();
+    if !other.is_empty() {
+        grouped.push(("Other", other));
+    }
+
+    grouped
+}
+
+fn talents(c: &Character) -> Vec<(&'static str, Vec<(String, i64)>)> {
+    unlocked_by_category(c, &TALENT_CATEGORIES, |x| !BLUEPRINTS.contains(x))
+}
+
+fn blueprints(c: &Character) -> Vec<(&'static str, Vec<(String, i64)>)> {
+    unlocked_by_category(c, &BLUEPRINT_CATEGORIES, |x| BLUEPRINTS.contains(x))
+}
+
+fn resource_name(meta_row: &str) -> &str {
+    KNOWN_META_RESOURCES.iter().find(|(row, _)| *row == meta_row).map(|(_, name)| *name).unwrap_or(meta_row)
+}
+
+fn markdown(profile: &Profile, characters: &[Character]) -> String {
+    let mut lines = vec![
+        "# Icarus save summary".to_string(),
+        String::new(),
+        format!("Exported {}", Local::now().format("%Y-%m-%d %H:%M")),
+        String::new(),
+        "## Profile".to_string(),
+        String::new(),
+        "| Resource | Count |".to_string(),
+        "| --- | --- |".to_string(),
+    ];
+    for resource in profile.meta_resources.iter() {
+        lines.push(format!("| {} | {} |", resource_name(&resource.meta_row), resource.count));
+    }
+    let workshop = profile.talents.iter().filter(|t| WORKSHOP_ITEMS.contains(t.row_name.as_str())).count();
+    lines.push(String::new());
+    lines.push(format!("Workshop items unlocked: {}", workshop));
+
+    for c in characters {
+        lines.push(String::new());
+        lines.push(format!("## {} (slot {})", c.character_name, c.character_slot));
+        lines.push(String::new());
+        lines.push(format!("Level {} ({} XP, {} XP debt)", c.level(), c.xp, c.xp_debt));
+        lines.push(String::new());
+        lines.push("### Talents".to_string());
+        for (category, rows) in talents(c) {
+            lines.push(String::new());
+            lines.push(format!("#### {}", category));
+            for (row_name, rank) in rows {
+                let max_rank = TALENT_LEVELS.get(row_name.as_str()).copied().unwrap_or(1);
+                lines.push(format!("- {}: {} / {}", display_name(&row_name), rank, max_rank));
+            }
+        }
+        lines.push(String::new());
+        lines.push(format!("### Blueprints ({} unlocked)", c.blueprints_unlocked()));
+        for (category, rows) in blueprints(c) {
+            lines.push(String::new());
+            lines.push(format!("#### {}", category));
+            for (row_name, _) in rows {
+                lines.push(format!("- {}", display_name(&row_name)));
+            }
+        }
+    }
+    lines.push(String::new());
+
+    lines.join("\n")
+}
+
+/// Quotes a CSV field if it needs it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn csv(profile: &Profile, characters: &[Character]) -> String {
+    let mut rows: Vec<[String; 5]> = vec![["Section", "Character", "Category", "Name", "Value"].map(String::from)];
+    for resource in profile.meta_resources.iter() {
+        rows.push(["Profile".into(), String::new(), "Resources".into(), resource_name(&resource.meta_row).into(), resource.count.to_string()]);
+    }
+    for talent in profile.talents.iter().filter(|t| WORKSHOP_ITEMS.contains(t.row_name.as_str())) {
+        rows.push(["Profile".into(), String::new(), "Workshop".into(), display_name(&talent.row_name).into(), "Unlocked".into()]);
+    }
+    for c in characters {
+        let mut push = |category: String, name: &str, value: String| rows.push(["Character".into(), c.character_name.clone(), category, name.into(), value]);
+        push(String::new(), "Level", c.level().to_string());
+        push(String::new(), "XP", c.xp.to_string());
+        push(String::new(), "XP debt", c.xp_debt.to_string());
+        for (category, talents) in talents(c) {
+            for (row_name, rank) in talents {
+                push(format!("Talents: {}", category), display_name(&row_name), rank.to_string());
+            }
+        }
+        for (category, blueprints) in blueprints(c) {
+            for (row_name, _) in blueprints {
+                push(format!("Blueprints: {}", category), display_name(&row_name), "Unlocked".into());
+            }
+        }
+    }
+
+    let mut text = rows.iter()
+        .map(|row| row.iter().map(|x| csv_field(x)).collect::<Vec<String>>().join(","))
+        .collect::<Vec<String>>()
+        .join("\r\n");
+    text.push_str("\r\n");
+    text
+}
+
+/// Writes a readable summary of the profile and every character - level, talents by category,
+/// blueprints and the profile's currencies - for sharing progress or keeping a record before a wipe.
+pub fn export_summary(profile: &Profile, characters: &[Character], path: &Path) -> Result<(), Box<dyn Error>> {
+    let text = match SummaryFormat::for_path(path) {
+        SummaryFormat::Markdown => markdown(profile, characters),
+        SummaryFormat::Csv => csv(profile, characters),
+    };
+    files::write_atomic(path, text.as_bytes())
+}
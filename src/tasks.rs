@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::sync::Mutex;
+use std::thread;
+
+use druid::ExtEventSink;
+use lazy_static::lazy_static;
+
+use crate::UiState;
+
+lazy_static! {
+    static ref SINK: Mutex<Option<ExtEventSink>> = Mutex::new(None);
+}
+
+/// Lets background work hand its results back to the window. Until this is called, as when running
+/// from the command line, work runs straight away on the calling thread.
+pub fn init(sink: ExtEventSink) {
+    *SINK.lock().unwrap_or_else(|e| e.into_inner()) = Some(sink);
+}
+
+fn sink() -> Option<ExtEventSink> {
+    SINK.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+impl UiState {
+    /// Whether loading or saving is still going on in the background.
+    pub fn is_busy(&self) -> bool {
+        !self.busy.is_empty()
+    }
+
+    /// Runs `work` - anything that reads or writes files - on a background thread so the window
+    /// stays responsive, showing `progress` until it's done. `finish` then gets the result back on
+    /// the UI thread. Errors from either are shown the same way `run_action` shows them. Only one
+    /// job runs at a time, so two saves can never write over each other.
+    pub fn run_in_background<T, W, F>(&mut self, what: &'static str, progress: &'static str, work: W, finish: F)
+    where
+        T: Send + 'static,
+        W: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+        F: FnOnce(&mut UiState, T) -> Result<(), Box<dyn Error>> + Send + 'static,
+    {
+        if self.is_busy() {
+            self.status = format!("Unable to {} - wait for {} to finish", what, self.busy.iter().cloned().collect::<Vec<&str>>().join(", ").to_lowercase());
+            return;
+        }
+        let sink = match sink() {
+            Some(sink) => sink,
+            None => {
+                self.run_action(what, |t| finish(t, work()?));
+                return;
+            }
+        };
+
+        self.busy.push_back(progress);
+        thread::spawn(move || {
+            // Errors can't cross threads, so they go back as text
+            let result = work().map_err(|e| e.to_string());
+            sink.add_idle_callback(move |t: &mut UiState| {
+                t.busy.retain(|x| *x != progress);
+                t.run_action(what, |t| finish(t, result?));
+            });
+        });
+    }
+}
+
+/// Runs `work` on a background thread without showing it as busy or holding up other jobs, for
+/// checks the user can carry on without. `finish` gets the result back on the UI thread, with any
+/// error as text. Nothing happens without a window to report back to.
+pub fn run_quietly<T, W, F>(work: W, finish: F)
+where
+    T: Send + 'static,
+    W: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+    F: FnOnce(&mut UiState, Result<T, String>) + Send + 'static,
+{
+    if let Some(sink) = sink() {
+        thread::spawn(move || {
+            let result = work().map_err(|e| e.to_string());
+            sink.add_idle_callback(move |t: &mut UiState| finish(t, result));
+        });
+    }
+}
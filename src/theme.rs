@@ -0,0 +1,127 @@
+use std::process::Command;
+
+use druid::{theme, Color, Data, Env, Key};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Serialize, Deserialize, Data, PartialEq, Debug)]
+pub enum Theme {
+    /// Light or dark to match the OS, checked once at startup
+    System,
+    Light,
+    Dark,
+}
+
+/// The text sizes the editor's own widgets use, so they follow the UI scale along with druid's.
+pub const HEADING_TEXT_SIZE: Key<f64> = Key::new("icarus-editor.heading-text-size");
+pub const SMALL_TEXT_SIZE: Key<f64> = Key::new("icarus-editor.small-text-size");
+
+/// The choices for the UI scale setting. druid already follows the OS display scaling, so these are
+/// on top of that.
+pub const UI_SCALES: [(&str, f64); 4] = [("100%", 1.0), ("125%", 1.25), ("150%", 1.5), ("200%", 2.0)];
+
+lazy_static! {
+    static ref SYSTEM_PREFERS_LIGHT: bool = system_prefers_light().unwrap_or(false);
+}
+
+/// Whether Windows is set to light mode for apps.
+#[cfg(windows)]
+fn system_prefers_light() -> Option<bool> {
+    use std::os::windows::process::CommandExt;
+    // Without this, the check flashes up a console window
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    let output = Command::new("reg")
+        .args(["query", r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize", "/v", "AppsUseLightTheme"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let value = output.lines().find(|x| x.contains("AppsUseLightTheme"))?.split_whitespace().last()?;
+    Some(value != "0x0")
+}
+
+/// Whether the desktop asks for light apps. Only GNOME's setting is checked.
+#[cfg(not(windows))]
+fn system_prefers_light() -> Option<bool> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(!String::from_utf8_lossy(&output.stdout).contains("dark"))
+}
+
+/// Sets up `env` for `theme`. druid's own theme is already dark, so only light needs any changes.
+pub fn apply(theme: Theme, env: &mut Env) {
+    let light = match theme {
+        Theme::System => *SYSTEM_PREFERS_LIGHT,
+        Theme::Light => true,
+        Theme::Dark => false,
+    };
+    if !light {
+        return;
+    }
+
+    env.set(theme::WINDOW_BACKGROUND_COLOR, Color::rgb8(0xF2, 0xF2, 0xF2));
+    env.set(theme::TEXT_COLOR, Color::rgb8(0x20, 0x20, 0x20));
+    env.set(theme::DISABLED_TEXT_COLOR, Color::rgb8(0x98, 0x98, 0x98));
+    env.set(theme::PLACEHOLDER_COLOR, Color::rgb8(0x80, 0x80, 0x80));
+    env.set(theme::BACKGROUND_LIGHT, Color::rgb8(0xFF, 0xFF, 0xFF));
+    env.set(theme::BACKGROUND_DARK, Color::rgb8(0xE4, 0xE4, 0xE4));
+    env.set(theme::FOREGROUND_LIGHT, Color::rgb8(0x20, 0x20, 0x20));
+    env.set(theme::FOREGROUND_DARK, Color::rgb8(0x40, 0x40, 0x40));
+    env.set(theme::DISABLED_FOREGROUND_LIGHT, Color::rgb8(0x98, 0x98, 0x98));
+    env.set(theme::DISABLED_FOREGROUND_DARK, Color::rgb8(0xB0, 0xB0, 0xB0));
+    env.set(theme::BUTTON_LIGHT, Color::rgb8(0xFF, 0xFF, 0xFF));
+    env.set(theme::BUTTON_DARK, Color::rgb8(0xDC, 0xDC, 0xDC));
+    env.set(theme::DISABLED_BUTTON_LIGHT, Color::rgb8(0xF0, 0xF0, 0xF0));
+    env.set(theme::DISABLED_BUTTON_DARK, Color::rgb8(0xE4, 0xE4, 0xE4));
+    env.set(theme::BORDER_DARK, Color::rgb8(0xA0, 0xA0, 0xA0));
+    env.set(theme::BORDER_LIGHT, Color::rgb8(0xC8, 0xC8, 0xC8));
+    env.set(theme::SELECTED_TEXT_BACKGROUND_COLOR, Color::rgb8(0xA6, 0xCC, 0xFF));
+    env.set(theme::SELECTED_TEXT_INACTIVE_BACKGROUND_COLOR, Color::rgb8(0xD0, 0xD0, 0xD0));
+    env.set(theme::SELECTION_TEXT_COLOR, Color::rgb8(0x00, 0x00, 0x00));
+    env.set(theme::CURSOR_COLOR, Color::rgb8(0x00, 0x00, 0x00));
+    env.set(theme::SCROLLBAR_COLOR, Color::rgb8(0x90, 0x90, 0x90));
+    env.set(theme::SCROLLBAR_BORDER_COLOR, Color::rgb8(0x70, 0x70, 0x70));
+}
+
+/// Gives a text box a red border, for while what's typed in it can't be used.
+pub fn apply_invalid(env: &mut Env) {
+    env.set(theme::BORDER_DARK, Color::rgb8(0xE0, 0x40, 0x40));
+    env.set(theme::PRIMARY_LIGHT, Color::rgb8(0xE0, 0x40, 0x40));
+}
+
+/// Scales the text and the standard widget sizes in `env` by `scale`.
+pub fn apply_scale(scale: f64, env: &mut Env) {
+    env.set(HEADING_TEXT_SIZE, 18.0 * scale);
+    env.set(SMALL_TEXT_SIZE, 11.0 * scale);
+    if scale == 1.0 {
+        return;
+    }
+
+    let sizes = [
+        theme::TEXT_SIZE_NORMAL,
+        theme::TEXT_SIZE_LARGE,
+        theme::BASIC_WIDGET_HEIGHT,
+        theme::WIDE_WIDGET_WIDTH,
+        theme::BORDERED_WIDGET_HEIGHT,
+        theme::WIDGET_PADDING_VERTICAL,
+        theme::WIDGET_PADDING_HORIZONTAL,
+        theme::WIDGET_CONTROL_COMPONENT_PADDING,
+        theme::SCROLLBAR_WIDTH,
+        theme::SCROLLBAR_PAD,
+    ];
+    for key in sizes {
+        let size = env.get(&key);
+        env.set(key, size * scale);
+    }
+    for key in [theme::UI_FONT, theme::UI_FONT_BOLD, theme::UI_FONT_ITALIC] {
+        let font = env.get(&key);
+        let size = font.size * scale;
+        env.set(key, font.with_size(size));
+    }
+}
@@ -0,0 +1,71 @@
+use std::error::Error;
+use std::time::Duration;
+
+use druid::Data;
+use serde::Deserialize;
+
+use crate::{tasks, UiState};
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/ipsi/icarus-offline-character-editor/releases/latest";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// A release on GitHub.
+#[derive(Clone, Data, PartialEq, Debug, Deserialize)]
+pub struct Release {
+    #[serde(rename = "tag_name")]
+    pub version: String,
+    /// The release's page, which has the downloads on it
+    #[serde(rename = "html_url")]
+    pub url: String,
+}
+
+fn latest_release() -> Result<Release, Box<dyn Error>> {
+    let release = ureq::get(LATEST_RELEASE_URL)
+        // GitHub turns away requests without one
+        .set("User-Agent", concat!("icarus-offline-character-editor/", env!("CARGO_PKG_VERSION")))
+        .set("Accept", "application/vnd.github+json")
+        .timeout(Duration::from_secs(10))
+        .call()?
+        .into_json()?;
+
+    Ok(release)
+}
+
+/// The numbers in a version such as `v1.2.0`, without any trailing zeros so `1.2` and `1.2.0`
+/// compare the same. Anything after the digits in a part, like `-beta`, is ignored.
+fn version_parts(version: &str) -> Vec<u64> {
+    let mut parts = version.trim().trim_start_matches('v')
+        .split('.')
+        .map(|part| part.split(|c: char| !c.is_ascii_digit()).next().and_then(|x| x.parse().ok()).unwrap_or(0))
+        .collect::<Vec<u64>>();
+    while parts.last() == Some(&0) {
+        parts.pop();
+    }
+
+    parts
+}
+
+fn is_newer(release: &Release) -> bool {
+    version_parts(&release.version) > version_parts(CURRENT_VERSION)
+}
+
+impl UiState {
+    /// Looks for a newer release in the background. This doesn't count as a job, so saving isn't
+    /// held up by a slow connection. When `quiet`, as at startup, nothing is said unless there's a
+    /// newer release.
+    pub fn check_for_updates(&mut self, quiet: bool) {
+        if !quiet {
+            self.status = "Checking for a new version...".to_string();
+        }
+        tasks::run_quietly(latest_release, move |t, release| match release {
+            Ok(release) if is_newer(&release) => {
+                t.status = format!("Version {} is available", release.version);
+                t.available_update = Some(release);
+            }
+            Ok(_) if !quiet => t.status = format!("This is the latest version ({})", CURRENT_VERSION),
+            Err(e) if !quiet => t.status = format!("Unable to check for updates: {}", e),
+            Err(e) => println!("Unable to check for updates: {}", e),
+            Ok(_) => {}
+        });
+    }
+}
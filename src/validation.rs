@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use serde_json::{Map, Value};
+
+use crate::numbers::Slot;
+
+/// Names a character from `Characters.json` for error messages, using as much of its name and slot
+/// as can still be read from the raw JSON.
+pub fn describe_character(idx: usize, raw: &str) -> String {
+    let value = serde_json::from_str::<Value>(raw).ok();
+    let name = value.as_ref().and_then(|v| v.get("CharacterName")).and_then(Value::as_str);
+    let slot = value.as_ref().and_then(|v| v.get("ChrSlot")).and_then(Value::as_f64);
+    match (name, slot) {
+        (Some(name), Some(slot)) => format!("character {} (\"{}\", slot {})", idx + 1, name, slot),
+        (Some(name), None) => format!("character {} (\"{}\")", idx + 1, name),
+        (None, Some(slot)) => format!("character {} (slot {})", idx + 1, slot),
+        (None, None) => format!("character {}", idx + 1),
+    }
+}
+
+/// The longest name the character creator accepts.
+pub const MAX_CHARACTER_NAME_LENGTH: usize = 24;
+/// Punctuation the character creator accepts in names, besides letters, digits and spaces.
+const CHARACTER_NAME_PUNCTUATION: [char; 4] = ['-', '_', '\'', '.'];
+
+/// What's wrong with a character name, if anything, going by what the game's character creator
+/// accepts.
+pub fn character_name_problem(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        Some("the name can't be empty".to_string())
+    } else if name.chars().count() > MAX_CHARACTER_NAME_LENGTH {
+        Some(format!("the name can't be longer than {} characters", MAX_CHARACTER_NAME_LENGTH))
+    } else if name.trim() != name {
+        Some("the name can't start or end with a space".to_string())
+    } else {
+        name.chars()
+            .find(|c| !c.is_alphanumeric() && *c != ' ' && !CHARACTER_NAME_PUNCTUATION.contains(c))
+            .map(|c| format!("the name can't contain [{}]", c))
+    }
+}
+
+/// Reads just the slot of a character that couldn't be read as a whole.
+pub fn character_slot(raw: &str) -> Option<Slot> {
+    let value = serde_json::from_str::<Value>(raw).ok()?;
+    serde_json::from_value(value.get("ChrSlot")?.clone()).ok()
+}
+
+/// Points out fields a game update has added that the editor doesn't know about. They're kept as
+/// they are, but can't be edited.
+pub fn unknown_fields_warning(file: &Path, what: &str, extra: &Map<String, Value>) -> Option<String> {
+    if extra.is_empty() {
+        None
+    } else {
+        let fields = extra.keys().cloned().collect::<Vec<String>>();
+        Some(format!("[{}] {} has fields this editor doesn't recognise, which will be saved unchanged: {}", file.to_string_lossy(), what, fields.join(", ")))
+    }
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use druid::widget::Controller;
+use druid::{Env, Event, EventCtx, TimerToken, Widget};
+
+use crate::{Prompt, UiState};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn modified_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files.iter().map(|x| fs::metadata(x).and_then(|m| m.modified()).ok()).collect()
+}
+
+impl UiState {
+    /// The files the game or Steam are likely to rewrite while the editor is open.
+    fn watched_files(&self) -> Vec<PathBuf> {
+        vec![self.profile_file.clone(), self.characters_file.clone()]
+    }
+
+    /// Remembers when the watched files were last changed, so later changes can be spotted.
+    pub fn remember_disk_times(&mut self) {
+        self.disk_times = modified_times(&self.watched_files());
+    }
+
+    fn changed_on_disk(&self) -> bool {
+        modified_times(&self.watched_files()) != self.disk_times
+    }
+
+    /// Throws away any edits and loads the save again.
+    pub fn reload_from_disk(&mut self) {
+        self.open_save_dir(self.save_dir.clone(), false);
+    }
+
+    /// Reloads, asking first if there are edits that would be lost.
+    pub fn request_reload(&mut self) {
+        if self.is_dirty() && self.config.confirm_unsaved {
+            self.prompt = Some(Prompt::Reload);
+        } else {
+            self.reload_from_disk();
+        }
+    }
+
+    /// Ignores the change on disk. The next save will overwrite it.
+    pub fn keep_edits(&mut self) {
+        self.remember_disk_times();
+        self.external_change = false;
+    }
+}
+
+/// Checks every couple of seconds whether something else has written to the save, and if so flags
+/// it so the user can choose which version to keep. Meant to wrap the root widget.
+pub struct FileWatcher {
+    timer: TimerToken,
+}
+
+impl Default for FileWatcher {
+    fn default() -> Self {
+        FileWatcher { timer: TimerToken::INVALID }
+    }
+}
+
+impl<W: Widget<UiState>> Controller<UiState, W> for FileWatcher {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        match event {
+            Event::WindowConnected => self.timer = ctx.request_timer(POLL_INTERVAL),
+            Event::Timer(token) if *token == self.timer => {
+                if data.error.is_none() && !data.external_change && data.changed_on_disk() {
+                    data.external_change = true;
+                }
+                self.timer = ctx.request_timer(POLL_INTERVAL);
+                return;
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+    }
+}
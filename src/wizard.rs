@@ -0,0 +1,70 @@
+use druid::Data;
+
+use crate::UiState;
+
+/// The steps of the first run wizard, in order.
+#[derive(Clone, Copy, PartialEq, Debug, Data)]
+pub enum WizardStep {
+    SaveFolder,
+    Characters,
+    Backup,
+    Updates,
+}
+
+const STEPS: [WizardStep; 4] = [WizardStep::SaveFolder, WizardStep::Characters, WizardStep::Backup, WizardStep::Updates];
+
+impl WizardStep {
+    pub fn title(self) -> String {
+        let name = match self {
+            WizardStep::SaveFolder => "Find your save",
+            WizardStep::Characters => "Check your characters",
+            WizardStep::Backup => "Back up your save",
+            WizardStep::Updates => "Updates",
+        };
+        format!("Step {} of {}: {}", self.index() + 1, STEPS.len(), name)
+    }
+
+    fn index(self) -> usize {
+        STEPS.iter().position(|x| *x == self).unwrap_or(0)
+    }
+
+    pub fn is_first(self) -> bool {
+        self.index() == 0
+    }
+
+    pub fn is_last(self) -> bool {
+        self.index() + 1 == STEPS.len()
+    }
+}
+
+impl UiState {
+    pub fn start_wizard(&mut self) {
+        self.wizard = Some(WizardStep::SaveFolder);
+    }
+
+    /// Moves on a step, finishing after the last.
+    pub fn wizard_next(&mut self) {
+        match self.wizard.and_then(|x| STEPS.get(x.index() + 1)) {
+            Some(step) => self.wizard = Some(*step),
+            None => self.finish_wizard(),
+        }
+    }
+
+    pub fn wizard_back(&mut self) {
+        if let Some(step) = self.wizard.filter(|x| !x.is_first()) {
+            self.wizard = Some(STEPS[step.index() - 1]);
+        }
+    }
+
+    /// Closes the wizard for good, and looks for updates straight away if the user asked for that.
+    pub fn finish_wizard(&mut self) {
+        self.wizard = None;
+        self.config.setup_done = true;
+        if let Err(e) = self.config.save() {
+            println!("Unable to save config: {}", e);
+        }
+        if self.config.check_for_updates {
+            self.check_for_updates(true);
+        }
+    }
+}
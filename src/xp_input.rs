@@ -0,0 +1,124 @@
+use std::error::Error;
+use std::fmt;
+
+use druid::text::{Formatter, Selection, Validation, ValidationError};
+use druid::widget::{TextBoxEvent, ValidationDelegate};
+use druid::{Data, EventCtx, Selector};
+
+use crate::numbers::Xp;
+use crate::UiState;
+
+/// The most XP or XP debt the game accepts. It keeps both in a 32 bit integer, so anything larger
+/// wraps round to a negative number when the save is loaded.
+pub const MAX_XP: i64 = i32::MAX as i64;
+
+/// Sets or clears the error shown beside a character's XP box, by the character's index.
+pub const XP_INPUT_ERROR: Selector<(usize, XpField, Option<String>)> = Selector::new("icarus-editor.xp-input-error");
+
+/// Which XP box an error is for.
+#[derive(Clone, Copy, PartialEq, Debug, Data)]
+pub enum XpField {
+    Xp,
+    XpDebt,
+}
+
+/// Why typed in XP can't be used.
+#[derive(Clone, PartialEq, Debug)]
+pub struct XpInputError(String);
+
+impl fmt::Display for XpInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for XpInputError {}
+
+/// Reads the XP boxes, only accepting whole numbers from 0 to `MAX_XP` where a plain
+/// `ParseFormatter` would take anything that parses.
+pub struct XpFormatter;
+
+impl XpFormatter {
+    fn parse(input: &str) -> Result<Xp, XpInputError> {
+        let input = input.trim();
+        let xp = match input.parse::<i64>() {
+            Ok(xp) => xp,
+            Err(_) => match input.parse::<f64>() {
+                Ok(xp) if !xp.is_finite() => return Err(XpInputError("XP has to be a number".to_string())),
+                Ok(xp) if xp.fract() != 0.0 => return Err(XpInputError("XP has to be a whole number".to_string())),
+                // Such as 1e6, or too big for an i64, which the range check below still catches
+                Ok(xp) => xp.clamp(-1.0, MAX_XP as f64 + 1.0) as i64,
+                Err(_) if input.is_empty() => return Err(XpInputError("XP can't be empty".to_string())),
+                Err(_) => return Err(XpInputError(format!("\"{}\" isn't a number", input))),
+            },
+        };
+        if xp < 0 {
+            Err(XpInputError("XP can't be negative".to_string()))
+        } else if xp > MAX_XP {
+            Err(XpInputError(format!("XP can't be more than {}, the most the game accepts", MAX_XP)))
+        } else {
+            Ok(Xp(xp))
+        }
+    }
+}
+
+impl Formatter<Xp> for XpFormatter {
+    fn format(&self, value: &Xp) -> String {
+        value.to_string()
+    }
+
+    fn validate_partial_input(&self, input: &str, _sel: &Selection) -> Validation {
+        // An empty box is on its way to a new number, so it's only an error once editing ends
+        match XpFormatter::parse(input) {
+            Err(e) if !input.trim().is_empty() => Validation::failure(e),
+            _ => Validation::success(),
+        }
+    }
+
+    fn value(&self, input: &str) -> Result<Xp, ValidationError> {
+        XpFormatter::parse(input).map_err(ValidationError::new)
+    }
+}
+
+/// Passes what's wrong with an XP box's text on to be shown beside it, clearing it again once the
+/// text is valid or editing stops. Holds the index of the character the box is for.
+pub struct XpErrorDelegate(pub usize, pub XpField);
+
+impl ValidationDelegate for XpErrorDelegate {
+    fn event(&mut self, ctx: &mut EventCtx, event: TextBoxEvent, _current_text: &str) {
+        let error = match event {
+            TextBoxEvent::PartiallyInvalid(e) => Some(e.to_string()),
+            TextBoxEvent::Invalid(e) => Some(format!("{}, so it was put back", e)),
+            TextBoxEvent::Began | TextBoxEvent::Changed | TextBoxEvent::Complete | TextBoxEvent::Cancel => None,
+        };
+        ctx.submit_command(XP_INPUT_ERROR.with((self.0, self.1, error)));
+    }
+}
+
+/// The errors beside the XP boxes, if any, each with the index of the character it's for so it
+/// isn't shown on the other characters' tabs.
+#[derive(Clone, PartialEq, Debug, Default, Data)]
+pub struct XpInputErrors {
+    pub xp: Option<(usize, String)>,
+    pub xp_debt: Option<(usize, String)>,
+}
+
+impl XpInputErrors {
+    pub fn get(&self, idx: usize, field: XpField) -> Option<&String> {
+        let error = match field {
+            XpField::Xp => self.xp.as_ref(),
+            XpField::XpDebt => self.xp_debt.as_ref(),
+        };
+        error.filter(|(i, _)| *i == idx).map(|(_, e)| e)
+    }
+}
+
+impl UiState {
+    pub fn set_xp_input_error(&mut self, idx: usize, field: XpField, error: Option<String>) {
+        let error = error.map(|e| (idx, e));
+        match field {
+            XpField::Xp => self.xp_input_errors.xp = error,
+            XpField::XpDebt => self.xp_input_errors.xp_debt = error,
+        }
+    }
+}
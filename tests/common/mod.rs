@@ -0,0 +1,74 @@
+//! The fixture save helpers, shared by the integration tests and the editor's own unit tests.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use serde_json::Value;
+
+const FIXTURE_DIR: &str = "tests/fixtures/save";
+
+/// A copy of the fixture save in its own folder, deleted again when the test is done with it.
+pub struct TestSave {
+    pub dir: PathBuf,
+}
+
+impl TestSave {
+    /// Copies the fixture save somewhere a test can freely write to. `name` keeps tests running in
+    /// parallel out of each other's way.
+    pub fn new(name: &str) -> TestSave {
+        let dir = std::env::temp_dir().join(format!("icarus_save_{}_{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        copy_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURE_DIR), &dir);
+
+        TestSave { dir }
+    }
+
+    pub fn path(&self, file: &str) -> PathBuf {
+        self.dir.join(file)
+    }
+
+    pub fn read_json(&self, file: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(self.path(file)).unwrap()).unwrap()
+    }
+
+    /// Every file in the save, relative to it, so tests can check nothing was left behind.
+    pub fn files(&self) -> Vec<String> {
+        let mut files = Vec::new();
+        list_files(&self.dir, &self.dir, &mut files);
+        files.sort();
+
+        files
+    }
+}
+
+impl Drop for TestSave {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in from.read_dir().unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &to.join(entry.file_name()));
+        } else {
+            fs::copy(entry.path(), to.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<String>) {
+    for entry in dir.read_dir().unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            list_files(root, &path, files);
+        } else {
+            files.push(path.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use std::fs;
+
+use icarus_save::inventory::{Inventory, PROPERTY_DURABILITY};
+use icarus_save::loadout::Loadout;
+use icarus_save::numbers::{Slot, Xp};
+use icarus_save::prospect::ProspectSave;
+use icarus_save::{Character, CharacterStore, Characters, Profile};
+use serde_json::Value;
+
+use common::TestSave;
+
+/// Parses `json` as a `T` and writes it back out, which should give the same JSON.
+fn assert_round_trip<T: serde::de::DeserializeOwned + serde::Serialize>(json: &Value) {
+    let parsed: T = serde_json::from_value(json.clone()).unwrap();
+    let written = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(numbers_as_floats(json), numbers_as_floats(&written));
+}
+
+/// The save files write whole numbers without a decimal point but they're read as floats, so `1`
+/// comes back as `1.0`. That's the same number as far as the game is concerned.
+fn numbers_as_floats(value: &Value) -> Value {
+    match value {
+        Value::Number(n) => serde_json::json!(n.as_f64().unwrap()),
+        Value::Array(items) => Value::Array(items.iter().map(numbers_as_floats).collect()),
+        Value::Object(fields) => Value::Object(fields.iter().map(|(k, v)| (k.clone(), numbers_as_floats(v))).collect()),
+        other => other.clone(),
+    }
+}
+
+#[test]
+fn profile_round_trips() {
+    let save = TestSave::new("profile_round_trips");
+    assert_round_trip::<Profile>(&save.read_json("Profile.json"));
+}
+
+#[test]
+fn characters_round_trip() {
+    let save = TestSave::new("characters_round_trip");
+    let json = save.read_json("Characters.json");
+    assert_round_trip::<Characters>(&json);
+    for character in json["Characters.json"].as_array().unwrap() {
+        assert_round_trip::<Character>(&serde_json::from_str(character.as_str().unwrap()).unwrap());
+    }
+}
+
+#[test]
+fn inventory_and_loadout_round_trip() {
+    let save = TestSave::new("inventory_and_loadout_round_trip");
+    assert_round_trip::<Inventory>(&save.read_json("Inventory/InventoryID_0.json"));
+    assert_round_trip::<Loadout>(&save.read_json("Loadout/Slot_0.json"));
+    assert_round_trip::<Loadout>(&save.read_json("Loadout/Slot_1.json"));
+}
+
+#[test]
+fn open_world_prospect_round_trips() {
+    let json = serde_json::json!({
+        "ProspectInfo": {
+            "ProspectID": "Open World 1",
+            "ProspectDTKey": "OpenWorld_Olympus",
+            "ProspectState": "Active",
+            "LobbyName": "Olympus",
+            "Difficulty": "Medium",
+            "ElapsedTime": 3600,
+            "NoRespawns": false,
+            "Insurance": true,
+            "AssociatedMembers": [
+                {"AccountName": "Player", "CharacterName": "Ranger", "UserID": "76561190000000000", "ChrSlot": 0, "Experience": 100, "Status": "Abandoned"}
+            ]
+        },
+        "ProspectBlob": {"BinaryBlob": "AAAA"}
+    });
+    assert_round_trip::<ProspectSave>(&json);
+
+    let mut prospect: ProspectSave = serde_json::from_value(json).unwrap();
+    assert!(prospect.info.is_open_world());
+    assert_eq!(prospect.info.reset_abandoned(), 1);
+    assert_eq!(prospect.info.abandoned_members(), 0);
+    assert_eq!(serde_json::to_value(&prospect).unwrap()["ProspectInfo"]["AssociatedMembers"][0]["Experience"], 100);
+}
+
+#[test]
+fn spawned_items_are_split_into_stacks() {
+    let mut inventory = Inventory::default();
+    assert_eq!(inventory.spawn_item("Wood", 250, 100, None), 3);
+    assert_eq!(inventory.spawn_item("Iron_Axe", 1, 1, Some(500.0)), 1);
+    assert_eq!(inventory.spawn_item("Stone", 0, 100, None), 0);
+
+    let json = serde_json::to_value(&inventory).unwrap();
+    let delta = json["Delta"].as_array().unwrap();
+    assert_eq!(delta.len(), 4);
+    assert_eq!(delta[2]["ItemStaticData"]["RowName"], "Wood");
+    assert_eq!(delta[2]["ItemStaticData"]["DataTableName"], "D_ItemsStatic");
+    assert_eq!(delta[2]["ItemDynamicData"][0]["Value"], 50.0);
+    assert_eq!(delta[3]["ItemDynamicData"][1]["PropertyType"], PROPERTY_DURABILITY);
+    assert_eq!(delta[3]["ItemDynamicData"][1]["Value"], 500.0);
+}
+
+#[test]
+fn unknown_fields_are_kept() {
+    let save = TestSave::new("unknown_fields_are_kept");
+    let store = CharacterStore::load(&save.dir).unwrap();
+    assert_eq!(store.profile.extra["NextChrSlot"], 2);
+    assert!(store.characters.iter().all(|c| c.extra.contains_key("TimeLastPlayed")));
+    assert!(store.warnings.iter().any(|w| w.contains("NextChrSlot")));
+
+    store.save().unwrap();
+    assert_eq!(save.read_json("Profile.json")["NextChrSlot"], 2);
+}
+
+#[test]
+fn unreadable_prospects_are_warned_about() {
+    let save = TestSave::new("unreadable_prospects_are_warned_about");
+    fs::create_dir_all(save.path("Prospects")).unwrap();
+    fs::write(save.path("Prospects/Broken.json"), "{ not json").unwrap();
+    let store = CharacterStore::load(&save.dir).unwrap();
+    assert!(store.prospects.is_empty());
+    assert!(store.warnings.iter().any(|w| w.contains("Broken.json")));
+}
+
+#[test]
+fn unknown_item_fields_are_kept() {
+    let json = serde_json::json!({
+        "ID": "MetaInventoryID_Main",
+        "Delta": [{
+            "ItemStaticData": { "RowName": "Meta_Bandage", "DataTableName": "D_ItemsStatic", "ItemVariant": 2 },
+            "ItemDynamicData": [{ "PropertyType": "ItemableStack", "Value": 10, "Index": 0 }]
+        }]
+    });
+    assert_round_trip::<Inventory>(&json);
+
+    let inventory: Inventory = serde_json::from_value(json).unwrap();
+    let item = &inventory.delta[0];
+    assert_eq!(item.item_static_data.extra["ItemVariant"], 2);
+    assert_eq!(item.item_dynamic_data[0].extra["Index"], 0);
+}
+
+#[test]
+fn whole_numbers_are_written_as_integers() {
+    assert_eq!(serde_json::from_str::<Slot>("3.0").unwrap(), Slot(3));
+    assert_eq!(serde_json::to_string(&Slot(3)).unwrap(), "3");
+    assert_eq!(serde_json::from_str::<Xp>("-5").unwrap(), Xp(-5));
+    assert!(serde_json::from_str::<Slot>("-1").is_err());
+}
+
+#[test]
+fn high_slots_name_their_own_files() {
+    let save = TestSave::new("high_slots_name_their_own_files");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    store.characters[0].character_slot = Slot(200);
+    store.characters[0].set_file_paths(&save.dir);
+    assert!(store.characters[0].inventory_path.ends_with("Inventory/InventoryID_200.json"));
+    assert!(store.characters[0].loadout_path.ends_with("Loadout/Slot_200.json"));
+}
+
+#[test]
+fn store_loads_fixture() {
+    let save = TestSave::new("store_loads_fixture");
+    let store = CharacterStore::load(&save.dir).unwrap();
+
+    assert_eq!(store.profile.meta_resource("Credits"), 1250.0);
+    assert_eq!(store.characters.len(), 2);
+    assert_eq!(store.characters[0].character_name, "Ranger");
+    assert_eq!(store.characters[1].character_name, "Abandoned");
+
+    let ranger = &store.characters[0];
+    assert!(ranger.inventory_loaded && ranger.loadout_loaded);
+    assert_eq!(ranger.inventory.delta.len(), 2);
+    assert_eq!(ranger.inventory.delta[0].item_dynamic_data[1].property_type, PROPERTY_DURABILITY);
+
+    // The second character's inventory has a damaged item in it, so it's skipped with a warning
+    let abandoned = &store.characters[1];
+    assert!(!abandoned.inventory_loaded);
+    assert!(abandoned.loadout_loaded);
+    assert!(store.warnings.iter().any(|w| w.contains("InventoryID_1.json")));
+}
+
+#[test]
+fn store_round_trips() {
+    let save = TestSave::new("store_round_trips");
+    let store = CharacterStore::load(&save.dir).unwrap();
+    let inventory_before = save.read_json("Inventory/InventoryID_1.json");
+    store.save().unwrap();
+
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    assert_eq!(store.profile, reloaded.profile);
+    assert_eq!(store.characters, reloaded.characters);
+    assert_eq!(store.characters_extra, reloaded.characters_extra);
+    // An inventory that couldn't be read is left alone rather than overwritten with an empty one
+    assert_eq!(inventory_before, save.read_json("Inventory/InventoryID_1.json"));
+}
+
+#[test]
+fn character_order_is_kept() {
+    let save = TestSave::new("character_order_is_kept");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    let ranger = store.characters.pop_front().unwrap();
+    store.characters.push_back(ranger);
+    store.save().unwrap();
+
+    for _ in 0..2 {
+        let reloaded = CharacterStore::load(&save.dir).unwrap();
+        let names = reloaded.characters.iter().map(|c| c.character_name.as_str()).collect::<Vec<&str>>();
+        assert_eq!(names, ["Abandoned", "Ranger"]);
+        reloaded.save().unwrap();
+    }
+}
+
+#[test]
+fn broken_character_keeps_its_place() {
+    let save = TestSave::new("broken_character_keeps_its_place");
+    let mut json = save.read_json("Characters.json");
+    let broken = Value::String("{\"CharacterName\": 5}".to_string());
+    json["Characters.json"].as_array_mut().unwrap().insert(1, broken.clone());
+    fs::write(save.path("Characters.json"), serde_json::to_string(&json).unwrap()).unwrap();
+
+    let store = CharacterStore::load_with(&save.dir, true).unwrap();
+    assert_eq!(store.characters.len(), 2);
+    store.save().unwrap();
+
+    let saved = save.read_json("Characters.json");
+    let saved = saved["Characters.json"].as_array().unwrap();
+    assert_eq!(saved.len(), 3);
+    assert_eq!(saved[1], broken);
+    let reloaded = CharacterStore::load_with(&save.dir, true).unwrap();
+    let names = reloaded.characters.iter().map(|c| c.character_name.as_str()).collect::<Vec<&str>>();
+    assert_eq!(names, ["Ranger", "Abandoned"]);
+}
+
+#[test]
+fn edits_are_saved() {
+    let save = TestSave::new("edits_are_saved");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    store.profile.set_meta_resource("Credits", 500000.0);
+    store.profile.set_meta_resource("Exotic_Red", 5.0);
+    store.characters[0].pay_xp_debt();
+    store.characters[0].inventory.add_item("Meta_Bandage", 20.0);
+    store.save().unwrap();
+
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    assert_eq!(reloaded.profile.meta_resource("Credits"), 500000.0);
+    assert_eq!(reloaded.profile.meta_resource("Exotic_Red"), 5.0);
+    assert_eq!(reloaded.characters[0].xp, Xp(1195000));
+    assert_eq!(reloaded.characters[0].xp_debt, Xp(0));
+    assert_eq!(reloaded.characters[0].inventory.delta.len(), 3);
+}
+
+#[test]
+fn missing_save_is_an_error() {
+    let save = TestSave::new("missing_save_is_an_error");
+    fs::remove_file(save.path("Profile.json")).unwrap();
+    let error = CharacterStore::load(&save.dir).unwrap_err();
+    assert!(error.to_string().contains("start playing offline"));
+}
+
+#[test]
+fn save_without_characters_loads_empty() {
+    let save = TestSave::new("save_without_characters_loads_empty");
+    fs::remove_file(save.path("Characters.json")).unwrap();
+    fs::remove_dir_all(save.path("Inventory")).unwrap();
+    fs::remove_dir_all(save.path("Loadout")).unwrap();
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    assert!(store.characters.is_empty());
+
+    store.characters.push_back(Character::new("Newcomer", Slot(0), &save.dir));
+    store.save().unwrap();
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    assert_eq!(reloaded.characters.len(), 1);
+    assert_eq!(reloaded.characters[0].character_name, "Newcomer");
+    assert!(reloaded.characters[0].inventory_loaded && reloaded.characters[0].loadout_loaded);
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::fs;
+
+use icarus_save::{backup, files, CharacterStore, STATION_LOCATION};
+
+use common::TestSave;
+
+#[test]
+fn restore_clears_flags_and_keeps_items() {
+    let save = TestSave::new("restore_clears_flags_and_keeps_items");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    let character = &mut store.characters[1];
+    character.restore(false).unwrap();
+
+    assert!(!character.is_abandoned && !character.is_dead);
+    // The damaged item is dropped and the rest are kept
+    assert!(character.inventory_loaded);
+    assert_eq!(character.inventory.delta.len(), 1);
+    assert_eq!(character.inventory.delta[0].item_static_data.row_name, "Meta_Bandage");
+    assert!(character.loadout.valid);
+
+    // The inventory and loadout are fixed on disk straight away
+    assert_eq!(save.read_json("Inventory/InventoryID_1.json")["Delta"].as_array().unwrap().len(), 1);
+    assert_eq!(save.read_json("Loadout/Slot_1.json")["Valid"], true);
+    // but the character itself isn't until the save is
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    assert!(reloaded.characters[1].is_abandoned);
+    assert!(reloaded.characters[1].inventory_loaded);
+}
+
+#[test]
+fn restore_can_wipe_inventory() {
+    let save = TestSave::new("restore_can_wipe_inventory");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    store.characters[1].restore(true).unwrap();
+
+    assert!(store.characters[1].inventory.delta.is_empty());
+    assert!(save.read_json("Inventory/InventoryID_1.json")["Delta"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn restore_creates_missing_files() {
+    let save = TestSave::new("restore_creates_missing_files");
+    fs::remove_file(save.path("Loadout/Slot_1.json")).unwrap();
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    assert!(store.warnings.iter().any(|w| w.contains("has no loadout file")));
+
+    store.characters[1].restore(false).unwrap();
+    assert_eq!(save.read_json("Loadout/Slot_1.json")["Valid"], true);
+    assert!(store.characters[1].loadout_loaded);
+    assert!(store.characters[1].loadout.valid);
+}
+
+#[test]
+fn rescue_returns_to_station() {
+    let save = TestSave::new("rescue_returns_to_station");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    store.characters[1].rescue(false).unwrap();
+    store.save().unwrap();
+
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    let character = &reloaded.characters[1];
+    assert_eq!(character.location, STATION_LOCATION);
+    assert!(character.last_prospect_id.is_empty());
+    assert!(!character.is_abandoned);
+    assert_eq!(character.inventory.delta.len(), 1);
+}
+
+#[test]
+fn detach_from_prospect_keeps_inventory() {
+    let save = TestSave::new("detach_from_prospect_keeps_inventory");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    store.characters[1].detach_from_prospect();
+    store.save().unwrap();
+
+    let reloaded = CharacterStore::load(&save.dir).unwrap();
+    let character = &reloaded.characters[1];
+    assert_eq!(character.location, STATION_LOCATION);
+    assert!(character.last_prospect_id.is_empty());
+    assert_eq!(character.inventory, store.characters[1].inventory);
+}
+
+#[test]
+fn save_leaves_no_temporary_files() {
+    let save = TestSave::new("save_leaves_no_temporary_files");
+    let before = save.files();
+    CharacterStore::load(&save.dir).unwrap().save().unwrap();
+    assert_eq!(before, save.files());
+}
+
+#[test]
+fn failed_write_leaves_original() {
+    let save = TestSave::new("failed_write_leaves_original");
+    let path = save.path("Profile.json");
+    let before = fs::read(&path).unwrap();
+    // A folder where the temporary file would go makes the write fail before the original is touched
+    fs::create_dir(save.path("Profile.json.tmp")).unwrap();
+
+    assert!(files::write_atomic(&path, b"{}").is_err());
+    assert_eq!(before, fs::read(&path).unwrap());
+    assert!(CharacterStore::load(&save.dir).unwrap().save().is_err());
+    assert_eq!(before, fs::read(&path).unwrap());
+}
+
+#[test]
+fn write_replaces_contents() {
+    let save = TestSave::new("write_replaces_contents");
+    let path = save.path("Profile.json");
+    files::write_atomic(&path, b"{}").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{}");
+    assert!(!save.path("Profile.json.tmp").exists());
+}
+
+#[test]
+fn backup_restores_every_file() {
+    let save = TestSave::new("backup_restores_every_file");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    let before: Vec<Vec<u8>> = store.files().iter().map(|f| fs::read(f).unwrap()).collect();
+    let created = backup::create_backup(&save.dir, &store.files()).unwrap();
+    assert!(created.time().is_some());
+
+    store.profile.set_meta_resource("Credits", 0.0);
+    store.characters[0].inventory.delta.clear();
+    store.save().unwrap();
+    assert_ne!(before, store.files().iter().map(|f| fs::read(f).unwrap()).collect::<Vec<Vec<u8>>>());
+
+    assert_eq!(backup::list_backups(&save.dir).unwrap()[0], created);
+    backup::restore_backup(&save.dir, &created).unwrap();
+    assert_eq!(before, store.files().iter().map(|f| fs::read(f).unwrap()).collect::<Vec<Vec<u8>>>());
+    assert!(!save.path("Profile.json.tmp").exists());
+}
+
+#[test]
+fn save_only_writes_the_files_given() {
+    let save = TestSave::new("save_only_writes_the_files_given");
+    let mut store = CharacterStore::load(&save.dir).unwrap();
+    let characters = fs::read(save.path("Characters.json")).unwrap();
+    let inventory = fs::read(&store.characters[0].inventory_path).unwrap();
+
+    store.profile.set_meta_resource("Credits", 0.0);
+    store.characters[0].character_name = "Renamed".to_string();
+    store.characters[0].inventory.delta.clear();
+    store.save_only(&[store.profile_file.clone()]).unwrap();
+
+    assert_eq!(save.read_json("Profile.json")["MetaResources"][0]["Count"], 0.0);
+    assert_eq!(characters, fs::read(save.path("Characters.json")).unwrap());
+    assert_eq!(inventory, fs::read(&store.characters[0].inventory_path).unwrap());
+}